@@ -2,6 +2,184 @@ use std::fs;
 use std::io::Write;
 use std::path::Path;
 
+/// Comandos registrados en `main.rs`. El ACL de la app valida cada invoke
+/// contra esta lista: un comando que falte aquí se rechaza al ejecutarse.
+const APP_COMMANDS: &[&str] = &[
+    "authenticate_user",
+    "debug_database",
+    "get_machine_id",
+    "get_license_type",
+    "update_license_validation",
+    "check_offline_license",
+    "get_active_usernames",
+    // Settings - Users
+    "check_username_available",
+    "create_user",
+    "get_all_roles",
+    "save_avatar",
+    "get_users_list",
+    "update_user",
+    "delete_users",
+    "update_own_profile",
+    "change_own_password",
+    // Settings - Permissions
+    "get_all_permissions",
+    "get_role_permissions",
+    "update_role_permissions",
+    "reset_permissions_to_default",
+    // Settings - Hardware
+    "save_settings",
+    "load_settings",
+    "get_system_printers",
+    "test_printer_connection",
+    "test_cash_drawer",
+    "open_cash_drawer",
+    "get_receipt_templates",
+    "save_receipt_template",
+    "reset_receipt_template",
+    "preview_receipt_template",
+    "test_print_ticket",
+    "print_sale_ticket",
+    "render_sale_receipt",
+    "print_return_voucher",
+    "print_shift_ticket",
+    "print_payment_receipt",
+    "print_x_report",
+    "print_z_report",
+    // Print queue
+    "get_print_jobs",
+    "retry_print_job",
+    "retry_failed_print_jobs",
+    "discard_print_job",
+    "get_printer_status",
+    "update_customer_display",
+    "open_customer_display",
+    "get_customer_display_state",
+    "test_customer_display",
+    // Inventory - Products
+    "get_products",
+    "get_all_filtered_products",
+    "get_product_by_id",
+    "create_product",
+    "save_product_image",
+    "update_product",
+    "bulk_update_products",
+    "delete_products",
+    "check_product_dependencies",
+    "generate_internal_barcode",
+    "backfill_internal_barcodes",
+    // Inventory - Categories
+    "get_categories",
+    "get_all_categories",
+    "create_category",
+    "update_category",
+    "delete_categories",
+    // Inventory - Tags
+    "get_all_tags",
+    // Inventory - Kits
+    "get_kits",
+    "get_kit_details",
+    "check_products_in_active_kits",
+    "create_kit",
+    "get_kit_for_product",
+    "get_all_kits",
+    "update_kit",
+    "delete_kits",
+    // Inventory - Promotions
+    "get_promotions",
+    "get_promotion_details",
+    "get_all_active_promotions",
+    "create_promotion",
+    "update_promotion",
+    "delete_promotions",
+    // Inventory - Movements
+    "get_inventory_movements",
+    "create_inventory_movement",
+    "process_bulk_reception",
+    // Inventory - Labels
+    "get_label_config",
+    "save_label_config",
+    "print_product_labels",
+    "get_price_changed_products",
+    "print_price_change_labels",
+    // Cash Register
+    "get_active_shift",
+    "open_shift",
+    "close_shift",
+    "register_cash_movement",
+    "get_shift_details",
+    "get_closed_shifts",
+    "get_shifts_history",
+    "get_x_report",
+    "create_z_report",
+    "get_z_reports",
+    "get_z_report",
+    "process_sale",
+    "validate_voucher",
+    "charge_card_terminal",
+    "void_terminal_charge",
+    // Settings - Business
+    "get_business_settings",
+    "update_business_settings",
+    "save_logo_image",
+    // Settings - Currencies
+    "get_currencies",
+    "save_currency",
+    "set_exchange_rate",
+    // Customers
+    "get_customers",
+    "upsert_customer",
+    "restore_customer",
+    "delete_customers",
+    "get_customer_account_statement",
+    "register_debt_payment",
+    "get_payment_details",
+    // Sales - History
+    "get_sales_history",
+    "get_sale_details",
+    // Sales - Returns
+    "process_return",
+    "find_return_candidates",
+    "get_no_receipt_return_price",
+    "get_no_receipt_limits",
+    "process_no_receipt_return",
+    // Sales - Exchanges
+    "process_exchange",
+    // Sales - Cancel
+    "cancel_sale",
+    // Reports
+    "get_sales_report",
+    "get_top_selling_products",
+    "get_dead_stock_report",
+    "get_inventory_valuation",
+    "get_low_stock_products",
+    "get_sales_heatmap",
+    "get_cashier_performance",
+    "get_discount_analysis",
+    "get_reorder_suggestions",
+    // Exports
+    "export_sales_report",
+    "export_top_selling_products",
+    "export_dead_stock_report",
+    "export_inventory_valuation",
+    "export_low_stock_products",
+    "export_reorder_list",
+    "export_sales_history",
+    "export_kardex",
+    // Backup
+    "backup_database",
+    "restore_latest_backup",
+    "sync_pending_backups",
+    "get_pending_backups_count",
+    // Report delivery
+    "get_report_delivery_config",
+    "save_report_delivery_config",
+    "test_report_delivery",
+    "send_daily_report_now",
+    "get_report_deliveries",
+    "retry_report_delivery",
+];
+
 fn main() {
     // ── Leer variables de entorno desde .env.local (o .env) ──
     let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
@@ -73,5 +251,10 @@ fn main() {
         );
     }
 
-    tauri_build::build()
+    // ── Permisos de comandos por ventana (capabilities/) ──
+    tauri_build::try_build(
+        tauri_build::Attributes::new()
+            .app_manifest(tauri_build::AppManifest::new().commands(APP_COMMANDS)),
+    )
+    .expect("No se pudo generar el contexto de Tauri")
 }
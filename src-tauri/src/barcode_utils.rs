@@ -0,0 +1,388 @@
+// Barcode encoders for raster printing and code validation.
+//
+// Encoders return one entry per module (1 = bar, 0 = space), quiet zones
+// included. Supported: EAN-13, EAN-8, UPC-A and CODE128 sets B and C.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symbology {
+    Ean13,
+    Ean8,
+    UpcA,
+    Code128B,
+    Code128C,
+}
+
+/// CODE128 symbols: each = 6 bars (3 black + 3 white) = 11 modules.
+/// 103-105 are Start A/B/C, 106 is the stop (plus a final 2-module bar).
+const CODE128_PATTERNS: [[u8; 6]; 107] = [
+    [2, 1, 2, 2, 2, 2],
+    [2, 2, 2, 1, 2, 2],
+    [2, 2, 2, 2, 2, 1],
+    [1, 2, 1, 2, 2, 3],
+    [1, 2, 1, 3, 2, 2], // 0-4
+    [1, 3, 1, 2, 2, 2],
+    [1, 2, 2, 2, 1, 3],
+    [1, 2, 2, 3, 1, 2],
+    [1, 3, 2, 2, 1, 2],
+    [2, 2, 1, 2, 1, 3], // 5-9
+    [2, 2, 1, 3, 1, 2],
+    [2, 3, 1, 2, 1, 2],
+    [1, 1, 2, 2, 3, 2],
+    [1, 2, 2, 1, 3, 2],
+    [1, 2, 2, 2, 3, 1], // 10-14
+    [1, 1, 3, 2, 2, 2],
+    [1, 2, 3, 1, 2, 2],
+    [1, 2, 3, 2, 2, 1],
+    [2, 2, 3, 2, 1, 1],
+    [2, 2, 1, 1, 3, 2], // 15-19
+    [2, 2, 1, 2, 3, 1],
+    [2, 1, 3, 2, 1, 2],
+    [2, 2, 3, 1, 1, 2],
+    [3, 1, 2, 1, 3, 1],
+    [3, 1, 1, 2, 2, 2], // 20-24
+    [3, 2, 1, 1, 2, 2],
+    [3, 2, 1, 2, 2, 1],
+    [3, 1, 2, 2, 1, 2],
+    [3, 2, 2, 1, 1, 2],
+    [3, 2, 2, 2, 1, 1], // 25-29
+    [2, 1, 2, 1, 2, 3],
+    [2, 1, 2, 3, 2, 1],
+    [2, 3, 2, 1, 2, 1],
+    [1, 1, 1, 3, 2, 3],
+    [1, 3, 1, 1, 2, 3], // 30-34
+    [1, 3, 1, 3, 2, 1],
+    [1, 1, 2, 3, 1, 3],
+    [1, 3, 2, 1, 1, 3],
+    [1, 3, 2, 3, 1, 1],
+    [2, 1, 1, 3, 1, 3], // 35-39
+    [2, 3, 1, 1, 1, 3],
+    [2, 3, 1, 3, 1, 1],
+    [1, 1, 2, 1, 3, 3],
+    [1, 1, 2, 3, 3, 1],
+    [1, 3, 2, 1, 3, 1], // 40-44
+    [1, 1, 3, 1, 2, 3],
+    [1, 1, 3, 3, 2, 1],
+    [1, 3, 3, 1, 2, 1],
+    [3, 1, 3, 1, 2, 1],
+    [2, 1, 1, 3, 3, 1], // 45-49
+    [2, 3, 1, 1, 3, 1],
+    [2, 1, 3, 1, 1, 3],
+    [2, 1, 3, 3, 1, 1],
+    [2, 1, 3, 1, 3, 1],
+    [3, 1, 1, 1, 2, 3], // 50-54
+    [3, 1, 1, 3, 2, 1],
+    [3, 3, 1, 1, 2, 1],
+    [3, 1, 2, 1, 1, 3],
+    [3, 1, 2, 3, 1, 1],
+    [3, 3, 2, 1, 1, 1], // 55-59
+    [3, 1, 4, 1, 1, 1],
+    [2, 2, 1, 4, 1, 1],
+    [4, 3, 1, 1, 1, 1],
+    [1, 1, 1, 2, 2, 4],
+    [1, 1, 1, 4, 2, 2], // 60-64
+    [1, 2, 1, 1, 2, 4],
+    [1, 2, 1, 4, 2, 1],
+    [1, 4, 1, 1, 2, 2],
+    [1, 4, 1, 2, 2, 1],
+    [1, 1, 2, 2, 1, 4], // 65-69
+    [1, 1, 2, 4, 1, 2],
+    [1, 2, 2, 1, 1, 4],
+    [1, 2, 2, 4, 1, 1],
+    [1, 4, 2, 1, 1, 2],
+    [1, 4, 2, 2, 1, 1], // 70-74
+    [2, 4, 1, 2, 1, 1],
+    [2, 2, 1, 1, 1, 4],
+    [4, 1, 3, 1, 1, 1],
+    [2, 4, 1, 1, 1, 2],
+    [1, 3, 4, 1, 1, 1], // 75-79
+    [1, 1, 1, 2, 4, 2],
+    [1, 2, 1, 1, 4, 2],
+    [1, 2, 1, 2, 4, 1],
+    [1, 1, 4, 2, 1, 2],
+    [1, 2, 4, 1, 1, 2], // 80-84
+    [1, 2, 4, 2, 1, 1],
+    [4, 1, 1, 2, 1, 2],
+    [4, 2, 1, 1, 1, 2],
+    [4, 2, 1, 2, 1, 1],
+    [2, 1, 2, 1, 4, 1], // 85-89
+    [2, 1, 4, 1, 2, 1],
+    [4, 1, 2, 1, 2, 1],
+    [1, 1, 1, 1, 4, 3],
+    [1, 1, 1, 3, 4, 1],
+    [1, 3, 1, 1, 4, 1], // 90-94
+    [1, 1, 4, 1, 1, 3],
+    [1, 1, 4, 3, 1, 1],
+    [4, 1, 1, 1, 1, 3],
+    [4, 1, 1, 3, 1, 1],
+    [1, 1, 3, 1, 4, 1], // 95-99
+    [1, 1, 4, 1, 3, 1],
+    [3, 1, 1, 1, 4, 1],
+    [4, 1, 1, 1, 3, 1],
+    [2, 1, 1, 4, 1, 2],
+    [2, 1, 1, 2, 1, 4], // 100-104
+    [2, 1, 1, 2, 3, 2],
+    [2, 3, 3, 1, 1, 1], // 105-106
+];
+
+const CODE128_STOP: [u8; 7] = [2, 3, 3, 1, 1, 1, 2];
+const CODE128_START_B: u32 = 104;
+const CODE128_START_C: u32 = 105;
+
+/// EAN/UPC set A (odd parity) digit patterns, 7 modules each.
+const EAN_L: [u8; 10] = [
+    0b0001101, 0b0011001, 0b0010011, 0b0111101, 0b0100011,
+    0b0110001, 0b0101111, 0b0111011, 0b0110111, 0b0001011,
+];
+/// Parity of the six left digits of EAN-13 by its first digit (1 = set B).
+const EAN13_PARITY: [u8; 10] = [
+    0b000000, 0b001011, 0b001101, 0b001110, 0b010011,
+    0b011001, 0b011100, 0b010101, 0b010110, 0b011010,
+];
+
+/// GS1 mod-10 check digit for `data` (the digits without the check digit).
+pub fn gs1_check_digit(data: &str) -> Result<u8, String> {
+    let mut sum = 0u32;
+    for (i, ch) in data.chars().rev().enumerate() {
+        let digit = ch
+            .to_digit(10)
+            .ok_or_else(|| format!("El código '{}' solo puede contener dígitos", data))?;
+        sum += if i % 2 == 0 { digit * 3 } else { digit };
+    }
+    Ok(((10 - sum % 10) % 10) as u8)
+}
+
+/// Returns `code` with its check digit: appends it when the code has
+/// `length - 1` digits, validates it when it has `length`.
+fn with_check_digit(code: &str, length: usize, name: &str) -> Result<String, String> {
+    let code = code.trim();
+    if !code.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("{} solo admite dígitos: '{}'", name, code));
+    }
+    if code.len() == length - 1 {
+        let check = gs1_check_digit(code)?;
+        return Ok(format!("{}{}", code, check));
+    }
+    if code.len() != length {
+        return Err(format!(
+            "{} requiere {} dígitos ({} sin dígito verificador): '{}'",
+            name,
+            length,
+            length - 1,
+            code
+        ));
+    }
+
+    let expected = gs1_check_digit(&code[..length - 1])?;
+    if code.as_bytes()[length - 1] - b'0' != expected {
+        return Err(format!(
+            "Dígito verificador inválido en '{}': debería ser {}",
+            code, expected
+        ));
+    }
+    Ok(code.to_string())
+}
+
+fn push_pattern(bars: &mut Vec<u8>, pattern: u8, set_b: bool, set_c: bool) {
+    // Set C is the complement of set A; set B is set C reversed
+    let pattern = if set_b || set_c { !pattern & 0x7F } else { pattern };
+    for bit in 0..7 {
+        let shift = if set_b { bit } else { 6 - bit };
+        bars.push((pattern >> shift) & 1);
+    }
+}
+
+fn push_modules(bars: &mut Vec<u8>, modules: &[u8]) {
+    bars.extend_from_slice(modules);
+}
+
+const EAN_GUARD: [u8; 3] = [1, 0, 1];
+const EAN_CENTER: [u8; 5] = [0, 1, 0, 1, 0];
+
+fn digits(code: &str) -> Vec<u8> {
+    code.bytes().map(|b| b - b'0').collect()
+}
+
+/// EAN-13 from 12 digits (check digit added) or 13 (check digit validated).
+pub fn encode_ean13(code: &str) -> Result<Vec<u8>, String> {
+    let code = with_check_digit(code, 13, "EAN-13")?;
+    let d = digits(&code);
+    let parity = EAN13_PARITY[d[0] as usize];
+
+    let mut bars = vec![0; 11];
+    push_modules(&mut bars, &EAN_GUARD);
+    for (i, &digit) in d[1..7].iter().enumerate() {
+        let set_b = parity & (1 << (5 - i)) != 0;
+        push_pattern(&mut bars, EAN_L[digit as usize], set_b, false);
+    }
+    push_modules(&mut bars, &EAN_CENTER);
+    for &digit in &d[7..13] {
+        push_pattern(&mut bars, EAN_L[digit as usize], false, true);
+    }
+    push_modules(&mut bars, &EAN_GUARD);
+    bars.extend(std::iter::repeat(0).take(7));
+    Ok(bars)
+}
+
+/// EAN-8 from 7 digits (check digit added) or 8 (check digit validated).
+pub fn encode_ean8(code: &str) -> Result<Vec<u8>, String> {
+    let code = with_check_digit(code, 8, "EAN-8")?;
+    let d = digits(&code);
+
+    let mut bars = vec![0; 7];
+    push_modules(&mut bars, &EAN_GUARD);
+    for &digit in &d[..4] {
+        push_pattern(&mut bars, EAN_L[digit as usize], false, false);
+    }
+    push_modules(&mut bars, &EAN_CENTER);
+    for &digit in &d[4..] {
+        push_pattern(&mut bars, EAN_L[digit as usize], false, true);
+    }
+    push_modules(&mut bars, &EAN_GUARD);
+    bars.extend(std::iter::repeat(0).take(7));
+    Ok(bars)
+}
+
+/// UPC-A from 11 digits (check digit added) or 12 (check digit validated).
+/// Same bars as the EAN-13 with a leading zero.
+pub fn encode_upca(code: &str) -> Result<Vec<u8>, String> {
+    let code = with_check_digit(code, 12, "UPC-A")?;
+    encode_ean13(&format!("0{}", code))
+}
+
+fn code128_bars(start: u32, values: &[u32]) -> Vec<u8> {
+    let mut checksum = start;
+    for (i, &val) in values.iter().enumerate() {
+        checksum += val * (i as u32 + 1);
+    }
+    checksum %= 103;
+
+    let mut bars: Vec<u8> = vec![0; 10];
+    let mut push = |pattern: &[u8]| {
+        let mut is_bar = true;
+        for &width in pattern {
+            for _ in 0..width {
+                bars.push(if is_bar { 1 } else { 0 });
+            }
+            is_bar = !is_bar;
+        }
+    };
+
+    push(&CODE128_PATTERNS[start as usize]);
+    for &val in values {
+        push(&CODE128_PATTERNS[val as usize]);
+    }
+    push(&CODE128_PATTERNS[checksum as usize]);
+    push(&CODE128_STOP);
+
+    bars.extend(std::iter::repeat(0).take(10));
+    bars
+}
+
+/// CODE128 Code Set B encoder — supports ASCII 32-127 (letters, digits, symbols)
+pub fn encode_code128b(data: &str) -> Result<Vec<u8>, String> {
+    let mut values: Vec<u32> = Vec::new();
+    for ch in data.chars() {
+        let ascii = ch as u32;
+        if !(32..=127).contains(&ascii) {
+            return Err(format!("Carácter no soportado en CODE128B: '{}'", ch));
+        }
+        values.push(ascii - 32);
+    }
+    Ok(code128_bars(CODE128_START_B, &values))
+}
+
+/// CODE128 Code Set C: digit pairs in one symbol each, for an even number of digits.
+pub fn encode_code128c(data: &str) -> Result<Vec<u8>, String> {
+    if data.is_empty() || data.len() % 2 != 0 || !data.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!(
+            "CODE128C requiere una cantidad par de dígitos: '{}'",
+            data
+        ));
+    }
+    let values: Vec<u32> = data
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| ((pair[0] - b'0') * 10 + (pair[1] - b'0')) as u32)
+        .collect();
+    Ok(code128_bars(CODE128_START_C, &values))
+}
+
+/// Symbology a code is printed with: GTINs with a valid check digit as
+/// EAN/UPC, other even-length numbers as CODE128C, anything else CODE128B.
+pub fn detect_symbology(code: &str) -> Symbology {
+    let is_numeric = !code.is_empty() && code.chars().all(|c| c.is_ascii_digit());
+    if is_numeric {
+        let symbology = match code.len() {
+            13 => Some(Symbology::Ean13),
+            12 => Some(Symbology::UpcA),
+            8 => Some(Symbology::Ean8),
+            _ => None,
+        };
+        if let Some(symbology) = symbology {
+            if with_check_digit(code, code.len(), "").is_ok() {
+                return symbology;
+            }
+        }
+        if code.len() % 2 == 0 {
+            return Symbology::Code128C;
+        }
+    }
+    Symbology::Code128B
+}
+
+pub fn encode(code: &str, symbology: Symbology) -> Result<Vec<u8>, String> {
+    match symbology {
+        Symbology::Ean13 => encode_ean13(code),
+        Symbology::Ean8 => encode_ean8(code),
+        Symbology::UpcA => encode_upca(code),
+        Symbology::Code128B => encode_code128b(code),
+        Symbology::Code128C => encode_code128c(code),
+    }
+}
+
+/// Checks a product barcode before saving it. Numeric codes with the length
+/// of an EAN-8, UPC-A or EAN-13 must carry a valid check digit; anything
+/// else must be printable as CODE128.
+pub fn validate_product_barcode(barcode: &str) -> Result<(), String> {
+    let barcode = barcode.trim();
+    if barcode.chars().all(|c| c.is_ascii_digit()) {
+        let name = match barcode.len() {
+            8 => Some("EAN-8"),
+            12 => Some("UPC-A"),
+            13 => Some("EAN-13"),
+            _ => None,
+        };
+        if let Some(name) = name {
+            return with_check_digit(barcode, barcode.len(), name).map(|_| ());
+        }
+    }
+    encode_code128b(barcode).map(|_| ())
+}
+
+/// Checks a prefix for in-store codes: 2 or 3 digits in the GS1
+/// restricted-circulation range (20-29), which no manufacturer uses.
+pub fn validate_internal_prefix(prefix: &str) -> Result<(), String> {
+    let valid = (2..=3).contains(&prefix.len())
+        && prefix.starts_with('2')
+        && prefix.chars().all(|c| c.is_ascii_digit());
+    if !valid {
+        return Err(format!(
+            "El prefijo de códigos internos debe tener 2 o 3 dígitos entre 20 y 29: '{}'",
+            prefix
+        ));
+    }
+    Ok(())
+}
+
+/// In-store EAN-13: prefix, zero-padded sequence and check digit.
+pub fn internal_ean13(prefix: &str, sequence: u64) -> Result<String, String> {
+    let width = 12 - prefix.len();
+    let data = format!("{}{:0width$}", prefix, sequence, width = width);
+    if data.len() != 12 {
+        return Err(format!(
+            "Se agotó el rango de códigos internos con el prefijo {}",
+            prefix
+        ));
+    }
+    Ok(format!("{}{}", data, gs1_check_digit(&data)?))
+}
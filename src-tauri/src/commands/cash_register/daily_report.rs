@@ -0,0 +1,465 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::State;
+use uuid::Uuid;
+
+use super::payments::payment_method_label;
+use super::shifts::ForeignCashTotal;
+use crate::commands::settings::business::fetch_business_settings;
+use crate::commands::settings::permissions::require_permission;
+
+/// Shifts opened on the business date (`?1`, YYYY-MM-DD).
+const DAY_SHIFTS_SQL: &str =
+    "SELECT id FROM cash_register_shifts WHERE date(opening_date) = ?1";
+
+/// Sales that count for their shift: cancellations booked in the same shift drop out,
+/// later cancellations show up as reversals.
+const COUNTED_SALE_SQL: &str =
+    "(NOT s.status = 'cancelled' OR s.cancelled_shift_id != s.cash_register_shift_id)";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DailyPaymentTotal {
+    pub method: String,
+    pub label: String,
+    pub sales_count: i64,
+    pub amount: f64,
+}
+
+/// X/Z report for a business date, aggregated over every shift opened that day.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DailyReport {
+    pub report_type: String, // 'X' or 'Z'
+    /// Consecutive number, Z reports only.
+    pub report_number: Option<i64>,
+    pub z_report_id: Option<String>,
+    pub business_date: String,
+    pub generated_at: String,
+    pub generated_by: String,
+    pub shift_codes: Vec<String>,
+    pub open_shifts: i64,
+    pub sales_count: i64,
+    pub gross_sales: f64,
+    pub total_discounts: f64,
+    pub total_sales: f64,
+    pub tax_rate: f64,
+    pub tax_base: f64,
+    pub tax_amount: f64,
+    pub payments: Vec<DailyPaymentTotal>,
+    pub foreign_cash: Vec<ForeignCashTotal>,
+    pub cancellations_count: i64,
+    pub cancellations_total: f64,
+    pub reversals_count: i64,
+    pub reversals_total: f64,
+    pub returns_count: i64,
+    pub returns_total: f64,
+    pub cash_refunds: f64,
+    pub card_refunds: f64,
+    pub debt_payments_total: f64,
+    pub movements_in: f64,
+    pub movements_out: f64,
+    pub first_folio: Option<String>,
+    pub last_folio: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ZReportSummary {
+    pub id: String,
+    pub report_number: i64,
+    pub business_date: String,
+    pub first_folio: Option<String>,
+    pub last_folio: Option<String>,
+    pub total_sales: f64,
+    pub print_count: i64,
+    pub user_name: Option<String>,
+    pub created_at: String,
+}
+
+fn resolve_date(date: Option<String>) -> Result<String, String> {
+    match date.filter(|d| !d.trim().is_empty()) {
+        Some(d) => chrono::NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d")
+            .map(|parsed| parsed.format("%Y-%m-%d").to_string())
+            .map_err(|_| format!("Fecha inválida: {}", d)),
+        None => Ok(chrono::Local::now().format("%Y-%m-%d").to_string()),
+    }
+}
+
+/// Computes the day's figures from transactional data. Nothing is written.
+pub fn build_daily_report(
+    conn: &Connection,
+    business_date: &str,
+    report_type: &str,
+    user_id: &str,
+) -> Result<DailyReport, String> {
+    let generated_by: String = conn
+        .query_row(
+            "SELECT full_name FROM users WHERE id = ?1",
+            [user_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .unwrap_or_else(|| "Desc.".to_string());
+
+    // Shifts
+    let mut stmt = conn
+        .prepare(
+            "SELECT COALESCE(code, CAST(id AS TEXT)), status
+             FROM cash_register_shifts
+             WHERE date(opening_date) = ?1
+             ORDER BY id ASC",
+        )
+        .map_err(|e| e.to_string())?;
+    let shifts = stmt
+        .query_map([business_date], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let open_shifts = shifts.iter().filter(|(_, status)| status == "open").count() as i64;
+    let shift_codes: Vec<String> = shifts.into_iter().map(|(code, _)| code).collect();
+
+    // Sales
+    let (sales_count, gross_sales, total_discounts, total_sales): (i64, f64, f64, f64) = conn
+        .query_row(
+            &format!(
+                "SELECT COUNT(*), COALESCE(SUM(s.subtotal), 0.0),
+                        COALESCE(SUM(s.discount_amount), 0.0), COALESCE(SUM(s.total), 0.0)
+                 FROM sales s
+                 WHERE s.cash_register_shift_id IN ({}) AND {}",
+                DAY_SHIFTS_SQL, COUNTED_SALE_SQL
+            ),
+            [business_date],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    // Tenders
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT sp.method, COUNT(DISTINCT sp.sale_id), COALESCE(SUM(sp.amount), 0.0)
+             FROM sale_payments sp
+             INNER JOIN sales s ON sp.sale_id = s.id
+             WHERE s.cash_register_shift_id IN ({}) AND {}
+             GROUP BY sp.method
+             ORDER BY SUM(sp.amount) DESC",
+            DAY_SHIFTS_SQL, COUNTED_SALE_SQL
+        ))
+        .map_err(|e| e.to_string())?;
+    let payments = stmt
+        .query_map([business_date], |row| {
+            let method: String = row.get(0)?;
+            Ok(DailyPaymentTotal {
+                label: payment_method_label(&method).to_string(),
+                method,
+                sales_count: row.get(1)?,
+                amount: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT sp.currency_code, COALESCE(SUM(sp.foreign_amount), 0.0), COALESCE(SUM(sp.tendered), 0.0)
+             FROM sale_payments sp
+             INNER JOIN sales s ON sp.sale_id = s.id
+             WHERE s.cash_register_shift_id IN ({}) AND {}
+               AND sp.method = 'cash' AND sp.currency_code IS NOT NULL
+             GROUP BY sp.currency_code
+             ORDER BY sp.currency_code",
+            DAY_SHIFTS_SQL, COUNTED_SALE_SQL
+        ))
+        .map_err(|e| e.to_string())?;
+    let foreign_cash = stmt
+        .query_map([business_date], |row| {
+            Ok(ForeignCashTotal {
+                currency_code: row.get(0)?,
+                amount: row.get(1)?,
+                base_amount: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    // Cancellations within the same shift, and reversals of earlier shifts booked today
+    let (cancellations_count, cancellations_total): (i64, f64) = conn
+        .query_row(
+            &format!(
+                "SELECT COUNT(*), COALESCE(SUM(s.total), 0.0)
+                 FROM sales s
+                 WHERE s.cash_register_shift_id IN ({}) AND s.status = 'cancelled'
+                   AND s.cancelled_shift_id = s.cash_register_shift_id",
+                DAY_SHIFTS_SQL
+            ),
+            [business_date],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let (reversals_count, reversals_total): (i64, f64) = conn
+        .query_row(
+            &format!(
+                "SELECT COUNT(*), COALESCE(SUM(total), 0.0)
+                 FROM sale_reversals
+                 WHERE cash_register_shift_id IN ({})",
+                DAY_SHIFTS_SQL
+            ),
+            [business_date],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    // Returns
+    let (returns_count, returns_total, cash_refunds, card_refunds): (i64, f64, f64, f64) = conn
+        .query_row(
+            &format!(
+                "SELECT COUNT(*), COALESCE(SUM(total), 0.0),
+                    COALESCE(SUM(CASE WHEN refund_method = 'cash' THEN total - exchange_amount ELSE 0 END), 0.0),
+                    COALESCE(SUM(CASE WHEN refund_method = 'card' THEN total - exchange_amount ELSE 0 END), 0.0)
+                 FROM returns
+                 WHERE cash_register_shift_id IN ({})",
+                DAY_SHIFTS_SQL
+            ),
+            [business_date],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    // Debt payments and cash movements
+    let debt_payments_total: f64 = conn
+        .query_row(
+            &format!(
+                "SELECT COALESCE(SUM(amount), 0.0) FROM debt_payments
+                 WHERE cash_register_shift_id IN ({})",
+                DAY_SHIFTS_SQL
+            ),
+            [business_date],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let (movements_in, movements_out): (f64, f64) = conn
+        .query_row(
+            &format!(
+                "SELECT
+                    COALESCE(SUM(CASE WHEN type = 'IN' THEN amount ELSE 0 END), 0.0),
+                    COALESCE(SUM(CASE WHEN type = 'OUT' THEN amount ELSE 0 END), 0.0)
+                 FROM cash_movements WHERE cash_register_shift_id IN ({})",
+                DAY_SHIFTS_SQL
+            ),
+            [business_date],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    // Folio range, cancelled sales included
+    let folio_at = |order: &str| -> Result<Option<String>, String> {
+        conn.query_row(
+            &format!(
+                "SELECT folio FROM sales WHERE cash_register_shift_id IN ({})
+                 ORDER BY rowid {} LIMIT 1",
+                DAY_SHIFTS_SQL, order
+            ),
+            [business_date],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())
+    };
+    let first_folio = folio_at("ASC")?;
+    let last_folio = folio_at("DESC")?;
+
+    // Prices include tax
+    let settings = fetch_business_settings(conn).unwrap_or_default();
+    let tax_rate = if settings.apply_tax { settings.tax_rate } else { 0.0 };
+    let tax_base = total_sales / (1.0 + tax_rate / 100.0);
+    let tax_amount = total_sales - tax_base;
+
+    Ok(DailyReport {
+        report_type: report_type.to_string(),
+        report_number: None,
+        z_report_id: None,
+        business_date: business_date.to_string(),
+        generated_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        generated_by,
+        shift_codes,
+        open_shifts,
+        sales_count,
+        gross_sales,
+        total_discounts,
+        total_sales,
+        tax_rate,
+        tax_base,
+        tax_amount,
+        payments,
+        foreign_cash,
+        cancellations_count,
+        cancellations_total,
+        reversals_count,
+        reversals_total,
+        returns_count,
+        returns_total,
+        cash_refunds,
+        card_refunds,
+        debt_payments_total,
+        movements_in,
+        movements_out,
+        first_folio,
+        last_folio,
+    })
+}
+
+/// Loads a stored Z report exactly as it was issued.
+pub fn fetch_z_report(conn: &Connection, z_report_id: &str) -> Result<DailyReport, String> {
+    let data: String = conn
+        .query_row(
+            "SELECT data FROM z_reports WHERE id = ?1",
+            [z_report_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or("Reporte Z no encontrado.".to_string())?;
+
+    serde_json::from_str(&data).map_err(|e| format!("Reporte Z dañado: {}", e))
+}
+
+// ── Commands ────────────────────────────────────────────────────
+
+/// X report: running totals for the day. Can be generated any number of times.
+#[tauri::command]
+pub fn get_x_report(
+    db: State<Mutex<Connection>>,
+    date: Option<String>,
+    user_id: String,
+) -> Result<DailyReport, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    require_permission(&conn, &user_id, "reports:view")?;
+
+    let business_date = resolve_date(date)?;
+    build_daily_report(&conn, &business_date, "X", &user_id)
+}
+
+/// Z report: closes the business date with the next consecutive number.
+/// Each date can only be closed once; the stored snapshot is never recalculated.
+#[tauri::command]
+pub fn create_z_report(
+    db: State<Mutex<Connection>>,
+    date: Option<String>,
+    user_id: String,
+) -> Result<DailyReport, String> {
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    require_permission(&conn, &user_id, "cash_register:z_report")?;
+
+    let business_date = resolve_date(date)?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let existing: Option<i64> = tx
+        .query_row(
+            "SELECT report_number FROM z_reports WHERE business_date = ?1",
+            [&business_date],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    if let Some(number) = existing {
+        return Err(format!(
+            "El día {} ya fue cerrado con el reporte Z #{}. Solo puede reimprimirse.",
+            business_date, number
+        ));
+    }
+
+    let mut report = build_daily_report(&tx, &business_date, "Z", &user_id)?;
+    if report.shift_codes.is_empty() {
+        return Err(format!("No hay turnos registrados el {}.", business_date));
+    }
+    if report.open_shifts > 0 {
+        return Err("Cierre todos los turnos del día antes de emitir el reporte Z.".to_string());
+    }
+
+    let report_number: i64 = tx
+        .query_row(
+            "SELECT COALESCE(MAX(report_number), 0) + 1 FROM z_reports",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    let id = Uuid::new_v4().to_string();
+    report.report_number = Some(report_number);
+    report.z_report_id = Some(id.clone());
+
+    let data = serde_json::to_string(&report).map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO z_reports (
+            id, report_number, business_date, first_folio, last_folio,
+            total_sales, data, user_id, created_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            id,
+            report_number,
+            business_date,
+            report.first_folio,
+            report.last_folio,
+            report.total_sales,
+            data,
+            user_id,
+            report.generated_at
+        ],
+    )
+    .map_err(|e| format!("Error guardando reporte Z: {}", e))?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(report)
+}
+
+#[tauri::command]
+pub fn get_z_reports(
+    db: State<Mutex<Connection>>,
+    user_id: String,
+) -> Result<Vec<ZReportSummary>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    require_permission(&conn, &user_id, "reports:view")?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT z.id, z.report_number, z.business_date, z.first_folio, z.last_folio,
+                    z.total_sales, z.print_count, u.full_name, z.created_at
+             FROM z_reports z
+             LEFT JOIN users u ON z.user_id = u.id
+             ORDER BY z.report_number DESC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(ZReportSummary {
+                id: row.get(0)?,
+                report_number: row.get(1)?,
+                business_date: row.get(2)?,
+                first_folio: row.get(3)?,
+                last_folio: row.get(4)?,
+                total_sales: row.get(5)?,
+                print_count: row.get(6)?,
+                user_name: row.get(7)?,
+                created_at: row.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_z_report(
+    db: State<Mutex<Connection>>,
+    z_report_id: String,
+    user_id: String,
+) -> Result<DailyReport, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    require_permission(&conn, &user_id, "reports:view")?;
+    fetch_z_report(&conn, &z_report_id)
+}
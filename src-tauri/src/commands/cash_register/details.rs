@@ -6,7 +6,8 @@ use tauri::State;
 
 use crate::database::DynamicQuery;
 use super::shifts::{
-    calculate_shift_totals, shift_from_row, SHIFT_SELECT_SQL,
+    calculate_shift_totals, fetch_currency_counts, shift_from_row, ForeignCashTotal,
+    ShiftCurrencyCount, SHIFT_SELECT_SQL,
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -32,9 +33,19 @@ pub struct ShiftDetailsDto {
     pub total_card_sales: f64,
     pub total_credit_sales: f64,
     pub total_voucher_sales: f64,
+    pub total_gift_card_sales: f64,
+    pub total_points_sales: f64,
+    pub total_exchange_sales: f64,
     pub total_debt_payments: f64,
     pub debt_payments_cash: f64,
     pub debt_payments_card: f64,
+    pub total_cash_refunds: f64,
+    pub total_card_refunds: f64,
+    pub total_reversals: f64,
+    pub cash_reversals: f64,
+    pub card_reversals: f64,
+    pub foreign_cash: Vec<ForeignCashTotal>,
+    pub currency_counts: Vec<ShiftCurrencyCount>,
     pub total_cash: f64,
 }
 
@@ -96,6 +107,7 @@ pub fn get_shift_details(
         .map_err(|e| e.to_string())?;
 
     let totals = calculate_shift_totals(&conn, shift_id, shift.initial_cash);
+    let currency_counts = fetch_currency_counts(&conn, shift_id)?;
 
     Ok(ShiftDetailsDto {
         shift,
@@ -108,9 +120,19 @@ pub fn get_shift_details(
         total_card_sales: totals.total_card_sales,
         total_credit_sales: totals.total_credit_sales,
         total_voucher_sales: totals.total_voucher_sales,
+        total_gift_card_sales: totals.total_gift_card_sales,
+        total_points_sales: totals.total_points_sales,
+        total_exchange_sales: totals.total_exchange_sales,
         total_debt_payments: totals.total_debt_payments,
         debt_payments_cash: totals.debt_payments_cash,
         debt_payments_card: totals.debt_payments_card,
+        total_cash_refunds: totals.total_cash_refunds,
+        total_card_refunds: totals.total_card_refunds,
+        total_reversals: totals.total_reversals,
+        cash_reversals: totals.cash_reversals,
+        card_reversals: totals.card_reversals,
+        foreign_cash: totals.foreign_cash,
+        currency_counts,
         total_cash: totals.total_cash,
     })
 }
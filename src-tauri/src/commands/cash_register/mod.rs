@@ -1,4 +1,7 @@
+pub mod daily_report;
 pub mod details;
 pub mod movements;
+pub mod payments;
 pub mod sales;
 pub mod shifts;
+pub mod terminal;
@@ -0,0 +1,270 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Tender types accepted in `sale_payments.method`.
+pub const PAYMENT_METHODS: [&str; 8] = [
+    "cash",
+    "debit_card",
+    "credit_card",
+    "transfer",
+    "voucher",
+    "gift_card",
+    "credit",
+    "points",
+];
+
+/// Tenders settled through the card terminal or bank (reported together as "card").
+pub const CARD_METHODS: [&str; 3] = ["debit_card", "credit_card", "transfer"];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SalePaymentRequest {
+    pub method: String,
+    /// Amount handed over. For `cash` it may exceed the amount due (change is returned).
+    /// For `voucher` and `credit`, 0 means "as much as the sale still needs".
+    #[serde(default)]
+    pub amount: f64,
+    /// Authorization or reference number (card, transfer, gift card, points).
+    pub reference: Option<String>,
+    /// Store voucher code; required when `method` is `voucher`.
+    pub voucher_code: Option<String>,
+    /// Foreign currency of a cash tender; `amount` is then in that currency.
+    pub currency_code: Option<String>,
+    /// Approved charge from `charge_card_terminal` backing a card tender.
+    pub terminal_transaction_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SalePaymentView {
+    pub id: String,
+    pub method: String,
+    pub amount: f64,
+    pub tendered: f64,
+    pub reference: Option<String>,
+    pub currency_code: Option<String>,
+    pub foreign_amount: Option<f64>,
+    pub exchange_rate: Option<f64>,
+    pub auth_code: Option<String>,
+    pub card_last4: Option<String>,
+    pub card_brand: Option<String>,
+}
+
+/// A tender after it has been applied to the sale total.
+/// `amount` and `tendered` are always in base currency.
+#[derive(Default)]
+pub(crate) struct AppliedPayment {
+    pub method: String,
+    pub amount: f64,
+    pub tendered: f64,
+    pub reference: Option<String>,
+    pub voucher_id: Option<String>,
+    pub currency_code: Option<String>,
+    pub foreign_amount: Option<f64>,
+    pub exchange_rate: Option<f64>,
+    pub auth_code: Option<String>,
+    pub card_last4: Option<String>,
+    pub card_brand: Option<String>,
+    pub terminal_transaction_id: Option<String>,
+}
+
+/// Sale tenders grouped the way returns and cancellations settle them
+/// (gift cards and points are not refundable to their origin).
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct PaymentSplit {
+    pub cash: f64,
+    pub card: f64,
+    pub credit: f64,
+    pub voucher: f64,
+}
+
+pub fn is_card_method(method: &str) -> bool {
+    CARD_METHODS.contains(&method)
+}
+
+/// Ticket label for a tender.
+pub fn payment_method_label(method: &str) -> &str {
+    match method {
+        "cash" => "EFECTIVO",
+        "debit_card" => "T. DEBITO",
+        "credit_card" => "T. CREDITO",
+        "transfer" => "TRANSFERENCIA",
+        "voucher" => "VALE",
+        "gift_card" => "TARJETA REGALO",
+        "credit" => "CREDITO",
+        "points" => "PUNTOS",
+        _ => method,
+    }
+}
+
+/// Value stored in `sales.payment_method` for a set of tenders
+/// ('cash', 'card', 'credit' or 'mixed'). Vouchers accompany any of them.
+pub(crate) fn summarize_payment_method(payments: &[AppliedPayment]) -> String {
+    let groups: Vec<&str> = payments
+        .iter()
+        .filter(|p| p.method != "voucher")
+        .map(|p| {
+            if is_card_method(&p.method) {
+                "card"
+            } else {
+                p.method.as_str()
+            }
+        })
+        .collect();
+
+    match groups.first() {
+        None => "cash".to_string(),
+        Some(first) if groups.iter().all(|g| g == first) => match *first {
+            "cash" | "card" | "credit" => first.to_string(),
+            _ => "mixed".to_string(),
+        },
+        _ => "mixed".to_string(),
+    }
+}
+
+pub(crate) fn insert_sale_payments(
+    tx: &Connection,
+    sale_id: &str,
+    payments: &[AppliedPayment],
+    now: &str,
+) -> Result<(), String> {
+    for payment in payments {
+        tx.execute(
+            "INSERT INTO sale_payments (
+                id, sale_id, method, amount, tendered, reference, voucher_id,
+                currency_code, foreign_amount, exchange_rate,
+                auth_code, card_last4, card_brand, terminal_transaction_id, created_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+            params![
+                Uuid::new_v4().to_string(),
+                sale_id,
+                payment.method,
+                payment.amount,
+                payment.tendered,
+                payment.reference,
+                payment.voucher_id,
+                payment.currency_code,
+                payment.foreign_amount,
+                payment.exchange_rate,
+                payment.auth_code,
+                payment.card_last4,
+                payment.card_brand,
+                payment.terminal_transaction_id,
+                now
+            ],
+        )
+        .map_err(|e| format!("Error registrando forma de pago: {}", e))?;
+
+        if let Some(terminal_id) = &payment.terminal_transaction_id {
+            tx.execute(
+                "UPDATE terminal_transactions SET sale_id = ?1 WHERE id = ?2",
+                params![sale_id, terminal_id],
+            )
+            .map_err(|e| format!("Error vinculando cobro de terminal: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+pub fn fetch_sale_payments(conn: &Connection, sale_id: &str) -> Result<Vec<SalePaymentView>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, method, amount, tendered, reference, currency_code, foreign_amount, exchange_rate,
+                    auth_code, card_last4, card_brand
+             FROM sale_payments WHERE sale_id = ?1
+             ORDER BY created_at ASC, rowid ASC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([sale_id], |row| {
+            Ok(SalePaymentView {
+                id: row.get(0)?,
+                method: row.get(1)?,
+                amount: row.get(2)?,
+                tendered: row.get(3)?,
+                reference: row.get(4)?,
+                currency_code: row.get(5)?,
+                foreign_amount: row.get(6)?,
+                exchange_rate: row.get(7)?,
+                auth_code: row.get(8)?,
+                card_last4: row.get(9)?,
+                card_brand: row.get(10)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+/// Approval data of a terminal charge, checked before it backs a card tender.
+pub(crate) fn claim_terminal_charge(
+    tx: &Connection,
+    transaction_id: &str,
+    method: &str,
+    amount: f64,
+) -> Result<(String, String, String), String> {
+    let (t_method, t_amount, status, sale_id, auth_code, last4, brand, voided_at): (
+        String,
+        f64,
+        String,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+    ) = tx
+        .query_row(
+            "SELECT method, amount, status, sale_id, auth_code, card_last4, card_brand, voided_at
+             FROM terminal_transactions WHERE id = ?1",
+            [transaction_id],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                    row.get(6)?,
+                    row.get(7)?,
+                ))
+            },
+        )
+        .map_err(|_| "Cobro de terminal no encontrado.".to_string())?;
+
+    if status != "approved" {
+        return Err("El cobro en terminal no fue aprobado.".to_string());
+    }
+    if voided_at.is_some() {
+        return Err("El cobro en terminal fue cancelado.".to_string());
+    }
+    if sale_id.is_some() {
+        return Err("El cobro en terminal ya está asociado a otra venta.".to_string());
+    }
+    if t_method != method || (t_amount - amount).abs() > 0.01 {
+        return Err(format!(
+            "El cobro en terminal (${:.2}) no coincide con el pago capturado (${:.2}).",
+            t_amount, amount
+        ));
+    }
+
+    Ok((
+        auth_code.unwrap_or_default(),
+        last4.unwrap_or_default(),
+        brand.unwrap_or_default(),
+    ))
+}
+
+pub(crate) fn fetch_payment_split(conn: &Connection, sale_id: &str) -> Result<PaymentSplit, String> {
+    let mut split = PaymentSplit::default();
+    for payment in fetch_sale_payments(conn, sale_id)? {
+        match payment.method.as_str() {
+            "cash" => split.cash += payment.amount,
+            "credit" => split.credit += payment.amount,
+            "voucher" => split.voucher += payment.amount,
+            m if is_card_method(m) => split.card += payment.amount,
+            _ => {}
+        }
+    }
+    Ok(split)
+}
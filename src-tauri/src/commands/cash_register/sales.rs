@@ -5,7 +5,14 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
 use tauri::State;
 use uuid::Uuid;
+use crate::commands::cash_register::payments::{
+    claim_terminal_charge, insert_sale_payments, is_card_method, payment_method_label,
+    summarize_payment_method, AppliedPayment, SalePaymentRequest, PAYMENT_METHODS,
+};
+use crate::commands::cash_register::terminal::void_unclaimed_charge;
 use crate::commands::settings::business::get_store_id;
+use crate::commands::settings::currencies::today_exchange_rate;
+use crate::customer_display::DisplayEvent;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SaleItemRequest {
@@ -23,13 +30,54 @@ pub struct SaleRequest {
     pub customer_id: Option<String>,
     pub user_id: String,
     pub cash_register_shift_id: String,
+    #[serde(default)]
     pub payment_method: String, // 'cash', 'card_transfer', 'credit', 'mixed'
+    #[serde(default)]
     pub cash_amount: f64,
+    #[serde(default)]
     pub card_transfer_amount: f64,
     pub notes: Option<String>,
     pub items: Vec<SaleItemRequest>,
     pub should_print: bool,
     pub voucher_code: Option<String>,
+    /// Split tenders. When empty, `payment_method`, `cash_amount`,
+    /// `card_transfer_amount` and `voucher_code` are used instead.
+    #[serde(default)]
+    pub payments: Vec<SalePaymentRequest>,
+}
+
+impl SaleRequest {
+    /// Tenders for this sale, translating the legacy single-method fields when needed.
+    pub(crate) fn tenders(&self) -> Vec<SalePaymentRequest> {
+        if !self.payments.is_empty() {
+            return self.payments.clone();
+        }
+
+        let tender = |method: &str, amount: f64, voucher_code: Option<String>| SalePaymentRequest {
+            method: method.to_string(),
+            amount,
+            reference: None,
+            voucher_code,
+            currency_code: None,
+            terminal_transaction_id: None,
+        };
+
+        let mut tenders = Vec::new();
+        if let Some(code) = &self.voucher_code {
+            tenders.push(tender("voucher", 0.0, Some(code.clone())));
+        }
+        if self.payment_method == "credit" {
+            tenders.push(tender("credit", 0.0, None));
+            return tenders;
+        }
+        if self.card_transfer_amount > 0.0 {
+            tenders.push(tender("debit_card", self.card_transfer_amount, None));
+        }
+        if self.cash_amount > 0.0 {
+            tenders.push(tender("cash", self.cash_amount, None));
+        }
+        tenders
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -99,6 +147,8 @@ pub struct SaleResponse {
     pub total: f64,
     pub change: f64,
     pub voucher_used: f64,
+    /// Printer problem the cashier should know about (paper low, offline...)
+    pub printer_warning: Option<String>,
 }
 
 // Helper struct for inserting items
@@ -594,17 +644,10 @@ fn validate_promotions(
 
 fn validate_credit_sale(
     tx: &Connection,
-    payment_method: &str,
     customer_id: &Option<String>,
     total_amount: f64,
 ) -> Result<Option<String>, String> {
-    let sale_type = if payment_method == "credit" {
-        "credit"
-    } else {
-        "cash"
-    };
-
-    if sale_type == "credit" {
+    if total_amount > 0.0 {
         let cid = customer_id
             .as_ref()
             .ok_or("Se requiere un cliente para ventas a crédito.".to_string())?;
@@ -810,31 +853,38 @@ fn calculate_sale_items<'a>(
     Ok((total_gross, total_item_discounts, final_items))
 }
 
-#[tauri::command]
-pub fn process_sale(
-    app_handle: tauri::AppHandle,
-    db: State<Mutex<Connection>>,
-    payload: SaleRequest,
-) -> Result<SaleResponse, String> {
-    let mut conn = db.lock().map_err(|e| e.to_string())?;
+/// Outcome of a sale recorded inside a caller-owned transaction.
+pub(crate) struct RecordedSale {
+    pub id: String,
+    pub folio: String,
+    pub total: f64,
+    pub total_paid: f64,
+    pub voucher_used: f64,
+    pub exchange_applied: f64,
+}
 
+/// Validates and records a sale (stock, kits, promotions, vouchers, credit) on `tx`.
+/// `exchange_credit` is the value of goods returned in the same exchange; it is applied
+/// before any other tender and never produces change.
+pub(crate) fn record_sale(
+    tx: &Connection,
+    payload: &SaleRequest,
+    exchange_credit: f64,
+) -> Result<RecordedSale, String> {
     if payload.items.is_empty() {
         return Err("No hay items en la venta.".to_string());
     }
 
     // Read max discount from system settings
-    let max_discount_percentage: f64 = {
-        let conn_ref = &*conn;
-        conn_ref
-            .query_row(
-                "SELECT value FROM system_settings WHERE key = 'max_discount_percentage'",
-                [],
-                |row| row.get::<_, String>(0),
-            )
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(20.0)
-    };
+    let max_discount_percentage: f64 = tx
+        .query_row(
+            "SELECT value FROM system_settings WHERE key = 'max_discount_percentage'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(20.0);
 
     // Validate discount percentage
     if payload.discount_percentage < 0.0 {
@@ -847,8 +897,7 @@ pub fn process_sale(
             max_discount_percentage, payload.discount_percentage
         ));
     }
-    let tx = conn.transaction().map_err(|e| e.to_string())?;
-    
+
     let allow_out_of_stock_str: Result<String, _> = tx.query_row(
         "SELECT value FROM system_settings WHERE key = 'allow_out_of_stock_sales'",
         [],
@@ -857,7 +906,7 @@ pub fn process_sale(
 
     let allow_out_of_stock_sales: bool = allow_out_of_stock_str.unwrap_or_else(|_| "false".to_string()) == "true";
     
-    let store_id = get_store_id(&tx)?;
+    let store_id = get_store_id(tx)?;
 
     if !allow_out_of_stock_sales {
         for item in &payload.items {
@@ -883,10 +932,23 @@ pub fn process_sale(
     }
 
     // Validate & Apply Kit Rules
-    let validated_items = apply_kit_rules(&tx, &payload.items)?;
+    let validated_items = apply_kit_rules(tx, &payload.items)?;
+
+    let tenders = payload.tenders();
+    for tender in &tenders {
+        if !PAYMENT_METHODS.contains(&tender.method.as_str()) {
+            return Err(format!("Forma de pago no válida: {}", tender.method));
+        }
+        if tender.amount < 0.0 {
+            return Err("Los montos de pago no pueden ser negativos.".to_string());
+        }
+        if tender.currency_code.is_some() && tender.method != "cash" {
+            return Err("Solo se acepta efectivo en moneda extranjera.".to_string());
+        }
+    }
 
     // Validate Credit Restrictions
-    if payload.payment_method == "credit" {
+    if tenders.iter().any(|t| t.method == "credit") {
         if payload.discount_percentage > 0.0 {
             return Err("Las ventas a crédito no aplican con descuentos globales.".to_string());
         }
@@ -904,15 +966,30 @@ pub fn process_sale(
 
     // Calculate Items & Totals
     let (total_gross, total_item_discounts, final_items) =
-        calculate_sale_items(&tx, &validated_items, payload.discount_percentage)?;
+        calculate_sale_items(tx, &validated_items, payload.discount_percentage)?;
 
     let final_total = total_gross - total_item_discounts;
+    let exchange_applied = exchange_credit.max(0.0).min(final_total);
+    let amount_due = final_total - exchange_applied;
+
+    // PAYMENT PROCESSING
+    // Applied in order: vouchers, card/transfer/gift card/points, cash, credit.
+    // Only cash may exceed what is due (the excess is returned as change).
+    let mut payments: Vec<AppliedPayment> = Vec::new();
+    let mut remaining = amount_due;
+
+    for tender in tenders.iter().filter(|t| t.method == "voucher") {
+        let code = tender
+            .voucher_code
+            .as_ref()
+            .ok_or("Falta el código del vale.".to_string())?;
+        if payments
+            .iter()
+            .any(|p| p.method == "voucher" && p.reference.as_deref() == Some(code.as_str()))
+        {
+            return Err(format!("El vale {} se capturó más de una vez.", code));
+        }
 
-    // VOUCHER PROCESSING
-    let mut voucher_amount_used = 0.0;
-    let mut voucher_id_used: Option<String> = None;
-
-    if let Some(code) = &payload.voucher_code {
         let (v_id, v_balance, v_active, v_expiry): (String, f64, bool, Option<String>) = tx.query_row(
             "SELECT id, current_balance, is_active, expires_at FROM store_vouchers WHERE code = ?",
             [code],
@@ -935,17 +1012,131 @@ pub fn process_sale(
         }
 
         // Calculate amount to use
-        let max_applicable = if v_balance > final_total {
-            final_total
+        let mut applicable = v_balance.min(remaining);
+        if tender.amount > 0.0 {
+            applicable = applicable.min(tender.amount);
+        }
+        if applicable <= 0.0 {
+            continue;
+        }
+        remaining -= applicable;
+        payments.push(AppliedPayment {
+            method: "voucher".to_string(),
+            amount: applicable,
+            tendered: applicable,
+            reference: Some(code.clone()),
+            voucher_id: Some(v_id),
+            ..Default::default()
+        });
+    }
+
+    for tender in tenders
+        .iter()
+        .filter(|t| !matches!(t.method.as_str(), "voucher" | "cash" | "credit"))
+    {
+        if tender.amount <= 0.0 {
+            continue;
+        }
+        if tender.amount > remaining + 0.01 {
+            return Err(format!(
+                "El pago con {} (${:.2}) excede el saldo pendiente (${:.2}).",
+                payment_method_label(&tender.method),
+                tender.amount,
+                remaining.max(0.0)
+            ));
+        }
+        let amount = tender.amount.min(remaining);
+        remaining -= amount;
+
+        let mut payment = AppliedPayment {
+            method: tender.method.clone(),
+            amount,
+            tendered: amount,
+            reference: tender.reference.clone(),
+            ..Default::default()
+        };
+        if let Some(terminal_id) = &tender.terminal_transaction_id {
+            if !is_card_method(&tender.method) {
+                return Err("Solo los pagos con tarjeta pueden cobrarse en terminal.".to_string());
+            }
+            let (auth_code, last4, brand) =
+                claim_terminal_charge(tx, terminal_id, &tender.method, tender.amount)?;
+            payment.reference = payment.reference.or_else(|| Some(auth_code.clone()));
+            payment.auth_code = Some(auth_code);
+            payment.card_last4 = Some(last4);
+            payment.card_brand = Some(brand);
+            payment.terminal_transaction_id = Some(terminal_id.clone());
+        }
+        payments.push(payment);
+    }
+
+    // Foreign cash is converted at today's rate; change is always given in base currency
+    for tender in tenders.iter().filter(|t| t.method == "cash") {
+        if tender.amount <= 0.0 {
+            continue;
+        }
+        let (tendered, exchange_rate) = match tender.currency_code.as_deref() {
+            Some(code) => {
+                let rate = today_exchange_rate(tx, code)?;
+                (((tender.amount * rate) * 100.0).round() / 100.0, Some(rate))
+            }
+            None => (tender.amount, None),
+        };
+        let amount = tendered.min(remaining.max(0.0));
+        remaining -= amount;
+        payments.push(AppliedPayment {
+            method: "cash".to_string(),
+            amount,
+            tendered,
+            currency_code: tender.currency_code.clone(),
+            foreign_amount: exchange_rate.map(|_| tender.amount),
+            exchange_rate,
+            ..Default::default()
+        });
+    }
+
+    for tender in tenders.iter().filter(|t| t.method == "credit") {
+        let amount = if tender.amount > 0.0 {
+            tender.amount.min(remaining.max(0.0))
         } else {
-            v_balance
+            remaining.max(0.0)
         };
-        voucher_amount_used = max_applicable;
-        voucher_id_used = Some(v_id);
+        if amount <= 0.0 {
+            continue;
+        }
+        remaining -= amount;
+        payments.push(AppliedPayment {
+            method: "credit".to_string(),
+            amount,
+            tendered: amount,
+            reference: tender.reference.clone(),
+            ..Default::default()
+        });
     }
 
+    let voucher_amount_used: f64 = payments
+        .iter()
+        .filter(|p| p.method == "voucher")
+        .map(|p| p.amount)
+        .sum();
+    let credit_amount: f64 = payments
+        .iter()
+        .filter(|p| p.method == "credit")
+        .map(|p| p.amount)
+        .sum();
+    let card_amount: f64 = payments
+        .iter()
+        .filter(|p| is_card_method(&p.method))
+        .map(|p| p.amount)
+        .sum();
+    let cash_tendered: f64 = payments
+        .iter()
+        .filter(|p| p.method == "cash")
+        .map(|p| p.tendered)
+        .sum();
+
     // Validate Payment
-    let total_paid = payload.cash_amount + payload.card_transfer_amount + voucher_amount_used;
+    let total_paid = payments.iter().map(|p| p.tendered).sum::<f64>() + exchange_applied;
     if total_paid < final_total - 0.01 {
         return Err(format!(
             "Pago insuficiente. Total calculado: ${:.2}, Pagado: ${:.2} (Incluye ${:.2} de vale)",
@@ -954,16 +1145,17 @@ pub fn process_sale(
     }
 
     // Credit Validation
-    let customer_id_opt = validate_credit_sale(
-        &tx,
-        &payload.payment_method,
-        &payload.customer_id,
-        final_total,
-    )?;
+    let customer_id_opt = validate_credit_sale(tx, &payload.customer_id, credit_amount)?;
+
+    let payment_method = if payload.payments.is_empty() {
+        payload.payment_method.clone()
+    } else {
+        summarize_payment_method(&payments)
+    };
 
     // Prepare Data for Insertion
     let sale_id = Uuid::new_v4().to_string();
-    let folio = generate_smart_folio(&tx)?;
+    let folio = generate_smart_folio(tx)?;
 
     let has_discount = total_item_discounts > 0.0;
 
@@ -972,8 +1164,8 @@ pub fn process_sale(
             id, folio, sale_date, subtotal, discount_percentage, discount_amount, total,
             status, user_id, cash_register_shift_id, payment_method,
             cash_amount, card_transfer_amount, notes, has_discount,
-            customer_id, created_at, updated_at
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'completed', ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+            customer_id, exchange_amount, created_at, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'completed', ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
         params![
             sale_id,
             folio,
@@ -984,12 +1176,13 @@ pub fn process_sale(
             final_total,
             payload.user_id,
             payload.cash_register_shift_id,
-            payload.payment_method,
-            payload.cash_amount,
-            payload.card_transfer_amount,
+            payment_method,
+            cash_tendered,
+            card_amount,
             payload.notes,
             has_discount,
             customer_id_opt,
+            exchange_applied,
             now_local,
             now_local
         ],
@@ -1091,42 +1284,102 @@ pub fn process_sale(
         .map_err(|e| format!("Error insertando item {}: {}", data.db_name, e))?;
     }
 
+    insert_sale_payments(tx, &sale_id, &payments, &now_local)?;
+
     // Handle Voucher Deduction & Recording
-    if let Some(v_id) = voucher_id_used {
-        if voucher_amount_used > 0.0 {
-            tx.execute(
-                "UPDATE store_vouchers SET current_balance = current_balance - ?1, updated_at = ?2 WHERE id = ?3",
-                params![voucher_amount_used, now_local, v_id]
-            ).map_err(|e| format!("Error actualizando saldo del vale: {}", e))?;
-
-            tx.execute(
-                "UPDATE store_vouchers SET is_active = 0, used_at = ?1, updated_at = ?2 WHERE id = ?3 AND current_balance <= 0",
-                params![now_local, now_local, v_id]
-            ).map_err(|e| format!("Error marcando vale como redimido: {}", e))?;
-
-            let sv_id = Uuid::new_v4().to_string();
-            tx.execute(
-                "INSERT INTO sale_vouchers (id, sale_id, voucher_id, amount, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
-                params![sv_id, sale_id, v_id, voucher_amount_used, now_local]
-            ).map_err(|e| format!("Error registrando uso de vale: {}", e))?;
-        }
+    for payment in payments.iter().filter(|p| p.method == "voucher") {
+        let Some(v_id) = &payment.voucher_id else {
+            continue;
+        };
+
+        tx.execute(
+            "UPDATE store_vouchers SET current_balance = current_balance - ?1, updated_at = ?2 WHERE id = ?3",
+            params![payment.amount, now_local, v_id]
+        ).map_err(|e| format!("Error actualizando saldo del vale: {}", e))?;
+
+        tx.execute(
+            "UPDATE store_vouchers SET is_active = 0, used_at = ?1, updated_at = ?2 WHERE id = ?3 AND current_balance <= 0",
+            params![now_local, now_local, v_id]
+        ).map_err(|e| format!("Error marcando vale como redimido: {}", e))?;
+
+        let sv_id = Uuid::new_v4().to_string();
+        tx.execute(
+            "INSERT INTO sale_vouchers (id, sale_id, voucher_id, amount, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![sv_id, sale_id, v_id, payment.amount, now_local]
+        ).map_err(|e| format!("Error registrando uso de vale: {}", e))?;
     }
 
-    // Commit
-    tx.commit().map_err(|e| e.to_string())?;
+    Ok(RecordedSale {
+        id: sale_id,
+        folio,
+        total: final_total,
+        total_paid,
+        voucher_used: voucher_amount_used,
+        exchange_applied,
+    })
+}
+
+#[tauri::command]
+pub fn process_sale(
+    app_handle: tauri::AppHandle,
+    db: State<Mutex<Connection>>,
+    payload: SaleRequest,
+) -> Result<SaleResponse, String> {
+    let printer_warning = if payload.should_print {
+        crate::commands::printer_status::check_before_print(&app_handle)?
+    } else {
+        None
+    };
+
+    let result = {
+        let mut conn = db.lock().map_err(|e| e.to_string())?;
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+        record_sale(&tx, &payload, 0.0)
+            .and_then(|sale| tx.commit().map(|_| sale).map_err(|e| e.to_string()))
+    };
+
+    let sale = match result {
+        Ok(sale) => sale,
+        Err(e) => {
+            // Terminal charges approved for this sale were not claimed: void them
+            let charges: Vec<String> = payload
+                .payments
+                .iter()
+                .filter_map(|p| p.terminal_transaction_id.clone())
+                .collect();
+            if !charges.is_empty() {
+                let app_handle_clone = app_handle.clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    for id in charges {
+                        if let Err(e) = void_unclaimed_charge(&app_handle_clone, &id) {
+                            println!("Warning: Failed to void terminal charge {}: {}", id, e);
+                        }
+                    }
+                });
+            }
+            return Err(e);
+        }
+    };
 
     // Calculate change
-    let change = if total_paid > final_total {
-        total_paid - final_total
+    let change = if sale.total_paid > sale.total {
+        sale.total_paid - sale.total
     } else {
         0.0
     };
 
+    crate::commands::customer_display::notify(DisplayEvent::Change {
+        total: sale.total,
+        paid: sale.total_paid,
+        change,
+    });
+
     let app_handle_clone = app_handle.clone();
 
     // PRINTING LOGIC
     if payload.should_print {
-        let sale_id_clone = sale_id.clone();
+        let sale_id_clone = sale.id.clone();
         tauri::async_runtime::spawn_blocking(move || {
             if let Err(_e) =
                 crate::printer_utils::print_sale_from_db(app_handle_clone, sale_id_clone)
@@ -1141,11 +1394,12 @@ pub fn process_sale(
     }
 
     Ok(SaleResponse {
-        id: sale_id,
-        folio,
-        total: final_total,
+        id: sale.id,
+        folio: sale.folio,
+        total: sale.total,
         change,
-        voucher_used: voucher_amount_used,
+        voucher_used: sale.voucher_used,
+        printer_warning,
     })
 }
 
@@ -3,6 +3,7 @@ use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use tauri::State;
+use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ShiftDto {
@@ -58,6 +59,31 @@ pub fn shift_from_row(row: &rusqlite::Row) -> rusqlite::Result<ShiftDto> {
     })
 }
 
+/// Foreign cash received during a shift.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ForeignCashTotal {
+    pub currency_code: String,
+    /// Amount in the foreign currency (what should be in the drawer).
+    pub amount: f64,
+    /// Base-currency value at the rates used on each sale.
+    pub base_amount: f64,
+}
+
+/// Foreign cash expected and counted when the shift was closed.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShiftCurrencyCount {
+    pub currency_code: String,
+    pub expected_amount: f64,
+    pub counted_amount: Option<f64>,
+    pub difference: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CurrencyCountInput {
+    pub currency_code: String,
+    pub counted_amount: f64,
+}
+
 /// Totals computed from transactional data during a shift.
 pub struct ShiftTotals {
     pub total_movements_in: f64,
@@ -68,12 +94,65 @@ pub struct ShiftTotals {
     pub total_card_sales: f64,
     pub total_credit_sales: f64,
     pub total_voucher_sales: f64,
+    pub total_gift_card_sales: f64,
+    pub total_points_sales: f64,
+    pub total_exchange_sales: f64,
     pub total_debt_payments: f64,
     pub debt_payments_cash: f64,
     pub debt_payments_card: f64,
+    pub total_cash_refunds: f64,
+    pub total_card_refunds: f64,
+    pub total_reversals: f64,
+    pub cash_reversals: f64,
+    pub card_reversals: f64,
+    pub foreign_cash: Vec<ForeignCashTotal>,
+    /// Base-currency cash only; foreign cash is tracked in `foreign_cash`.
     pub total_cash: f64,
 }
 
+fn fetch_foreign_cash(conn: &Connection, shift_id: i64) -> rusqlite::Result<Vec<ForeignCashTotal>> {
+    let mut stmt = conn.prepare(
+        "SELECT sp.currency_code, COALESCE(SUM(sp.foreign_amount), 0.0), COALESCE(SUM(sp.tendered), 0.0)
+         FROM sale_payments sp
+         INNER JOIN sales s ON sp.sale_id = s.id
+         WHERE s.cash_register_shift_id = ?1
+           AND (NOT s.status = 'cancelled' OR s.cancelled_shift_id != ?1)
+           AND sp.method = 'cash' AND sp.currency_code IS NOT NULL
+         GROUP BY sp.currency_code
+         ORDER BY sp.currency_code",
+    )?;
+    let rows = stmt.query_map(params![shift_id], |row| {
+        Ok(ForeignCashTotal {
+            currency_code: row.get(0)?,
+            amount: row.get(1)?,
+            base_amount: row.get(2)?,
+        })
+    })?;
+    rows.collect()
+}
+
+pub fn fetch_currency_counts(conn: &Connection, shift_id: i64) -> Result<Vec<ShiftCurrencyCount>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT currency_code, expected_amount, counted_amount, difference
+             FROM shift_currency_counts
+             WHERE cash_register_shift_id = ?1
+             ORDER BY currency_code",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![shift_id], |row| {
+            Ok(ShiftCurrencyCount {
+                currency_code: row.get(0)?,
+                expected_amount: row.get(1)?,
+                counted_amount: row.get(2)?,
+                difference: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
 /// Calculates all shift totals from the database.
 pub fn calculate_shift_totals(conn: &Connection, shift_id: i64, initial_cash: f64) -> ShiftTotals {
     // Movements
@@ -89,41 +168,54 @@ pub fn calculate_shift_totals(conn: &Connection, shift_id: i64, initial_cash: f6
         .unwrap_or((0.0, 0.0));
 
     // Sales
-    let (sales_count, total_sales, total_card_sales): (i64, f64, f64) = conn
+    let (sales_count, total_sales, total_exchange_sales): (i64, f64, f64) = conn
         .query_row(
             "SELECT
                 COUNT(*),
                 COALESCE(SUM(total), 0.0),
-                COALESCE(SUM(card_transfer_amount), 0.0)
+                COALESCE(SUM(exchange_amount), 0.0)
              FROM sales
-             WHERE cash_register_shift_id = ?1 AND NOT status = 'cancelled'",
+             WHERE cash_register_shift_id = ?1
+               AND (NOT status = 'cancelled' OR cancelled_shift_id != ?1)",
             params![shift_id],
             |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
         )
         .unwrap_or((0, 0.0, 0.0));
 
-    // Credit sales
-    let total_credit_sales: f64 = conn
-        .query_row(
-            "SELECT COALESCE(SUM(total), 0.0)
-             FROM sales
-             WHERE cash_register_shift_id = ?1 AND payment_method = 'credit' AND NOT status = 'cancelled'",
-            params![shift_id],
-            |row| row.get(0),
-        )
-        .unwrap_or(0.0);
-
-    // Voucher amounts
-    let total_voucher_sales: f64 = conn
+    // Tenders
+    let (
+        total_cash_sales,
+        total_card_sales,
+        total_credit_sales,
+        total_voucher_sales,
+        total_gift_card_sales,
+        total_points_sales,
+    ): (f64, f64, f64, f64, f64, f64) = conn
         .query_row(
-            "SELECT COALESCE(SUM(sv.amount), 0.0)
-             FROM sale_vouchers sv
-             INNER JOIN sales s ON sv.sale_id = s.id
-             WHERE s.cash_register_shift_id = ?1 AND NOT s.status = 'canceled'",
+            "SELECT
+                COALESCE(SUM(CASE WHEN sp.method = 'cash' THEN sp.amount ELSE 0 END), 0.0),
+                COALESCE(SUM(CASE WHEN sp.method IN ('debit_card', 'credit_card', 'transfer') THEN sp.amount ELSE 0 END), 0.0),
+                COALESCE(SUM(CASE WHEN sp.method = 'credit' THEN sp.amount ELSE 0 END), 0.0),
+                COALESCE(SUM(CASE WHEN sp.method = 'voucher' THEN sp.amount ELSE 0 END), 0.0),
+                COALESCE(SUM(CASE WHEN sp.method = 'gift_card' THEN sp.amount ELSE 0 END), 0.0),
+                COALESCE(SUM(CASE WHEN sp.method = 'points' THEN sp.amount ELSE 0 END), 0.0)
+             FROM sale_payments sp
+             INNER JOIN sales s ON sp.sale_id = s.id
+             WHERE s.cash_register_shift_id = ?1
+               AND (NOT s.status = 'cancelled' OR s.cancelled_shift_id != ?1)",
             params![shift_id],
-            |row| row.get(0),
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                ))
+            },
         )
-        .unwrap_or(0.0);
+        .unwrap_or((0.0, 0.0, 0.0, 0.0, 0.0, 0.0));
 
     // Debt payments
     let (total_debt_payments, debt_payments_cash, debt_payments_card): (f64, f64, f64) = conn
@@ -139,9 +231,43 @@ pub fn calculate_shift_totals(conn: &Connection, shift_id: i64, initial_cash: f6
         )
         .unwrap_or((0.0, 0.0, 0.0));
 
+    // Refunds paid out during this shift
+    let (total_cash_refunds, total_card_refunds): (f64, f64) = conn
+        .query_row(
+            "SELECT
+                COALESCE(SUM(CASE WHEN refund_method = 'cash' THEN total - exchange_amount ELSE 0 END), 0.0),
+                COALESCE(SUM(CASE WHEN refund_method = 'card' THEN total - exchange_amount ELSE 0 END), 0.0)
+             FROM returns
+             WHERE cash_register_shift_id = ?1",
+            params![shift_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap_or((0.0, 0.0));
+
+    // Reversals of sales from closed shifts, booked into this one
+    let (total_reversals, cash_reversals, card_reversals): (f64, f64, f64) = conn
+        .query_row(
+            "SELECT
+                COALESCE(SUM(total), 0.0),
+                COALESCE(SUM(cash_amount), 0.0),
+                COALESCE(SUM(card_amount), 0.0)
+             FROM sale_reversals
+             WHERE cash_register_shift_id = ?1",
+            params![shift_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap_or((0.0, 0.0, 0.0));
+
+    // Foreign cash goes to its own count; the base drawer pays the change for it
+    let foreign_cash = fetch_foreign_cash(conn, shift_id).unwrap_or_default();
+    let foreign_cash_base: f64 = foreign_cash.iter().map(|f| f.base_amount).sum();
+
     // Derived
-    let total_cash_sales = total_sales - total_card_sales - total_credit_sales - total_voucher_sales;
-    let total_cash = initial_cash + total_cash_sales + debt_payments_cash + total_movements_in - total_movements_out;
+    let total_cash = initial_cash + total_cash_sales + debt_payments_cash + total_movements_in
+        - total_movements_out
+        - total_cash_refunds
+        - cash_reversals
+        - foreign_cash_base;
 
     ShiftTotals {
         total_movements_in,
@@ -152,9 +278,18 @@ pub fn calculate_shift_totals(conn: &Connection, shift_id: i64, initial_cash: f6
         total_card_sales,
         total_credit_sales,
         total_voucher_sales,
+        total_gift_card_sales,
+        total_points_sales,
+        total_exchange_sales,
         total_debt_payments,
         debt_payments_cash,
         debt_payments_card,
+        total_cash_refunds,
+        total_card_refunds,
+        total_reversals,
+        cash_reversals,
+        card_reversals,
+        foreign_cash,
         total_cash,
     }
 }
@@ -283,6 +418,8 @@ pub fn close_shift(
     shift_id: i64,
     notes: Option<String>,
     user_id: String,
+    currency_counts: Option<Vec<CurrencyCountInput>>,
+    counted_cash: Option<f64>,
 ) -> Result<ShiftDto, String> {
     let mut conn = db.lock().map_err(|e| e.to_string())?;
     let tx = conn.transaction().map_err(|e| e.to_string())?;
@@ -319,7 +456,7 @@ pub fn close_shift(
             "UPDATE cash_register_shifts
          SET closing_date = ?1, closing_user_id = ?2, status = 'closed',
              expected_cash = ?3, cash_withdrawal = ?4, notes = ?5,
-             total_sales = ?6,
+             total_sales = ?6, counted_cash = ?8, cash_difference = ?9,
              updated_at = ?1
          WHERE id = ?7 AND status = 'open'",
             params![
@@ -330,6 +467,8 @@ pub fn close_shift(
                 notes_trimmed,
                 totals.total_sales,
                 shift_id,
+                counted_cash,
+                counted_cash.map(|c| c - expected_cash),
             ],
         )
         .map_err(|e| e.to_string())?;
@@ -338,6 +477,49 @@ pub fn close_shift(
         return Err("No se pudo cerrar el turno. Verifique que el turno exista y esté abierto.".to_string());
     }
 
+    // Foreign cash count (every currency received, plus any counted without sales)
+    let counts = currency_counts.unwrap_or_default();
+    let mut codes: Vec<&str> = totals
+        .foreign_cash
+        .iter()
+        .map(|f| f.currency_code.as_str())
+        .collect();
+    for count in &counts {
+        if !codes.contains(&count.currency_code.as_str()) {
+            codes.push(&count.currency_code);
+        }
+    }
+
+    for code in codes {
+        let expected = totals
+            .foreign_cash
+            .iter()
+            .find(|f| f.currency_code == code)
+            .map(|f| f.amount)
+            .unwrap_or(0.0);
+        let counted = counts
+            .iter()
+            .find(|c| c.currency_code == code)
+            .map(|c| c.counted_amount);
+
+        tx.execute(
+            "INSERT INTO shift_currency_counts (
+                id, cash_register_shift_id, currency_code, expected_amount,
+                counted_amount, difference, created_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                Uuid::new_v4().to_string(),
+                shift_id,
+                code,
+                expected,
+                counted,
+                counted.map(|c| c - expected),
+                now_local
+            ],
+        )
+        .map_err(|e| format!("Error registrando conteo de {}: {}", code, e))?;
+    }
+
     let sql = format!("{} WHERE s.id = ?1", SHIFT_SELECT_SQL);
     let shift = tx
         .query_row(&sql, params![shift_id], shift_from_row)
@@ -0,0 +1,185 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+use uuid::Uuid;
+
+use crate::commands::cash_register::payments::is_card_method;
+use crate::commands::settings::hardware::load_settings;
+use crate::payment_terminal::{build_terminal, ChargeOutcome, ChargeRequest};
+
+#[derive(Debug, Serialize)]
+pub struct TerminalChargeResponse {
+    /// Pass as `terminal_transaction_id` on the card tender when approved.
+    pub transaction_id: String,
+    pub status: String, // 'approved', 'declined', 'timeout'
+    pub approved: bool,
+    pub auth_code: Option<String>,
+    pub card_last4: Option<String>,
+    pub card_brand: Option<String>,
+    pub message: Option<String>,
+}
+
+/// Sends a card charge to the configured terminal and logs the result.
+/// The charge is linked to a sale when the sale is recorded with it.
+#[tauri::command]
+pub async fn charge_card_terminal(
+    app_handle: AppHandle,
+    amount: f64,
+    method: String,
+    user_id: String,
+) -> Result<TerminalChargeResponse, String> {
+    if !is_card_method(&method) {
+        return Err("Solo los pagos con tarjeta pueden cobrarse en terminal.".to_string());
+    }
+    if amount <= 0.0 {
+        return Err("El monto a cobrar debe ser mayor a cero.".to_string());
+    }
+
+    let config = load_settings(app_handle.clone())?;
+    let transaction_id = Uuid::new_v4().to_string();
+    let request = ChargeRequest {
+        amount,
+        reference: transaction_id.clone(),
+        terminal_id: config.terminal_id.clone(),
+    };
+
+    let terminal_config = config.payment_terminal.clone();
+    // A timed-out charge is voided right away, so a late approval on the
+    // device never leaves the card charged without a sale.
+    let (outcome, voided) = tauri::async_runtime::spawn_blocking(move || {
+        let terminal = build_terminal(&terminal_config)?;
+        let outcome = terminal.charge(&request)?;
+        let voided = match outcome {
+            ChargeOutcome::TimedOut => terminal.void(&request).is_ok(),
+            _ => false,
+        };
+        Ok::<_, String>((outcome, voided))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let response = match outcome {
+        ChargeOutcome::Approved {
+            auth_code,
+            last4,
+            brand,
+        } => TerminalChargeResponse {
+            transaction_id,
+            status: "approved".to_string(),
+            approved: true,
+            auth_code: Some(auth_code),
+            card_last4: Some(last4),
+            card_brand: Some(brand),
+            message: None,
+        },
+        ChargeOutcome::Declined { message } => TerminalChargeResponse {
+            transaction_id,
+            status: "declined".to_string(),
+            approved: false,
+            auth_code: None,
+            card_last4: None,
+            card_brand: None,
+            message: Some(message),
+        },
+        ChargeOutcome::TimedOut => TerminalChargeResponse {
+            transaction_id,
+            status: "timeout".to_string(),
+            approved: false,
+            auth_code: None,
+            card_last4: None,
+            card_brand: None,
+            message: Some(if voided {
+                "La terminal no respondió a tiempo; el cargo fue cancelado. Puede reintentar el cobro."
+                    .to_string()
+            } else {
+                "La terminal no respondió y no se pudo cancelar el cargo. Verifique en la terminal si el cargo se realizó antes de reintentar."
+                    .to_string()
+            }),
+        },
+    };
+
+    let db: State<Mutex<Connection>> = app_handle.state();
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+    conn.execute(
+        "INSERT INTO terminal_transactions (
+            id, method, amount, status, auth_code, card_last4, card_brand,
+            message, terminal_id, user_id, created_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            response.transaction_id,
+            method,
+            amount,
+            response.status,
+            response.auth_code,
+            response.card_last4,
+            response.card_brand,
+            response.message,
+            config.terminal_id,
+            user_id,
+            now
+        ],
+    )
+    .map_err(|e| format!("Error registrando cobro de terminal: {}", e))?;
+
+    Ok(response)
+}
+
+/// Voids an approved charge that no sale claimed, so the card is not left
+/// charged when checkout fails or is cancelled.
+pub(crate) fn void_unclaimed_charge(app_handle: &AppHandle, transaction_id: &str) -> Result<(), String> {
+    let db: State<Mutex<Connection>> = app_handle.state();
+    let (amount, terminal_id) = {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        let row: Option<(f64, String, Option<String>, Option<String>, Option<String>)> = conn
+            .query_row(
+                "SELECT amount, status, sale_id, voided_at, terminal_id
+                 FROM terminal_transactions WHERE id = ?1",
+                [transaction_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?;
+
+        let (amount, status, sale_id, voided_at, terminal_id) =
+            row.ok_or("Cobro de terminal no encontrado.".to_string())?;
+        if status != "approved" {
+            return Err("Solo los cobros aprobados pueden cancelarse.".to_string());
+        }
+        if sale_id.is_some() {
+            return Err("El cobro ya está asociado a una venta; cancele la venta.".to_string());
+        }
+        if voided_at.is_some() {
+            return Ok(());
+        }
+        (amount, terminal_id)
+    };
+
+    let config = load_settings(app_handle.clone())?;
+    let request = ChargeRequest {
+        amount,
+        reference: transaction_id.to_string(),
+        terminal_id: terminal_id.unwrap_or_else(|| config.terminal_id.clone()),
+    };
+    build_terminal(&config.payment_terminal)?.void(&request)?;
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    conn.execute(
+        "UPDATE terminal_transactions SET voided_at = ?1 WHERE id = ?2 AND sale_id IS NULL",
+        params![now, transaction_id],
+    )
+    .map_err(|e| format!("Error registrando cancelación de cobro: {}", e))?;
+    Ok(())
+}
+
+/// Cancels an approved terminal charge that was not used in a sale
+/// (checkout cancelled or the sale failed).
+#[tauri::command]
+pub async fn void_terminal_charge(app_handle: AppHandle, transaction_id: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || void_unclaimed_charge(&app_handle, &transaction_id))
+        .await
+        .map_err(|e| format!("Error de ejecución: {}", e))?
+}
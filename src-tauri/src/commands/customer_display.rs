@@ -0,0 +1,211 @@
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::commands::settings::business::fetch_business_settings;
+use crate::commands::settings::hardware::load_settings;
+use crate::customer_display::{
+    render_rows, vfd_commands, CustomerDisplayConfig, DisplayEvent, IdleMessages,
+};
+use crate::printer_transport::{PrinterTransport, SerialTransport};
+
+const WINDOW_LABEL: &str = "customer-display";
+/// How long the change stays on screen before the idle messages return.
+const CHANGE_HOLD: Duration = Duration::from_secs(20);
+
+/// What the customer window receives on the `customer-display` event.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayUpdate {
+    pub event: DisplayEvent,
+    pub rows: [String; 2],
+    pub currency_symbol: String,
+}
+
+static DISPLAY_QUEUE: OnceLock<Mutex<Sender<DisplayEvent>>> = OnceLock::new();
+/// Last update sent to the window, for when it (re)loads.
+static LAST_UPDATE: Mutex<Option<DisplayUpdate>> = Mutex::new(None);
+
+/// Queues an update for the customer display. Never blocks the caller;
+/// does nothing until the display worker is running.
+pub(crate) fn notify(event: DisplayEvent) {
+    if let Some(queue) = DISPLAY_QUEUE.get() {
+        if let Ok(sender) = queue.lock() {
+            let _ = sender.send(event);
+        }
+    }
+}
+
+fn idle_messages(app_handle: &AppHandle) -> (IdleMessages, String) {
+    let settings = app_handle
+        .try_state::<Mutex<Connection>>()
+        .and_then(|state| state.inner().lock().ok().and_then(|conn| fetch_business_settings(&conn).ok()))
+        .unwrap_or_default();
+
+    let top = if settings.customer_display_top_line.trim().is_empty() {
+        settings.store_name
+    } else {
+        settings.customer_display_top_line
+    };
+    (
+        IdleMessages {
+            top,
+            bottom: settings.customer_display_bottom_line,
+        },
+        settings.currency_symbol,
+    )
+}
+
+fn send_to_pole(config: &CustomerDisplayConfig, rows: &[String; 2]) -> Result<(), String> {
+    let port = config
+        .serial_port
+        .clone()
+        .filter(|p| !p.trim().is_empty())
+        .ok_or("Configure el puerto serie de la pantalla del cliente.".to_string())?;
+    SerialTransport {
+        port,
+        baud_rate: config.baud_rate,
+    }
+    .send(&vfd_commands(config, rows))
+}
+
+fn show(app_handle: &AppHandle, event: &DisplayEvent) {
+    let Ok(hardware) = load_settings(app_handle.clone()) else {
+        return;
+    };
+    let config = hardware.customer_display;
+    if !config.uses_serial() && !config.uses_window() {
+        return;
+    }
+
+    let (idle, currency_symbol) = idle_messages(app_handle);
+    let rows = render_rows(event, &idle, &currency_symbol, config.columns.max(1));
+
+    if config.uses_serial() {
+        let _ = send_to_pole(&config, &rows);
+    }
+    if config.uses_window() {
+        let update = DisplayUpdate {
+            event: event.clone(),
+            rows,
+            currency_symbol,
+        };
+        let _ = app_handle.emit_to(WINDOW_LABEL, "customer-display", &update);
+        if let Ok(mut last) = LAST_UPDATE.lock() {
+            *last = Some(update);
+        }
+    }
+}
+
+/// Starts the display worker, opens the customer window when configured
+/// and shows the idle messages. Updates are coalesced so a slow serial
+/// port only ever shows the latest one.
+pub fn start_customer_display(app_handle: AppHandle) {
+    let (sender, receiver) = mpsc::channel::<DisplayEvent>();
+    if DISPLAY_QUEUE.set(Mutex::new(sender)).is_err() {
+        return;
+    }
+
+    let opens_window = load_settings(app_handle.clone())
+        .map(|config| config.customer_display.uses_window())
+        .unwrap_or(false);
+    if opens_window {
+        let _ = open_display_window(&app_handle);
+    }
+
+    std::thread::spawn(move || {
+        show(&app_handle, &DisplayEvent::Idle);
+        let mut idle_at: Option<Instant> = None;
+
+        loop {
+            let wait = idle_at
+                .map(|at| at.saturating_duration_since(Instant::now()))
+                .unwrap_or(Duration::from_secs(3600));
+
+            match receiver.recv_timeout(wait) {
+                Ok(mut event) => {
+                    while let Ok(next) = receiver.try_recv() {
+                        event = next;
+                    }
+                    idle_at = match event {
+                        DisplayEvent::Change { .. } => Some(Instant::now() + CHANGE_HOLD),
+                        _ => None,
+                    };
+                    show(&app_handle, &event);
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    if idle_at.take().is_some() {
+                        show(&app_handle, &DisplayEvent::Idle);
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+}
+
+/// Opens (or focuses) the customer window, fullscreen on the configured monitor.
+pub(crate) fn open_display_window(app_handle: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app_handle.get_webview_window(WINDOW_LABEL) {
+        return window.set_focus().map_err(|e| e.to_string());
+    }
+
+    let config = load_settings(app_handle.clone())?.customer_display;
+    let window = WebviewWindowBuilder::new(
+        app_handle,
+        WINDOW_LABEL,
+        WebviewUrl::App("index.html".into()),
+    )
+    .title("Pantalla del cliente")
+    .inner_size(800.0, 480.0)
+    .build()
+    .map_err(|e| format!("No se pudo abrir la pantalla del cliente: {}", e))?;
+
+    if let Some(index) = config.window_monitor {
+        let monitors = window.available_monitors().map_err(|e| e.to_string())?;
+        if let Some(monitor) = monitors.get(index) {
+            window
+                .set_position(*monitor.position())
+                .and_then(|_| window.set_fullscreen(true))
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Sent by the sale screen when the cart changes or payment starts.
+#[tauri::command]
+pub fn update_customer_display(event: DisplayEvent) {
+    notify(event);
+}
+
+#[tauri::command]
+pub async fn open_customer_display(app_handle: AppHandle) -> Result<(), String> {
+    open_display_window(&app_handle)
+}
+
+/// What the customer window should show right after loading.
+#[tauri::command]
+pub fn get_customer_display_state() -> Option<DisplayUpdate> {
+    LAST_UPDATE.lock().ok().and_then(|last| last.clone())
+}
+
+/// Writes a test message to the pole display. `config` lets the settings
+/// screen try unsaved values.
+#[tauri::command]
+pub async fn test_customer_display(config: CustomerDisplayConfig) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let width = config.columns.max(1);
+        let rows = [
+            format!("{:<width$}", "PRUEBA DE PANTALLA", width = width),
+            "0123456789".repeat(width.div_ceil(10)),
+        ];
+        send_to_pole(&config, &rows)
+    })
+    .await
+    .map_err(|e| format!("Error de ejecución: {}", e))?
+}
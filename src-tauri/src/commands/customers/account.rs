@@ -52,16 +52,21 @@ pub fn get_customer_account_statement(
 
     let sql = r#"
     SELECT 
-      id, 
+      s.id, 
       'charge' as type, 
-      created_at as date, 
-      total as amount, 
-      folio, 
-      notes
-    FROM sales 
-    WHERE customer_id = ?1 
-      AND payment_method = 'credit' 
-      AND status != 'cancelled'
+      s.created_at as date, 
+      sp.amount as amount, 
+      s.folio, 
+      s.notes
+    FROM sales s
+    JOIN (
+      SELECT sale_id, SUM(amount) as amount
+      FROM sale_payments
+      WHERE method = 'credit'
+      GROUP BY sale_id
+    ) sp ON sp.sale_id = s.id
+    WHERE s.customer_id = ?1 
+      AND s.status != 'cancelled'
 
     UNION ALL
 
@@ -0,0 +1,746 @@
+use crate::commands::inventory::movements::{movements_filter_query, MovementsFilter};
+use crate::commands::reports::{
+    compute_reorder_suggestions, dead_stock_data_sql, dead_stock_from_row, low_stock_data_sql,
+    low_stock_from_row, top_selling_data_sql, top_selling_from_row, ReorderParams,
+};
+use crate::commands::sales::history::{sales_filter_sql, sales_order_sql, SalesFilter};
+use crate::commands::settings::permissions::require_permission;
+use crate::database::get_current_store_id;
+use chrono::{NaiveDate, NaiveDateTime};
+use rusqlite::types::ToSql;
+use rusqlite::Connection;
+use rust_xlsxwriter::{Format, Workbook, Worksheet};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOptions {
+    pub path: String,
+    pub format: String,         // 'csv' o 'xlsx'
+    pub locale: Option<String>, // 'es-MX' (default), 'en-US', 'es-ES'
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportResult {
+    pub path: String,
+    pub rows: usize,
+}
+
+/// Number and date conventions for one locale.
+struct ExportLocale {
+    decimal: char,
+    separator: char,
+    date: &'static str,
+    datetime: &'static str,
+    xlsx_date: &'static str,
+    xlsx_datetime: &'static str,
+}
+
+fn export_locale(code: Option<&str>) -> ExportLocale {
+    match code.unwrap_or("es-MX") {
+        "en-US" => ExportLocale {
+            decimal: '.',
+            separator: ',',
+            date: "%m/%d/%Y",
+            datetime: "%m/%d/%Y %H:%M",
+            xlsx_date: "mm/dd/yyyy",
+            xlsx_datetime: "mm/dd/yyyy hh:mm",
+        },
+        // Europa usa coma decimal, por eso el CSV se separa con punto y coma
+        "es-ES" => ExportLocale {
+            decimal: ',',
+            separator: ';',
+            date: "%d/%m/%Y",
+            datetime: "%d/%m/%Y %H:%M",
+            xlsx_date: "dd/mm/yyyy",
+            xlsx_datetime: "dd/mm/yyyy hh:mm",
+        },
+        _ => ExportLocale {
+            decimal: '.',
+            separator: ',',
+            date: "%d/%m/%Y",
+            datetime: "%d/%m/%Y %H:%M",
+            xlsx_date: "dd/mm/yyyy",
+            xlsx_datetime: "dd/mm/yyyy hh:mm",
+        },
+    }
+}
+
+enum Cell {
+    Text(String),
+    Integer(i64),
+    Money(f64),
+    Quantity(f64),
+    Date(Option<String>),
+}
+
+impl Cell {
+    fn text(value: Option<String>) -> Cell {
+        Cell::Text(value.unwrap_or_default())
+    }
+}
+
+/// Parses a DB timestamp; the bool tells whether it carries a time part.
+fn parse_db_date(value: &str) -> Option<(NaiveDateTime, bool)> {
+    for fmt in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, fmt) {
+            return Some((dt, true));
+        }
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| (dt, false))
+}
+
+/// Days since 1899-12-30, the way Excel stores dates.
+fn excel_serial(dt: &NaiveDateTime) -> f64 {
+    let base = NaiveDate::from_ymd_opt(1899, 12, 30)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .unwrap();
+    (*dt - base).num_seconds() as f64 / 86_400.0
+}
+
+enum ExportWriter {
+    Csv {
+        out: BufWriter<File>,
+        locale: ExportLocale,
+    },
+    Xlsx {
+        sheet: Box<Worksheet>,
+        path: PathBuf,
+        row: u32,
+        money: Format,
+        quantity: Format,
+        date: Format,
+        datetime: Format,
+    },
+}
+
+impl ExportWriter {
+    fn create(options: &ExportOptions, sheet_name: &str, headers: &[&str]) -> Result<(PathBuf, Self), String> {
+        let locale = export_locale(options.locale.as_deref());
+        let is_xlsx = match options.format.as_str() {
+            "csv" => false,
+            "xlsx" => true,
+            other => return Err(format!("Formato de exportación no soportado: {}", other)),
+        };
+
+        let mut path = PathBuf::from(&options.path);
+        let extension = if is_xlsx { "xlsx" } else { "csv" };
+        let has_extension = path
+            .extension()
+            .map(|e| e.eq_ignore_ascii_case(extension))
+            .unwrap_or(false);
+        if !has_extension {
+            path.set_extension(extension);
+        }
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
+                return Err(format!("La carpeta destino no existe: {}", parent.display()));
+            }
+        }
+
+        if !is_xlsx {
+            let file = File::create(&path)
+                .map_err(|e| format!("No se pudo crear el archivo: {}", e))?;
+            let mut out = BufWriter::new(file);
+            // BOM para que Excel reconozca UTF-8
+            out.write_all(b"\xEF\xBB\xBF").map_err(|e| e.to_string())?;
+            let mut writer = ExportWriter::Csv { out, locale };
+            writer.write_header(headers)?;
+            return Ok((path, writer));
+        }
+
+        let mut sheet = Worksheet::new();
+        sheet.set_name(sheet_name).map_err(|e| e.to_string())?;
+        let mut writer = ExportWriter::Xlsx {
+            sheet: Box::new(sheet),
+            path: path.clone(),
+            row: 0,
+            money: Format::new().set_num_format("#,##0.00"),
+            quantity: Format::new().set_num_format("#,##0.###"),
+            date: Format::new().set_num_format(locale.xlsx_date),
+            datetime: Format::new().set_num_format(locale.xlsx_datetime),
+        };
+        writer.write_header(headers)?;
+        Ok((path, writer))
+    }
+
+    fn write_header(&mut self, headers: &[&str]) -> Result<(), String> {
+        match self {
+            ExportWriter::Csv { .. } => {
+                let cells: Vec<Cell> = headers.iter().map(|h| Cell::Text(h.to_string())).collect();
+                self.write_row(&cells)
+            }
+            ExportWriter::Xlsx { sheet, row, .. } => {
+                let bold = Format::new().set_bold();
+                for (col, header) in headers.iter().enumerate() {
+                    sheet
+                        .write_string_with_format(0, col as u16, *header, &bold)
+                        .map_err(|e| e.to_string())?;
+                    sheet
+                        .set_column_width(col as u16, (header.chars().count() + 4).max(12) as f64)
+                        .map_err(|e| e.to_string())?;
+                }
+                sheet.set_freeze_panes(1, 0).map_err(|e| e.to_string())?;
+                *row = 1;
+                Ok(())
+            }
+        }
+    }
+
+    fn write_row(&mut self, cells: &[Cell]) -> Result<(), String> {
+        match self {
+            ExportWriter::Csv { out, locale } => {
+                let fields: Vec<String> = cells
+                    .iter()
+                    .map(|cell| csv_field(cell, locale))
+                    .collect();
+                let line = fields.join(&locale.separator.to_string());
+                out.write_all(line.as_bytes()).map_err(|e| e.to_string())?;
+                out.write_all(b"\r\n").map_err(|e| e.to_string())
+            }
+            ExportWriter::Xlsx { sheet, row, money, quantity, date, datetime, .. } => {
+                for (col, cell) in cells.iter().enumerate() {
+                    let col = col as u16;
+                    let result = match cell {
+                        Cell::Text(s) => sheet.write_string(*row, col, s.as_str()).map(|_| ()),
+                        Cell::Integer(n) => sheet.write_number(*row, col, *n as f64).map(|_| ()),
+                        Cell::Money(n) => sheet.write_number_with_format(*row, col, *n, money).map(|_| ()),
+                        Cell::Quantity(n) => sheet.write_number_with_format(*row, col, *n, quantity).map(|_| ()),
+                        Cell::Date(Some(s)) => match parse_db_date(s) {
+                            Some((dt, true)) => sheet
+                                .write_number_with_format(*row, col, excel_serial(&dt), datetime)
+                                .map(|_| ()),
+                            Some((dt, false)) => sheet
+                                .write_number_with_format(*row, col, excel_serial(&dt), date)
+                                .map(|_| ()),
+                            None => sheet.write_string(*row, col, s.as_str()).map(|_| ()),
+                        },
+                        Cell::Date(None) => Ok(()),
+                    };
+                    result.map_err(|e| e.to_string())?;
+                }
+                *row += 1;
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(self) -> Result<(), String> {
+        match self {
+            ExportWriter::Csv { mut out, .. } => out.flush().map_err(|e| e.to_string()),
+            ExportWriter::Xlsx { sheet, path, .. } => {
+                let mut workbook = Workbook::new();
+                workbook.push_worksheet(*sheet);
+                workbook
+                    .save(Path::new(&path))
+                    .map_err(|e| format!("No se pudo guardar el archivo: {}", e))
+            }
+        }
+    }
+}
+
+fn csv_field(cell: &Cell, locale: &ExportLocale) -> String {
+    let raw = match cell {
+        Cell::Text(s) => s.clone(),
+        Cell::Integer(n) => n.to_string(),
+        Cell::Money(n) => format!("{:.2}", n).replace('.', &locale.decimal.to_string()),
+        Cell::Quantity(n) => {
+            let s = format!("{:.3}", n);
+            let s = s.trim_end_matches('0').trim_end_matches('.');
+            s.replace('.', &locale.decimal.to_string())
+        }
+        Cell::Date(Some(s)) => match parse_db_date(s) {
+            Some((dt, true)) => dt.format(locale.datetime).to_string(),
+            Some((dt, false)) => dt.format(locale.date).to_string(),
+            None => s.clone(),
+        },
+        Cell::Date(None) => String::new(),
+    };
+
+    if raw.contains(locale.separator) || raw.contains('"') || raw.contains('\n') || raw.contains('\r') {
+        format!("\"{}\"", raw.replace('"', "\"\""))
+    } else {
+        raw
+    }
+}
+
+/// Runs an export off the UI thread with the DB lock held.
+async fn run_export<F>(app_handle: AppHandle, user_id: String, job: F) -> Result<ExportResult, String>
+where
+    F: FnOnce(&Connection) -> Result<ExportResult, String> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(move || {
+        let db = app_handle.state::<Mutex<Connection>>();
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        require_permission(&conn, &user_id, "reports:export")?;
+        job(&conn)
+    })
+    .await
+    .map_err(|e| format!("Error en hilo de exportación: {}", e))?
+}
+
+/// Streams every row of `sql` through `to_cells` into a new file.
+fn stream_export<F>(
+    conn: &Connection,
+    options: &ExportOptions,
+    sheet_name: &str,
+    headers: &[&str],
+    sql: &str,
+    params: &[&dyn ToSql],
+    mut to_cells: F,
+) -> Result<ExportResult, String>
+where
+    F: FnMut(&rusqlite::Row) -> rusqlite::Result<Vec<Cell>>,
+{
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let (path, mut writer) = ExportWriter::create(options, sheet_name, headers)?;
+
+    let mut rows = stmt.query(params).map_err(|e| e.to_string())?;
+    let mut count = 0;
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let cells = to_cells(row).map_err(|e| e.to_string())?;
+        writer.write_row(&cells)?;
+        count += 1;
+    }
+    writer.finish()?;
+
+    Ok(ExportResult {
+        path: path.to_string_lossy().to_string(),
+        rows: count,
+    })
+}
+
+fn category_params(params: &mut Vec<Box<dyn ToSql + Send>>, category_ids: &Option<Vec<String>>) {
+    if let Some(ids) = category_ids {
+        for id in ids {
+            params.push(Box::new(id.clone()));
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn export_sales_report(
+    app_handle: AppHandle,
+    user_id: String,
+    options: ExportOptions,
+    from_date: String,
+    to_date: String,
+) -> Result<ExportResult, String> {
+    run_export(app_handle, user_id, move |conn| {
+        let sql = r#"
+            SELECT
+                date(s.created_at) as day,
+                COUNT(DISTINCT s.id) as transaction_count,
+                COALESCE(SUM(si.total - COALESCE(ri.returned_subtotal, 0.0)), 0.0) as net_sales,
+                COALESCE(SUM(
+                    (si.total - COALESCE(ri.returned_subtotal, 0.0))
+                    - ((si.quantity - COALESCE(ri.returned_qty, 0.0)) * COALESCE(p.purchase_price, 0))
+                ), 0.0) as net_profit
+            FROM sale_items si
+            JOIN sales s ON si.sale_id = s.id
+            JOIN products p ON si.product_id = p.id
+            LEFT JOIN (
+                SELECT sale_item_id, SUM(quantity) as returned_qty, SUM(subtotal) as returned_subtotal
+                FROM return_items
+                GROUP BY sale_item_id
+            ) ri ON ri.sale_item_id = si.id
+            WHERE s.created_at BETWEEN ?1 AND ?2
+              AND s.status IN ('completed', 'partial_return')
+            GROUP BY day
+            ORDER BY day ASC
+        "#;
+
+        stream_export(
+            conn,
+            &options,
+            "Ventas",
+            &["Fecha", "Transacciones", "Venta neta", "Utilidad", "Ticket promedio"],
+            sql,
+            &[&from_date, &to_date],
+            |row| {
+                let count: i64 = row.get(1)?;
+                let net_sales: f64 = row.get(2)?;
+                let average = if count > 0 { net_sales / count as f64 } else { 0.0 };
+                Ok(vec![
+                    Cell::Date(row.get(0)?),
+                    Cell::Integer(count),
+                    Cell::Money(net_sales),
+                    Cell::Money(row.get(3)?),
+                    Cell::Money(average),
+                ])
+            },
+        )
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn export_top_selling_products(
+    app_handle: AppHandle,
+    user_id: String,
+    options: ExportOptions,
+    from_date: String,
+    to_date: String,
+    category_ids: Option<Vec<String>>,
+    sort_by: Option<String>,
+    sort_order: Option<String>,
+) -> Result<ExportResult, String> {
+    run_export(app_handle, user_id, move |conn| {
+        let sql = top_selling_data_sql(&category_ids, sort_by.as_deref(), sort_order.as_deref());
+
+        let mut params: Vec<Box<dyn ToSql + Send>> = vec![
+            Box::new(from_date),
+            Box::new(to_date),
+            Box::new(-1_i64),
+            Box::new(0_i64),
+        ];
+        category_params(&mut params, &category_ids);
+        let params_refs: Vec<&dyn ToSql> = params.iter().map(|p| p.as_ref() as &dyn ToSql).collect();
+
+        stream_export(
+            conn,
+            &options,
+            "Más vendidos",
+            &["#", "Producto", "Código", "Categoría", "Cantidad", "Importe", "% del total"],
+            &sql,
+            &params_refs,
+            |row| {
+                let p = top_selling_from_row(row)?;
+                Ok(vec![
+                    Cell::Integer(p.ranking),
+                    Cell::Text(p.product_name),
+                    Cell::Text(p.product_code),
+                    Cell::Text(p.category_name),
+                    Cell::Quantity(p.quantity_sold),
+                    Cell::Money(p.total_revenue),
+                    Cell::Money(p.percentage_of_total),
+                ])
+            },
+        )
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn export_dead_stock_report(
+    app_handle: AppHandle,
+    user_id: String,
+    options: ExportOptions,
+    from_date: String,
+    to_date: String,
+    category_ids: Option<Vec<String>>,
+    sort_by: Option<String>,
+    sort_order: Option<String>,
+) -> Result<ExportResult, String> {
+    run_export(app_handle, user_id, move |conn| {
+        let store_id = get_current_store_id(conn)?;
+        let sql = dead_stock_data_sql(&category_ids, sort_by.as_deref(), sort_order.as_deref());
+
+        let mut params: Vec<Box<dyn ToSql + Send>> = vec![
+            Box::new(from_date),
+            Box::new(to_date),
+            Box::new(store_id),
+            Box::new(-1_i64),
+            Box::new(0_i64),
+        ];
+        category_params(&mut params, &category_ids);
+        let params_refs: Vec<&dyn ToSql> = params.iter().map(|p| p.as_ref() as &dyn ToSql).collect();
+
+        stream_export(
+            conn,
+            &options,
+            "Sin movimiento",
+            &["Producto", "Código", "Categoría", "Existencia", "Costo", "Valor detenido", "Última venta"],
+            &sql,
+            &params_refs,
+            |row| {
+                let p = dead_stock_from_row(row)?;
+                Ok(vec![
+                    Cell::Text(p.product_name),
+                    Cell::Text(p.product_code),
+                    Cell::Text(p.category_name),
+                    Cell::Integer(p.current_stock),
+                    Cell::Money(p.purchase_price),
+                    Cell::Money(p.stagnant_value),
+                    Cell::Date(p.last_sale_date),
+                ])
+            },
+        )
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn export_inventory_valuation(
+    app_handle: AppHandle,
+    user_id: String,
+    options: ExportOptions,
+) -> Result<ExportResult, String> {
+    run_export(app_handle, user_id, move |conn| {
+        let store_id = get_current_store_id(conn)?;
+        let sql = r#"
+            SELECT
+                p.name,
+                p.code,
+                COALESCE(c.name, 'Sin Categoría') as category_name,
+                i.stock,
+                COALESCE(p.purchase_price, 0.0) as purchase_price,
+                COALESCE(p.retail_price, 0.0) as retail_price
+            FROM products p
+            JOIN store_inventory i ON p.id = i.product_id
+            LEFT JOIN categories c ON p.category_id = c.id
+            WHERE p.deleted_at IS NULL
+              AND i.stock > 0
+              AND i.store_id = ?1
+            ORDER BY category_name ASC, p.name ASC
+        "#;
+
+        stream_export(
+            conn,
+            &options,
+            "Valuación",
+            &[
+                "Producto", "Código", "Categoría", "Existencia", "Costo unitario",
+                "Precio", "Valor a costo", "Valor a venta", "Utilidad proyectada",
+            ],
+            sql,
+            &[&store_id],
+            |row| {
+                let stock: i64 = row.get(3)?;
+                let cost: f64 = row.get(4)?;
+                let retail: f64 = row.get(5)?;
+                let total_cost = stock as f64 * cost;
+                let total_retail = stock as f64 * retail;
+                Ok(vec![
+                    Cell::Text(row.get(0)?),
+                    Cell::Text(row.get(1)?),
+                    Cell::Text(row.get(2)?),
+                    Cell::Integer(stock),
+                    Cell::Money(cost),
+                    Cell::Money(retail),
+                    Cell::Money(total_cost),
+                    Cell::Money(total_retail),
+                    Cell::Money(total_retail - total_cost),
+                ])
+            },
+        )
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn export_low_stock_products(
+    app_handle: AppHandle,
+    user_id: String,
+    options: ExportOptions,
+    category_ids: Option<Vec<String>>,
+    sort_by: Option<String>,
+    sort_order: Option<String>,
+) -> Result<ExportResult, String> {
+    run_export(app_handle, user_id, move |conn| {
+        let store_id = get_current_store_id(conn)?;
+        let sql = low_stock_data_sql(&category_ids, sort_by.as_deref(), sort_order.as_deref());
+
+        let mut params: Vec<Box<dyn ToSql + Send>> = vec![
+            Box::new(store_id),
+            Box::new(-1_i64),
+            Box::new(0_i64),
+        ];
+        category_params(&mut params, &category_ids);
+        let params_refs: Vec<&dyn ToSql> = params.iter().map(|p| p.as_ref() as &dyn ToSql).collect();
+
+        stream_export(
+            conn,
+            &options,
+            "Stock bajo",
+            &[
+                "Producto", "Código", "Categoría", "Existencia", "Mínimo",
+                "Pedido sugerido", "Costo", "Precio",
+            ],
+            &sql,
+            &params_refs,
+            |row| {
+                let p = low_stock_from_row(row)?;
+                Ok(vec![
+                    Cell::Text(p.product_name),
+                    Cell::Text(p.product_code),
+                    Cell::Text(p.category_name),
+                    Cell::Integer(p.current_stock),
+                    Cell::Integer(p.minimum_stock),
+                    Cell::Integer(p.suggested_order),
+                    Cell::Money(p.purchase_price),
+                    Cell::Money(p.retail_price),
+                ])
+            },
+        )
+    })
+    .await
+}
+
+/// Draft purchase list from the reorder suggestions.
+#[tauri::command]
+pub async fn export_reorder_list(
+    app_handle: AppHandle,
+    user_id: String,
+    options: ExportOptions,
+    params: Option<ReorderParams>,
+) -> Result<ExportResult, String> {
+    run_export(app_handle, user_id, move |conn| {
+        let suggestions = compute_reorder_suggestions(conn, &params.unwrap_or_default())?;
+
+        let (path, mut writer) = ExportWriter::create(
+            &options,
+            "Pedido sugerido",
+            &[
+                "Producto", "Código", "Categoría", "Existencia", "Venta diaria",
+                "Días de cobertura", "Cantidad a pedir", "Costo unitario", "Importe",
+            ],
+        )?;
+
+        let mut count = 0;
+        for s in suggestions.into_iter().filter(|s| s.suggested_order > 0) {
+            writer.write_row(&[
+                Cell::Text(s.product_name),
+                Cell::Text(s.product_code),
+                Cell::Text(s.category_name),
+                Cell::Integer(s.current_stock),
+                Cell::Quantity(s.daily_average),
+                s.days_of_cover.map(Cell::Quantity).unwrap_or(Cell::Text(String::new())),
+                Cell::Integer(s.suggested_order),
+                Cell::Money(s.purchase_price),
+                Cell::Money(s.estimated_cost),
+            ])?;
+            count += 1;
+        }
+        writer.finish()?;
+
+        Ok(ExportResult {
+            path: path.to_string_lossy().to_string(),
+            rows: count,
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn export_sales_history(
+    app_handle: AppHandle,
+    user_id: String,
+    options: ExportOptions,
+    filter: SalesFilter,
+) -> Result<ExportResult, String> {
+    run_export(app_handle, user_id, move |conn| {
+        let (where_sql, params) = sales_filter_sql(&filter);
+        let sql = format!(
+            "SELECT
+              s.folio, s.sale_date, s.status, s.payment_method,
+              s.subtotal, s.discount_amount, s.total,
+              u.full_name, c.name
+             FROM sales s
+             LEFT JOIN users u ON s.user_id = u.id
+             LEFT JOIN customers c ON s.customer_id = c.id
+             WHERE {}
+             ORDER BY {}",
+            where_sql,
+            sales_order_sql(&filter)
+        );
+        let params_refs: Vec<&dyn ToSql> = params.iter().map(|p| p.as_ref()).collect();
+
+        stream_export(
+            conn,
+            &options,
+            "Historial de ventas",
+            &[
+                "Folio", "Fecha", "Estado", "Forma de pago", "Subtotal",
+                "Descuento", "Total", "Cajero", "Cliente",
+            ],
+            &sql,
+            &params_refs,
+            |row| {
+                Ok(vec![
+                    Cell::Text(row.get(0)?),
+                    Cell::Date(row.get(1)?),
+                    Cell::Text(row.get(2)?),
+                    Cell::Text(row.get(3)?),
+                    Cell::Money(row.get::<_, Option<f64>>(4)?.unwrap_or(0.0)),
+                    Cell::Money(row.get::<_, Option<f64>>(5)?.unwrap_or(0.0)),
+                    Cell::Money(row.get(6)?),
+                    Cell::text(row.get(7)?),
+                    Cell::text(row.get(8)?),
+                ])
+            },
+        )
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn export_kardex(
+    app_handle: AppHandle,
+    user_id: String,
+    options: ExportOptions,
+    filters: Option<MovementsFilter>,
+) -> Result<ExportResult, String> {
+    run_export(app_handle, user_id, move |conn| {
+        let dq = movements_filter_query(filters.as_ref());
+        let sql = format!(
+            "SELECT
+              m.created_at, p.name, p.code, m.type, m.reason, m.quantity,
+              m.previous_stock, m.new_stock, m.cost, m.reference,
+              u.full_name, m.notes
+             FROM inventory_movements m
+             JOIN products p ON m.product_id = p.id
+             LEFT JOIN users u ON m.user_id = u.id
+             WHERE {}
+             ORDER BY m.created_at ASC, m.rowid ASC",
+            dq.sql_parts.join(" AND ")
+        );
+        let params_refs: Vec<&dyn ToSql> = dq.params.iter().map(|p| p.as_ref() as &dyn ToSql).collect();
+
+        stream_export(
+            conn,
+            &options,
+            "Kardex",
+            &[
+                "Fecha", "Producto", "Código", "Tipo", "Motivo", "Entrada", "Salida",
+                "Existencia anterior", "Existencia", "Costo", "Referencia", "Usuario", "Notas",
+            ],
+            &sql,
+            &params_refs,
+            |row| {
+                let movement_type: String = row.get(3)?;
+                let quantity: i64 = row.get(5)?;
+                let (entry, exit) = if movement_type == "IN" {
+                    (Cell::Integer(quantity), Cell::Text(String::new()))
+                } else {
+                    (Cell::Text(String::new()), Cell::Integer(quantity))
+                };
+                Ok(vec![
+                    Cell::Date(row.get(0)?),
+                    Cell::Text(row.get(1)?),
+                    Cell::Text(row.get(2)?),
+                    Cell::Text(if movement_type == "IN" { "Entrada".into() } else { "Salida".into() }),
+                    Cell::Text(row.get(4)?),
+                    entry,
+                    exit,
+                    Cell::Integer(row.get(6)?),
+                    Cell::Integer(row.get(7)?),
+                    Cell::Money(row.get::<_, Option<f64>>(8)?.unwrap_or(0.0)),
+                    Cell::text(row.get(9)?),
+                    Cell::text(row.get(10)?),
+                    Cell::text(row.get(11)?),
+                ])
+            },
+        )
+    })
+    .await
+}
@@ -0,0 +1,116 @@
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{command, State};
+
+use crate::barcode_utils::{internal_ean13, validate_internal_prefix};
+use crate::commands::settings::business::fetch_business_settings;
+use crate::commands::settings::permissions::require_permission;
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BarcodeBackfillResult {
+    pub assigned: usize,
+    pub first_code: Option<String>,
+    pub last_code: Option<String>,
+}
+
+/// Reserves `count` unused in-store EAN-13 codes with the configured prefix.
+/// Sequences continue after the highest code already issued, skipping any
+/// value present as a barcode or product code (deleted products included).
+pub(crate) fn allocate_internal_barcodes(conn: &Connection, count: usize) -> Result<Vec<String>, String> {
+    let prefix = fetch_business_settings(conn)?.internal_barcode_prefix;
+    validate_internal_prefix(&prefix)?;
+
+    let mut stmt = conn
+        .prepare("SELECT barcode FROM products WHERE barcode LIKE ?1 AND length(barcode) = 13")
+        .map_err(|e| e.to_string())?;
+    let issued = stmt
+        .query_map([format!("{}%", prefix)], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|code| code.ok())
+        .filter_map(|code| code.get(prefix.len()..12).and_then(|seq| seq.parse::<u64>().ok()))
+        .max()
+        .unwrap_or(0);
+
+    let mut taken_stmt = conn
+        .prepare("SELECT EXISTS(SELECT 1 FROM products WHERE barcode = ?1 OR code = ?1)")
+        .map_err(|e| e.to_string())?;
+
+    let mut codes = Vec::with_capacity(count);
+    let mut sequence = issued + 1;
+    while codes.len() < count {
+        let code = internal_ean13(&prefix, sequence)?;
+        let taken: bool = taken_stmt
+            .query_row([&code], |row| row.get(0))
+            .map_err(|e| format!("Error al verificar código de barras: {}", e))?;
+        if !taken {
+            codes.push(code);
+        }
+        sequence += 1;
+    }
+    Ok(codes)
+}
+
+/// Next free in-store barcode, for the product form's "generate" button.
+/// Nothing is reserved until the product is saved.
+#[command]
+pub fn generate_internal_barcode(db: State<'_, Mutex<Connection>>) -> Result<String, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    allocate_internal_barcodes(&conn, 1)?
+        .pop()
+        .ok_or("No se pudo generar el código de barras".to_string())
+}
+
+/// Assigns in-store barcodes to every product that has none.
+#[command]
+pub fn backfill_internal_barcodes(
+    db: State<'_, Mutex<Connection>>,
+    user_id: String,
+) -> Result<BarcodeBackfillResult, String> {
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    require_permission(&conn, &user_id, "products:edit")?;
+
+    let product_ids: Vec<String> = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id FROM products
+                 WHERE (barcode IS NULL OR TRIM(barcode) = '') AND deleted_at IS NULL
+                 ORDER BY created_at, name",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?
+    };
+
+    if product_ids.is_empty() {
+        return Ok(BarcodeBackfillResult {
+            assigned: 0,
+            first_code: None,
+            last_code: None,
+        });
+    }
+
+    let codes = allocate_internal_barcodes(&conn, product_ids.len())?;
+    let now_local = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    {
+        let mut stmt = tx
+            .prepare("UPDATE products SET barcode = ?1, updated_at = ?2 WHERE id = ?3")
+            .map_err(|e| e.to_string())?;
+        for (product_id, code) in product_ids.iter().zip(&codes) {
+            stmt.execute(params![code, &now_local, product_id])
+                .map_err(|e| format!("Error al asignar código de barras: {}", e))?;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(BarcodeBackfillResult {
+        assigned: codes.len(),
+        first_code: codes.first().cloned(),
+        last_code: codes.last().cloned(),
+    })
+}
@@ -0,0 +1,297 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::sync::Mutex;
+use tauri::{command, AppHandle, Manager, State};
+
+use crate::commands::inventory::products::{query_filtered_products, ProductFilters, ProductView};
+use crate::commands::settings::business::fetch_business_settings;
+use crate::commands::settings::hardware::load_settings;
+use crate::label_utils::{render_labels, LabelData};
+use crate::printer_transport::open_transport;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LabelConfig {
+    /// 'zpl', 'epl' or 'escpos'
+    pub language: String,
+    pub width_mm: u32,
+    pub height_mm: u32,
+    /// 203 or 300
+    pub dpi: u32,
+    pub show_name: bool,
+    pub show_price: bool,
+    pub show_wholesale_price: bool,
+    pub show_barcode: bool,
+    /// 'code128' or 'ean13'; codes that aren't valid EAN-13 print as CODE128
+    pub barcode_type: String,
+    pub copies: u32,
+    /// Label printer; `None` prints on the receipt printer
+    pub printer_transport: Option<String>,
+    pub printer_name: Option<String>,
+    /// host[:port] for tcp, port name for serial
+    pub printer_address: Option<String>,
+}
+
+impl Default for LabelConfig {
+    fn default() -> Self {
+        Self {
+            language: "zpl".to_string(),
+            width_mm: 50,
+            height_mm: 25,
+            dpi: 203,
+            show_name: true,
+            show_price: true,
+            show_wholesale_price: false,
+            show_barcode: true,
+            barcode_type: "code128".to_string(),
+            copies: 1,
+            printer_transport: None,
+            printer_name: None,
+            printer_address: None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelPrintResult {
+    pub products: usize,
+    pub labels: usize,
+    pub printer: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceChangedProduct {
+    pub id: String,
+    pub code: String,
+    pub name: String,
+    pub retail_price: f64,
+    pub wholesale_price: f64,
+    pub printed_retail_price: f64,
+    pub printed_wholesale_price: f64,
+    pub printed_at: String,
+}
+
+fn label_config_path(app_handle: &AppHandle) -> Result<std::path::PathBuf, String> {
+    let app_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("No se pudo obtener el directorio de datos: {}", e))?;
+    Ok(app_dir.join("labels.json"))
+}
+
+#[command]
+pub fn get_label_config(app_handle: AppHandle) -> Result<LabelConfig, String> {
+    let path = label_config_path(&app_handle)?;
+    if !path.exists() {
+        return Ok(LabelConfig::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Error leyendo archivo {:?}: {}", path, e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Error al leer configuración de etiquetas (JSON corrupto): {}", e))
+}
+
+#[command]
+pub fn save_label_config(app_handle: AppHandle, config: LabelConfig) -> Result<(), String> {
+    if !matches!(config.language.as_str(), "zpl" | "epl" | "escpos") {
+        return Err(format!("Lenguaje de etiqueta no soportado: {}", config.language));
+    }
+    if config.width_mm == 0 || config.height_mm == 0 {
+        return Err("Las medidas de la etiqueta deben ser mayores a cero.".to_string());
+    }
+
+    let path = label_config_path(&app_handle)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Error al crear directorio de datos: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Error al serializar configuración: {}", e))?;
+    fs::write(&path, json)
+        .map_err(|e| format!("Error al escribir archivo de configuración en {:?}: {}", path, e))
+}
+
+/// Renders and sends the labels, then records the printed prices.
+/// The database is not locked while the printer receives the job.
+fn print_labels(
+    app_handle: &AppHandle,
+    products: &[ProductView],
+    copies: Option<u32>,
+    user_id: Option<&str>,
+) -> Result<LabelPrintResult, String> {
+    if products.is_empty() {
+        return Err("No hay productos para imprimir etiquetas.".to_string());
+    }
+
+    let config = get_label_config(app_handle.clone())?;
+    let mut hardware = load_settings(app_handle.clone())?;
+    if let Some(transport) = config.printer_transport.clone() {
+        hardware.printer_transport = transport;
+        hardware.printer_name = config.printer_name.clone();
+        if hardware.printer_transport == "serial" {
+            hardware.printer_serial_port = config.printer_address.clone();
+        } else {
+            hardware.printer_address = config.printer_address.clone();
+        }
+    }
+    let printer = open_transport(&hardware)?
+        .ok_or("No hay impresora de etiquetas configurada.".to_string())?;
+
+    let db = app_handle.state::<Mutex<Connection>>();
+    let symbol = {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        fetch_business_settings(&conn)
+            .map(|s| s.currency_symbol)
+            .unwrap_or_else(|_| "$".to_string())
+    };
+    let labels: Vec<LabelData> = products
+        .iter()
+        .map(|p| LabelData {
+            name: p.name.clone(),
+            price: p.retail_price,
+            wholesale_price: p.wholesale_price,
+            barcode: p
+                .barcode
+                .clone()
+                .filter(|b| !b.trim().is_empty())
+                .unwrap_or_else(|| p.code.clone()),
+        })
+        .collect();
+
+    let copies = copies.unwrap_or(config.copies).max(1);
+    let data = render_labels(&config, &labels, &symbol, copies);
+    printer.send(&data)?;
+
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "INSERT INTO label_prints (product_id, retail_price, wholesale_price, printed_at, printed_by)
+             VALUES (?1, ?2, ?3, datetime('now', 'localtime'), ?4)
+             ON CONFLICT(product_id) DO UPDATE SET
+                retail_price = excluded.retail_price,
+                wholesale_price = excluded.wholesale_price,
+                printed_at = excluded.printed_at,
+                printed_by = excluded.printed_by",
+        )
+        .map_err(|e| e.to_string())?;
+    for p in products {
+        stmt.execute(params![p.id, p.retail_price, p.wholesale_price, user_id])
+            .map_err(|e| format!("Error al registrar impresión de etiqueta: {}", e))?;
+    }
+
+    Ok(LabelPrintResult {
+        products: products.len(),
+        labels: products.len() * copies as usize,
+        printer: printer.describe(),
+    })
+}
+
+/// Prints labels for the products of the inventory list (same search and
+/// filters as `get_all_filtered_products`), optionally narrowed to `product_ids`.
+#[command]
+pub async fn print_product_labels(
+    app_handle: AppHandle,
+    search: Option<String>,
+    filters: Option<ProductFilters>,
+    product_ids: Option<Vec<String>>,
+    copies: Option<u32>,
+    user_id: Option<String>,
+) -> Result<LabelPrintResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let app_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("No se pudo obtener el directorio de datos: {}", e))?;
+
+        let mut products = {
+            let db = app_handle.state::<Mutex<Connection>>();
+            let conn = db.lock().map_err(|e| e.to_string())?;
+            query_filtered_products(&conn, &app_dir, search, filters)?
+        };
+        if let Some(ids) = product_ids {
+            let ids: HashSet<String> = ids.into_iter().collect();
+            products.retain(|p| ids.contains(&p.id));
+        }
+
+        print_labels(&app_handle, &products, copies, user_id.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Error de ejecución: {}", e))?
+}
+
+fn query_price_changed(conn: &Connection) -> Result<Vec<PriceChangedProduct>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT p.id, p.code, p.name, p.retail_price, p.wholesale_price,
+                    lp.retail_price, lp.wholesale_price, lp.printed_at
+             FROM label_prints lp
+             INNER JOIN products p ON p.id = lp.product_id
+             WHERE p.deleted_at IS NULL
+               AND (ABS(p.retail_price - lp.retail_price) >= 0.005
+                    OR ABS(p.wholesale_price - lp.wholesale_price) >= 0.005)
+             ORDER BY p.name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(PriceChangedProduct {
+                id: row.get(0)?,
+                code: row.get(1)?,
+                name: row.get(2)?,
+                retail_price: row.get(3)?,
+                wholesale_price: row.get(4)?,
+                printed_retail_price: row.get(5)?,
+                printed_wholesale_price: row.get(6)?,
+                printed_at: row.get(7)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+/// Products whose price changed since their label was last printed.
+#[command]
+pub fn get_price_changed_products(
+    db: State<'_, Mutex<Connection>>,
+) -> Result<Vec<PriceChangedProduct>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    query_price_changed(&conn)
+}
+
+/// Reprints every label whose price changed since the last print.
+#[command]
+pub async fn print_price_change_labels(
+    app_handle: AppHandle,
+    copies: Option<u32>,
+    user_id: Option<String>,
+) -> Result<LabelPrintResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let app_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("No se pudo obtener el directorio de datos: {}", e))?;
+
+        let products = {
+            let db = app_handle.state::<Mutex<Connection>>();
+            let conn = db.lock().map_err(|e| e.to_string())?;
+            let changed: HashSet<String> = query_price_changed(&conn)?.into_iter().map(|p| p.id).collect();
+            if changed.is_empty() {
+                return Err("No hay etiquetas con cambio de precio.".to_string());
+            }
+
+            let mut products = query_filtered_products(&conn, &app_dir, None, None)?;
+            products.retain(|p| changed.contains(&p.id));
+            products
+        };
+
+        print_labels(&app_handle, &products, copies, user_id.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Error de ejecución: {}", e))?
+}
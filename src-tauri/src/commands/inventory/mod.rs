@@ -1,6 +1,8 @@
+pub mod barcodes;
 pub mod categories;
 pub mod db_utils;
 pub mod kits;
+pub mod labels;
 pub mod movements;
 pub mod products;
 pub mod promotions;
@@ -36,6 +36,7 @@ pub struct PaginatedResponse<T> {
 #[derive(serde::Deserialize)]
 pub struct MovementsFilter {
   pub search: Option<String>,
+  pub product_id: Option<String>,    // kardex de un solo producto
   pub movement_type: Option<String>, // 'IN', 'OUT' o null
   pub start_date: Option<String>,    // YYYY-MM-DD
   pub end_date: Option<String>,      // YYYY-MM-DD
@@ -66,23 +67,19 @@ pub struct BulkReceptionPayload {
   pub user_id: String,
 }
 
-#[tauri::command]
-pub fn get_inventory_movements(
-  app_handle: AppHandle,
-  db_state: State<'_, Mutex<Connection>>,
-  page: i64,
-  page_size: i64,
-  sort_by: Option<String>,
-  sort_order: Option<String>,
-  filters: Option<MovementsFilter>,
-) -> Result<PaginatedResponse<InventoryMovementView>, String> {
-  let conn = db_state.lock().unwrap();
-  let app_dir = app_handle.path().app_data_dir().unwrap();
-
+/// WHERE conditions (over `inventory_movements m JOIN products p`) for a movements filter.
+pub(crate) fn movements_filter_query(filters: Option<&MovementsFilter>) -> DynamicQuery {
   let mut dq = DynamicQuery::new();
   dq.add_condition("1=1");
   
   if let Some(f) = filters {
+    if let Some(product_id) = &f.product_id {
+      if !product_id.is_empty() {
+        dq.add_condition("m.product_id = ?");
+        dq.add_param(product_id.clone());
+      }
+    }
+
     if let Some(s) = &f.search {
       if !s.is_empty() {
         dq.add_condition("(p.name LIKE ? OR p.code LIKE ?)");
@@ -113,6 +110,23 @@ pub fn get_inventory_movements(
     }
   }
 
+  dq
+}
+
+#[tauri::command]
+pub fn get_inventory_movements(
+  app_handle: AppHandle,
+  db_state: State<'_, Mutex<Connection>>,
+  page: i64,
+  page_size: i64,
+  sort_by: Option<String>,
+  sort_order: Option<String>,
+  filters: Option<MovementsFilter>,
+) -> Result<PaginatedResponse<InventoryMovementView>, String> {
+  let conn = db_state.lock().unwrap();
+  let app_dir = app_handle.path().app_data_dir().unwrap();
+
+  let dq = movements_filter_query(filters.as_ref());
   let where_clause = dq.sql_parts.join(" AND ");
 
   let count_sql = format!(
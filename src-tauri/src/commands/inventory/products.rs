@@ -1,3 +1,6 @@
+use crate::barcode_utils::validate_product_barcode;
+use crate::commands::inventory::barcodes::allocate_internal_barcodes;
+use crate::commands::settings::business::fetch_business_settings;
 use crate::database::get_current_store_id;
 use crate::database::DynamicQuery;
 use rusqlite::types::ToSql;
@@ -12,23 +15,23 @@ use std::fmt;
 
 #[derive(Serialize)]
 pub struct ProductView {
-    id: String,
-    code: String,
-    barcode: Option<String>,
-    name: String,
-    description: Option<String>,
-    category_id: String,
-    category_name: Option<String>,
-    category_color: Option<String>,
-    retail_price: f64,
-    wholesale_price: f64,
-    purchase_price: f64,
-    stock: i64,
-    min_stock: i64,
-    image_url: Option<String>,
-    is_active: bool,
-    tags: Vec<String>,
-    created_at: String,
+    pub(crate) id: String,
+    pub(crate) code: String,
+    pub(crate) barcode: Option<String>,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) category_id: String,
+    pub(crate) category_name: Option<String>,
+    pub(crate) category_color: Option<String>,
+    pub(crate) retail_price: f64,
+    pub(crate) wholesale_price: f64,
+    pub(crate) purchase_price: f64,
+    pub(crate) stock: i64,
+    pub(crate) min_stock: i64,
+    pub(crate) image_url: Option<String>,
+    pub(crate) is_active: bool,
+    pub(crate) tags: Vec<String>,
+    pub(crate) created_at: String,
 }
 
 #[derive(Serialize)]
@@ -57,6 +60,10 @@ pub struct CreateProductPayload {
     pub user_id: Option<String>,
     pub is_active: bool,
     pub tags: Vec<String>,
+    /// Assign an in-store barcode when none is given; `None` follows the
+    /// business setting
+    #[serde(default)]
+    pub generate_barcode: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -565,6 +572,11 @@ pub async fn create_product(
 
     if let Some(barcode) = &payload.barcode {
         if !barcode.trim().is_empty() {
+            validate_product_barcode(barcode).map_err(|message| InventoryError {
+                code: "INVALID_BARCODE".to_string(),
+                message,
+            })?;
+
             let barcode_exists: bool = conn
         .query_row(
           "SELECT EXISTS(SELECT 1 FROM products WHERE barcode = ? AND deleted_at IS NULL)",
@@ -595,6 +607,27 @@ pub async fn create_product(
         message: e,
     })?;
 
+    let has_barcode = payload
+        .barcode
+        .as_ref()
+        .is_some_and(|b| !b.trim().is_empty());
+    let generate_barcode = !has_barcode
+        && payload.generate_barcode.unwrap_or_else(|| {
+            fetch_business_settings(&conn)
+                .map(|s| s.auto_internal_barcode)
+                .unwrap_or(false)
+        });
+    let barcode = if generate_barcode {
+        allocate_internal_barcodes(&conn, 1)
+            .map_err(|e| InventoryError {
+                code: "BARCODE_GENERATION_ERROR".to_string(),
+                message: e,
+            })?
+            .pop()
+    } else {
+        payload.barcode.clone()
+    };
+
     let tx = conn.transaction().map_err(|e| InventoryError {
         code: "DB_TX_ERROR".to_string(),
         message: format!("Error iniciando transacción: {}", e),
@@ -619,7 +652,7 @@ pub async fn create_product(
             .execute(rusqlite::params![
                 &product_id,
                 &payload.code,
-                &payload.barcode,
+                &barcode,
                 &payload.name,
                 &payload.description,
                 &payload.category_id,
@@ -742,7 +775,7 @@ pub async fn create_product(
     Ok(Product {
         code: payload.code,
         id: product_id,
-        barcode: payload.barcode,
+        barcode,
         name: payload.name,
         category_id: payload.category_id,
         retail_price: payload.retail_price,
@@ -800,6 +833,11 @@ pub async fn update_product(
     if current_barcode != payload.barcode {
         if let Some(new_barcode) = &payload.barcode {
             if !new_barcode.trim().is_empty() {
+                validate_product_barcode(new_barcode).map_err(|message| InventoryError {
+                    code: "INVALID_BARCODE".to_string(),
+                    message,
+                })?;
+
                 let exists: bool = conn.query_row(
           "SELECT EXISTS(SELECT 1 FROM products WHERE barcode = ? AND id != ? AND deleted_at IS NULL)",
           [new_barcode, &payload.id],
@@ -1397,7 +1435,17 @@ pub fn get_all_filtered_products(
         .app_data_dir()
         .expect("No se pudo obtener el directorio de datos");
 
-    let store_id = get_current_store_id(&conn)?;
+    query_filtered_products(&conn, &app_dir, search, filters)
+}
+
+/// Every product matching the search and filters, ordered by name.
+pub(crate) fn query_filtered_products(
+    conn: &Connection,
+    app_dir: &std::path::Path,
+    search: Option<String>,
+    filters: Option<ProductFilters>,
+) -> Result<Vec<ProductView>, String> {
+    let store_id = get_current_store_id(conn)?;
     let mut dq = DynamicQuery::new();
     dq.add_condition("p.deleted_at IS NULL");
 
@@ -1,10 +1,15 @@
 pub mod auth;
 pub mod backup;
 pub mod cash_register;
+pub mod customer_display;
 pub mod customers;
+pub mod exports;
 pub mod inventory;
 pub mod kit_utils;
+pub mod print_queue;
 pub mod printer;
+pub mod printer_status;
+pub mod report_delivery;
 pub mod reports;
 pub mod sales;
 pub mod settings;
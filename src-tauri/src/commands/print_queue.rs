@@ -0,0 +1,355 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::commands::settings::hardware::load_settings;
+use crate::printer_transport::{open_transport, PrinterTransport};
+
+const MAX_ATTEMPTS: i64 = 5;
+const RETRY_DELAYS_SECS: [i64; 4] = [30, 60, 120, 300];
+const WORKER_INTERVAL: Duration = Duration::from_secs(15);
+const KEEP_PRINTED_DAYS: i64 = 7;
+
+/// Serializes jobs so tickets never interleave on the printer.
+pub(crate) static PRINT_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Serialize)]
+pub struct PrintJob {
+    pub id: i64,
+    pub document_type: String, // 'sale', 'voucher', 'exchange', 'payment', 'shift', 'daily_report'
+    pub reference: Option<String>,
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<String>,
+    pub created_at: String,
+    pub printed_at: Option<String>,
+    pub size_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrintJobEvent {
+    pub id: i64,
+    pub document_type: String,
+    pub reference: Option<String>,
+    pub attempts: i64,
+    pub error: Option<String>,
+    pub will_retry: bool,
+}
+
+const PRINT_JOB_SELECT_SQL: &str =
+    "SELECT id, document_type, reference, status, attempts, last_error,
+            next_attempt_at, created_at, printed_at, length(payload)
+     FROM print_jobs";
+
+fn print_job_from_row(row: &rusqlite::Row) -> rusqlite::Result<PrintJob> {
+    Ok(PrintJob {
+        id: row.get(0)?,
+        document_type: row.get(1)?,
+        reference: row.get(2)?,
+        status: row.get(3)?,
+        attempts: row.get(4)?,
+        last_error: row.get(5)?,
+        next_attempt_at: row.get(6)?,
+        created_at: row.get(7)?,
+        printed_at: row.get(8)?,
+        size_bytes: row.get(9)?,
+    })
+}
+
+fn now_str() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Queues a rendered document and prints it right away on `printer`.
+/// On failure the job stays queued for the worker and the error is returned.
+pub fn submit_job(
+    app_handle: &tauri::AppHandle,
+    printer: &dyn PrinterTransport,
+    document_type: &str,
+    reference: Option<&str>,
+    data: Vec<u8>,
+) -> Result<(), String> {
+    let job_id = {
+        let db: State<Mutex<Connection>> = app_handle.state();
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO print_jobs (document_type, reference, payload, status, created_at)
+             VALUES (?1, ?2, ?3, 'printing', ?4)",
+            params![document_type, reference, data, now_str()],
+        )
+        .map_err(|e| format!("Error registrando trabajo de impresión: {}", e))?;
+        conn.last_insert_rowid()
+    };
+
+    run_job(app_handle, job_id, printer, &data)
+}
+
+/// Sends a claimed ('printing') job and records the outcome.
+fn run_job(
+    app_handle: &tauri::AppHandle,
+    job_id: i64,
+    printer: &dyn PrinterTransport,
+    data: &[u8],
+) -> Result<(), String> {
+    let result = {
+        let _guard = PRINT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        printer.send(data)
+    };
+
+    let db: State<Mutex<Connection>> = app_handle.state();
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let now = now_str();
+
+    let (document_type, reference, attempts): (String, Option<String>, i64) = conn
+        .query_row(
+            "UPDATE print_jobs SET attempts = attempts + 1 WHERE id = ?1
+             RETURNING document_type, reference, attempts",
+            [job_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    match result {
+        Ok(()) => {
+            conn.execute(
+                "UPDATE print_jobs
+                 SET status = 'printed', printed_at = ?1, last_error = NULL, next_attempt_at = NULL
+                 WHERE id = ?2",
+                params![now, job_id],
+            )
+            .map_err(|e| e.to_string())?;
+            drop(conn);
+
+            let _ = app_handle.emit(
+                "print-job-printed",
+                PrintJobEvent {
+                    id: job_id,
+                    document_type,
+                    reference,
+                    attempts,
+                    error: None,
+                    will_retry: false,
+                },
+            );
+            Ok(())
+        }
+        Err(error) => {
+            let will_retry = attempts < MAX_ATTEMPTS;
+            let next_attempt_at = if will_retry {
+                let delay = RETRY_DELAYS_SECS[((attempts - 1) as usize).min(RETRY_DELAYS_SECS.len() - 1)];
+                Some(
+                    (chrono::Local::now() + chrono::Duration::seconds(delay))
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string(),
+                )
+            } else {
+                None
+            };
+
+            conn.execute(
+                "UPDATE print_jobs SET status = ?1, last_error = ?2, next_attempt_at = ?3 WHERE id = ?4",
+                params![
+                    if will_retry { "pending" } else { "failed" },
+                    error,
+                    next_attempt_at,
+                    job_id
+                ],
+            )
+            .map_err(|e| e.to_string())?;
+            drop(conn);
+
+            let _ = app_handle.emit(
+                "print-job-failed",
+                PrintJobEvent {
+                    id: job_id,
+                    document_type,
+                    reference,
+                    attempts,
+                    error: Some(error.clone()),
+                    will_retry,
+                },
+            );
+
+            if will_retry {
+                Err(format!("{} (se reintentará automáticamente)", error))
+            } else {
+                Err(error)
+            }
+        }
+    }
+}
+
+/// Claims a job for printing; false if someone else already took it.
+fn claim_job(conn: &Connection, job_id: i64, from_statuses: &str) -> Result<bool, String> {
+    let rows = conn
+        .execute(
+            &format!(
+                "UPDATE print_jobs SET status = 'printing' WHERE id = ?1 AND status IN ({})",
+                from_statuses
+            ),
+            [job_id],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(rows > 0)
+}
+
+fn process_due_jobs(app_handle: &AppHandle) {
+    let Some(state) = app_handle.try_state::<Mutex<Connection>>() else {
+        return;
+    };
+
+    let due: Vec<(i64, Vec<u8>)> = match state.inner().lock() {
+        Ok(conn) => {
+            let _ = conn.execute(
+                "DELETE FROM print_jobs
+                 WHERE status IN ('printed', 'discarded')
+                   AND created_at < datetime('now', 'localtime', ?1)",
+                [format!("-{} days", KEEP_PRINTED_DAYS)],
+            );
+            conn.prepare(
+                "SELECT id, payload FROM print_jobs
+                 WHERE status = 'pending' AND (next_attempt_at IS NULL OR next_attempt_at <= ?1)
+                 ORDER BY id ASC",
+            )
+            .and_then(|mut stmt| {
+                stmt.query_map([now_str()], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<rusqlite::Result<Vec<_>>>()
+            })
+            .unwrap_or_default()
+        }
+        Err(_) => return,
+    };
+
+    if due.is_empty() {
+        return;
+    }
+
+    let config = load_settings(app_handle.clone()).unwrap_or_default();
+    let Ok(Some(printer)) = open_transport(&config) else {
+        return;
+    };
+
+    for (job_id, payload) in due {
+        let claimed = match state.inner().lock() {
+            Ok(conn) => claim_job(&conn, job_id, "'pending'").unwrap_or(false),
+            Err(_) => false,
+        };
+        if !claimed {
+            continue;
+        }
+        // Printer still down: leave the rest for the next round, in order
+        if run_job(app_handle, job_id, printer.as_ref(), &payload).is_err() {
+            break;
+        }
+    }
+}
+
+/// Retries queued print jobs in the background.
+pub fn start_print_worker(app_handle: AppHandle) {
+    // Jobs interrupted by a crash or shutdown go back to the queue
+    if let Some(state) = app_handle.try_state::<Mutex<Connection>>() {
+        if let Ok(conn) = state.inner().lock() {
+            let _ = conn.execute(
+                "UPDATE print_jobs SET status = 'pending', next_attempt_at = NULL WHERE status = 'printing'",
+                [],
+            );
+        }
+    }
+
+    std::thread::spawn(move || loop {
+        std::thread::sleep(WORKER_INTERVAL);
+        process_due_jobs(&app_handle);
+    });
+}
+
+/// Queued jobs; defaults to the ones still needing attention (pending and failed).
+#[tauri::command]
+pub fn get_print_jobs(
+    db: State<Mutex<Connection>>,
+    status: Option<Vec<String>>,
+) -> Result<Vec<PrintJob>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+
+    let statuses = status
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| vec!["pending".to_string(), "failed".to_string()]);
+    let placeholders = statuses.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+    let sql = format!(
+        "{} WHERE status IN ({}) ORDER BY id DESC",
+        PRINT_JOB_SELECT_SQL, placeholders
+    );
+
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(statuses.iter()), print_job_from_row)
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// Prints a pending or failed job now.
+#[tauri::command]
+pub async fn retry_print_job(app_handle: AppHandle, job_id: i64) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let config = load_settings(app_handle.clone())?;
+        let printer = open_transport(&config)?
+            .ok_or("No hay una impresora configurada".to_string())?;
+
+        let payload: Vec<u8> = {
+            let db: State<Mutex<Connection>> = app_handle.state();
+            let conn = db.lock().map_err(|e| e.to_string())?;
+            let payload: Vec<u8> = conn
+                .query_row(
+                    "SELECT payload FROM print_jobs WHERE id = ?1",
+                    [job_id],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|e| e.to_string())?
+                .ok_or("Trabajo de impresión no encontrado".to_string())?;
+
+            if !claim_job(&conn, job_id, "'pending', 'failed'")? {
+                return Err("El trabajo ya fue impreso o descartado.".to_string());
+            }
+            payload
+        };
+
+        run_job(&app_handle, job_id, printer.as_ref(), &payload)
+    })
+    .await
+    .map_err(|e| format!("Error de ejecución: {}", e))?
+}
+
+/// Sends every failed job back to the queue for the worker.
+#[tauri::command]
+pub fn retry_failed_print_jobs(db: State<Mutex<Connection>>) -> Result<usize, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE print_jobs SET status = 'pending', attempts = 0, next_attempt_at = NULL
+         WHERE status = 'failed'",
+        [],
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn discard_print_job(db: State<Mutex<Connection>>, job_id: i64) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let rows = conn
+        .execute(
+            "UPDATE print_jobs SET status = 'discarded', next_attempt_at = NULL
+             WHERE id = ?1 AND status IN ('pending', 'failed')",
+            [job_id],
+        )
+        .map_err(|e| e.to_string())?;
+
+    if rows == 0 {
+        return Err("Solo se pueden descartar trabajos pendientes o fallidos.".to_string());
+    }
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use crate::commands::settings::business::BusinessSettings;
 use crate::commands::settings::hardware::HardwareConfig;
+use crate::printer_transport::open_transport;
 use crate::printer_utils;
-use printers::common::base::job::PrinterJobOptions;
 use tauri::{command, AppHandle};
 
 #[command]
@@ -15,11 +15,10 @@ pub async fn test_print_ticket(
     
     // async workers
     tauri::async_runtime::spawn_blocking(move || {
-        let printers_list = printers::get_printers();
-        let printer = printers_list
-            .iter()
-            .find(|p| p.name == printer_name)
-            .ok_or_else(|| format!("Impresora '{}' no encontrada", printer_name))?;
+        let mut transport_config = hardware_config.clone();
+        transport_config.printer_name = Some(printer_name);
+        let printer = open_transport(&transport_config)?
+            .ok_or("No hay una impresora configurada".to_string())?;
 
         let mut job_content = Vec::new();
 
@@ -81,8 +80,8 @@ pub async fn test_print_ticket(
 
         // Send to Printer
         printer
-            .print(&job_content, PrinterJobOptions::none())
-            .map_err(|e| format!("Error imprimiendo: {:?}", e))?;
+            .send(&job_content)
+            .map_err(|e| format!("Error imprimiendo: {}", e))?;
 
         Ok("Ticket enviado correctamente".to_string())
     }).await.map_err(|e| format!("Error de ejecución: {}", e))?
@@ -101,6 +100,21 @@ pub async fn print_sale_ticket(
     .map(|_| "Ticket enviado a imprimir".to_string())
 }
 
+/// Renders a sale receipt as 'pdf' or 'png' and returns the file path.
+/// Works without a configured printer.
+#[command]
+pub async fn render_sale_receipt(
+    app_handle: AppHandle,
+    sale_id: String,
+    format: String,
+    printer_width: Option<u32>,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::receipt_document::render_sale_receipt_file(&app_handle, &sale_id, &format, printer_width)
+    }).await
+    .map_err(|e| format!("Error de ejecución: {}", e))?
+}
+
 #[command]
 pub async fn print_return_voucher(
     app_handle: AppHandle,
@@ -142,3 +156,64 @@ pub async fn print_payment_receipt(
     .map_err(|e| format!("Error en hilo de impresión: {}", e))?
     .map(|_| "Comprobante de abono enviado a imprimir".to_string())
 }
+
+#[command]
+pub async fn print_x_report(
+    app_handle: AppHandle,
+    date: Option<String>,
+    user_id: String,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        use tauri::Manager;
+        let report = crate::commands::cash_register::daily_report::get_x_report(
+            app_handle.state(),
+            date,
+            user_id,
+        )?;
+        crate::printer_utils::print_daily_report(app_handle, &report, false)
+    }).await
+    .map_err(|e| format!("Error en hilo de impresión: {}", e))?
+    .map(|_| "Reporte X enviado a imprimir".to_string())
+}
+
+/// Prints an issued Z report. Every print after the first is marked as a reprint.
+#[command]
+pub async fn print_z_report(
+    app_handle: AppHandle,
+    z_report_id: String,
+    user_id: String,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        use rusqlite::Connection;
+        use std::sync::Mutex;
+        use tauri::{Manager, State};
+
+        let (report, print_count) = {
+            let db: State<Mutex<Connection>> = app_handle.state();
+            let conn = db.lock().map_err(|e| e.to_string())?;
+            crate::commands::settings::permissions::require_permission(&conn, &user_id, "reports:view")?;
+            let report = crate::commands::cash_register::daily_report::fetch_z_report(&conn, &z_report_id)?;
+            let print_count: i64 = conn
+                .query_row(
+                    "SELECT print_count FROM z_reports WHERE id = ?1",
+                    [&z_report_id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            (report, print_count)
+        };
+
+        crate::printer_utils::print_daily_report(app_handle.clone(), &report, print_count > 0)?;
+
+        let db: State<Mutex<Connection>> = app_handle.state();
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE z_reports SET print_count = print_count + 1 WHERE id = ?1",
+            [&z_report_id],
+        )
+        .map_err(|e| e.to_string())?;
+        Ok::<(), String>(())
+    }).await
+    .map_err(|e| format!("Error en hilo de impresión: {}", e))?
+    .map(|_| "Reporte Z enviado a imprimir".to_string())
+}
@@ -0,0 +1,160 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::print_queue::PRINT_LOCK;
+use crate::commands::settings::hardware::{load_settings, HardwareConfig};
+use crate::printer_transport::{open_transport, PrinterStatus};
+
+const MONITOR_INTERVAL: Duration = Duration::from_secs(20);
+/// A cached status younger than this is trusted before a sale.
+const STATUS_MAX_AGE: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PrinterStatusReport {
+    pub transport: String,
+    pub destination: Option<String>,
+    /// False for the spooler, which cannot query the device
+    pub supported: bool,
+    pub status: Option<PrinterStatus>,
+    pub error: Option<String>,
+    pub checked_at: String,
+}
+
+static LAST_STATUS: Mutex<Option<(Instant, PrinterStatusReport)>> = Mutex::new(None);
+
+fn query_printer_status(config: &HardwareConfig) -> PrinterStatusReport {
+    let mut report = PrinterStatusReport {
+        transport: config.printer_transport.clone(),
+        destination: None,
+        supported: false,
+        status: None,
+        error: None,
+        checked_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+
+    let printer = match open_transport(config) {
+        Ok(Some(printer)) => printer,
+        Ok(None) => return report,
+        Err(e) => {
+            report.error = Some(e);
+            return report;
+        }
+    };
+    report.destination = Some(printer.describe());
+
+    // Don't talk to the printer in the middle of a job
+    let result = {
+        let _guard = PRINT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        printer.query_status()
+    };
+
+    match result {
+        Ok(Some(status)) => {
+            report.supported = true;
+            report.status = Some(status);
+        }
+        Ok(None) => {}
+        Err(e) => {
+            report.supported = true;
+            report.error = Some(e);
+        }
+    }
+    report
+}
+
+/// Queries the printer and caches the result; returns whether it changed.
+fn refresh_status(app_handle: &AppHandle) -> Result<(PrinterStatusReport, bool), String> {
+    let config = load_settings(app_handle.clone())?;
+    let report = query_printer_status(&config);
+
+    let mut last = LAST_STATUS.lock().map_err(|e| e.to_string())?;
+    let changed = last
+        .as_ref()
+        .map(|(_, previous)| {
+            previous.status != report.status
+                || previous.error != report.error
+                || previous.destination != report.destination
+        })
+        .unwrap_or(true);
+    *last = Some((Instant::now(), report.clone()));
+    Ok((report, changed))
+}
+
+/// Checks the printer before a sale that will print its ticket, using the
+/// status cached by the monitor only (never talks to the device).
+/// Returns a warning for the cashier, or an error when the hardware
+/// config asks to block sales while the printer has no paper.
+pub(crate) fn check_before_print(app_handle: &AppHandle) -> Result<Option<String>, String> {
+    let cached = LAST_STATUS
+        .lock()
+        .ok()
+        .and_then(|last| last.clone())
+        .filter(|(checked, _)| checked.elapsed() < STATUS_MAX_AGE)
+        .map(|(_, report)| report);
+
+    let Some(report) = cached else {
+        return Ok(None);
+    };
+
+    let Some(status) = report.status else {
+        return Ok(None);
+    };
+
+    if status.paper_out {
+        let block = load_settings(app_handle.clone())
+            .map(|config| config.block_sale_on_paper_out)
+            .unwrap_or(false);
+        if block {
+            return Err(
+                "La impresora no tiene papel. Cargue papel o desactive la impresión del ticket."
+                    .to_string(),
+            );
+        }
+        return Ok(Some(
+            "La impresora no tiene papel; el ticket se imprimirá al reponerlo.".to_string(),
+        ));
+    }
+    if !status.online {
+        return Ok(Some(
+            "La impresora no responde; el ticket quedará en cola.".to_string(),
+        ));
+    }
+    if status.cover_open {
+        return Ok(Some("La tapa de la impresora está abierta.".to_string()));
+    }
+    if status.error {
+        return Ok(Some("La impresora reporta un error.".to_string()));
+    }
+    if status.paper_near_end {
+        return Ok(Some("Queda poco papel en la impresora.".to_string()));
+    }
+    Ok(None)
+}
+
+/// Polls the printer periodically and emits `printer-status` when it changes.
+pub fn start_printer_monitor(app_handle: AppHandle) {
+    std::thread::spawn(move || loop {
+        if let Ok((report, changed)) = refresh_status(&app_handle) {
+            if changed && report.supported {
+                let _ = app_handle.emit("printer-status", &report);
+            }
+        }
+        std::thread::sleep(MONITOR_INTERVAL);
+    });
+}
+
+#[tauri::command]
+pub async fn get_printer_status(app_handle: AppHandle) -> Result<PrinterStatusReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (report, changed) = refresh_status(&app_handle)?;
+        if changed {
+            let _ = app_handle.emit("printer-status", &report);
+        }
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("Error de ejecución: {}", e))?
+}
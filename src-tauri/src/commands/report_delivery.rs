@@ -0,0 +1,1106 @@
+use std::fs;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use reqwest::header::AUTHORIZATION;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::commands::reports::{
+    get_low_stock_products, get_sales_report, get_top_selling_products, LowStockProduct,
+    SalesReport, TopSellingProduct,
+};
+use crate::commands::settings::business::fetch_business_settings;
+use crate::commands::settings::permissions::require_permission;
+
+const CONFIG_FILE: &str = "report_delivery.json";
+const MAX_ATTEMPTS: i64 = 5;
+const RETRY_BASE_MINUTES: i64 = 5;
+const TOP_SELLERS_LIMIT: i64 = 10;
+const LOW_STOCK_LIMIT: i64 = 50;
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Serializes delivery attempts between the scheduler and manual sends.
+static DELIVERY_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReportDeliveryConfig {
+    pub enabled: bool,
+    /// Local time of the daily send, HH:MM
+    pub send_time: String,
+    /// 'smtp' or 'webhook'
+    pub channel: String,
+    pub recipients: Vec<String>,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    /// 'none' (plain, for local relays), 'starttls' or 'tls'
+    pub smtp_security: String,
+    pub smtp_username: Option<String>,
+    /// Never sent to the UI. On save, `None` keeps the stored password and
+    /// an empty string clears it.
+    pub smtp_password: Option<String>,
+    pub smtp_from: String,
+    pub webhook_url: String,
+    /// Sent as `Authorization: Bearer <token>` when set. Stored like `smtp_password`.
+    pub webhook_token: Option<String>,
+    /// Set by `get_report_delivery_config` in place of the password
+    #[serde(skip_deserializing, skip_serializing_if = "std::ops::Not::not")]
+    pub has_smtp_password: bool,
+    #[serde(skip_deserializing, skip_serializing_if = "std::ops::Not::not")]
+    pub has_webhook_token: bool,
+}
+
+impl Default for ReportDeliveryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            send_time: "21:00".to_string(),
+            channel: "smtp".to_string(),
+            recipients: Vec::new(),
+            smtp_host: String::new(),
+            smtp_port: 587,
+            smtp_security: "starttls".to_string(),
+            smtp_username: None,
+            smtp_password: None,
+            smtp_from: String::new(),
+            webhook_url: String::new(),
+            webhook_token: None,
+            has_smtp_password: false,
+            has_webhook_token: false,
+        }
+    }
+}
+
+impl ReportDeliveryConfig {
+    /// Copy without the secrets, flagging which ones are stored.
+    fn masked(&self) -> Self {
+        Self {
+            smtp_password: None,
+            webhook_token: None,
+            has_smtp_password: self.smtp_password.as_deref().is_some_and(|p| !p.is_empty()),
+            has_webhook_token: self.webhook_token.as_deref().is_some_and(|t| !t.is_empty()),
+            ..self.clone()
+        }
+    }
+
+    /// Fills the secrets the UI left out (`None`) from `stored`; an empty
+    /// string clears them.
+    fn with_secrets_from(mut self, stored: &ReportDeliveryConfig) -> Self {
+        fn merge(sent: Option<String>, stored: &Option<String>) -> Option<String> {
+            match sent {
+                None => stored.clone(),
+                Some(s) if s.is_empty() => None,
+                Some(s) => Some(s),
+            }
+        }
+        self.smtp_password = merge(self.smtp_password.take(), &stored.smtp_password);
+        self.webhook_token = merge(self.webhook_token.take(), &stored.webhook_token);
+        self.has_smtp_password = false;
+        self.has_webhook_token = false;
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportDelivery {
+    pub id: i64,
+    pub report_date: String,
+    pub source: String,
+    pub channel: String,
+    pub destination: String,
+    pub subject: String,
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<String>,
+    pub sent_at: Option<String>,
+    pub created_at: String,
+}
+
+struct RenderedReport {
+    subject: String,
+    html: String,
+    csv: String,
+}
+
+fn now_str() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+// ── Config ──────────────────────────────────────────────────────
+
+pub fn load_report_delivery_config(app_handle: &AppHandle) -> Result<ReportDeliveryConfig, String> {
+    let app_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("No se pudo obtener el directorio de datos: {}", e))?;
+
+    let path = app_dir.join(CONFIG_FILE);
+    if !path.exists() {
+        return Ok(ReportDeliveryConfig::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Error leyendo archivo {:?}: {}", path, e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Error al leer configuración (JSON corrupto): {}", e))
+}
+
+fn validate_config(config: &ReportDeliveryConfig) -> Result<(), String> {
+    chrono::NaiveTime::parse_from_str(&config.send_time, "%H:%M")
+        .map_err(|_| "La hora de envío debe tener el formato HH:MM.".to_string())?;
+
+    match config.channel.as_str() {
+        "smtp" => {
+            if config.smtp_host.trim().is_empty() {
+                return Err("Configure el servidor SMTP.".to_string());
+            }
+            if !["none", "starttls", "tls"].contains(&config.smtp_security.as_str()) {
+                return Err(format!("Seguridad SMTP no válida: {}", config.smtp_security));
+            }
+            config
+                .smtp_from
+                .parse::<Mailbox>()
+                .map_err(|_| format!("Remitente inválido: {}", config.smtp_from))?;
+            if config.recipients.is_empty() {
+                return Err("Agregue al menos un destinatario.".to_string());
+            }
+            for recipient in &config.recipients {
+                recipient
+                    .parse::<Mailbox>()
+                    .map_err(|_| format!("Destinatario inválido: {}", recipient))?;
+            }
+        }
+        "webhook" => {
+            let url = config.webhook_url.trim();
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err("La URL del webhook debe comenzar con http:// o https://".to_string());
+            }
+        }
+        other => return Err(format!("Canal de envío no válido: {}", other)),
+    }
+    Ok(())
+}
+
+fn destination_for(config: &ReportDeliveryConfig) -> String {
+    match config.channel.as_str() {
+        "webhook" => config.webhook_url.trim().to_string(),
+        _ => config.recipients.join(", "),
+    }
+}
+
+// ── Rendering ───────────────────────────────────────────────────
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_csv(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn render_html(
+    store_name: &str,
+    currency: &str,
+    date: &str,
+    sales: &SalesReport,
+    top: &[TopSellingProduct],
+    low: &[LowStockProduct],
+) -> String {
+    let money = |v: f64| format!("{}{:.2}", currency, v);
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html><html><head><meta charset=\"utf-8\"></head>");
+    html.push_str("<body style=\"font-family:Arial,sans-serif;color:#222\">");
+    html.push_str(&format!(
+        "<h2>{}</h2><p>Reporte de ventas del {}</p>",
+        escape_html(store_name),
+        date
+    ));
+
+    html.push_str("<h3>Resumen</h3><table cellpadding=\"4\">");
+    html.push_str(&format!(
+        "<tr><td>Ventas netas</td><td align=\"right\"><b>{}</b></td></tr>",
+        money(sales.kpis.gross_sales)
+    ));
+    html.push_str(&format!(
+        "<tr><td>Utilidad</td><td align=\"right\">{}</td></tr>",
+        money(sales.kpis.net_profit)
+    ));
+    html.push_str(&format!(
+        "<tr><td>Transacciones</td><td align=\"right\">{}</td></tr>",
+        sales.kpis.transaction_count
+    ));
+    html.push_str(&format!(
+        "<tr><td>Ticket promedio</td><td align=\"right\">{}</td></tr></table>",
+        money(sales.kpis.average_ticket)
+    ));
+
+    if !sales.payment_methods.is_empty() {
+        html.push_str("<h3>Formas de pago</h3><table border=\"1\" cellspacing=\"0\" cellpadding=\"4\">");
+        html.push_str("<tr><th>Método</th><th>Ventas</th><th>Importe</th><th>%</th></tr>");
+        for pm in &sales.payment_methods {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td align=\"right\">{}</td><td align=\"right\">{}</td><td align=\"right\">{:.1}</td></tr>",
+                escape_html(&pm.method),
+                pm.sales_count,
+                money(pm.total_amount),
+                pm.percentage
+            ));
+        }
+        html.push_str("</table>");
+    }
+
+    html.push_str("<h3>Más vendidos</h3>");
+    if top.is_empty() {
+        html.push_str("<p>Sin ventas en el día.</p>");
+    } else {
+        html.push_str("<table border=\"1\" cellspacing=\"0\" cellpadding=\"4\">");
+        html.push_str("<tr><th>#</th><th>Producto</th><th>Código</th><th>Cantidad</th><th>Importe</th></tr>");
+        for p in top {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td align=\"right\">{:.2}</td><td align=\"right\">{}</td></tr>",
+                p.ranking,
+                escape_html(&p.product_name),
+                escape_html(&p.product_code),
+                p.quantity_sold,
+                money(p.total_revenue)
+            ));
+        }
+        html.push_str("</table>");
+    }
+
+    html.push_str("<h3>Stock bajo</h3>");
+    if low.is_empty() {
+        html.push_str("<p>No hay productos por debajo del mínimo.</p>");
+    } else {
+        html.push_str("<table border=\"1\" cellspacing=\"0\" cellpadding=\"4\">");
+        html.push_str("<tr><th>Producto</th><th>Código</th><th>Existencia</th><th>Mínimo</th><th>Sugerido</th></tr>");
+        for p in low {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td align=\"right\">{}</td><td align=\"right\">{}</td><td align=\"right\">{}</td></tr>",
+                escape_html(&p.product_name),
+                escape_html(&p.product_code),
+                p.current_stock,
+                p.minimum_stock,
+                p.suggested_order
+            ));
+        }
+        html.push_str("</table>");
+    }
+
+    html.push_str("</body></html>");
+    html
+}
+
+fn render_csv(
+    date: &str,
+    sales: &SalesReport,
+    top: &[TopSellingProduct],
+    low: &[LowStockProduct],
+) -> String {
+    let mut csv = String::new();
+
+    csv.push_str("Resumen\n");
+    csv.push_str("fecha,ventas_netas,utilidad,transacciones,ticket_promedio\n");
+    csv.push_str(&format!(
+        "{},{:.2},{:.2},{},{:.2}\n\n",
+        date,
+        sales.kpis.gross_sales,
+        sales.kpis.net_profit,
+        sales.kpis.transaction_count,
+        sales.kpis.average_ticket
+    ));
+
+    csv.push_str("Formas de pago\n");
+    csv.push_str("metodo,ventas,importe,porcentaje\n");
+    for pm in &sales.payment_methods {
+        csv.push_str(&format!(
+            "{},{},{:.2},{:.2}\n",
+            escape_csv(&pm.method),
+            pm.sales_count,
+            pm.total_amount,
+            pm.percentage
+        ));
+    }
+
+    csv.push_str("\nMas vendidos\n");
+    csv.push_str("ranking,producto,codigo,categoria,cantidad,importe\n");
+    for p in top {
+        csv.push_str(&format!(
+            "{},{},{},{},{:.3},{:.2}\n",
+            p.ranking,
+            escape_csv(&p.product_name),
+            escape_csv(&p.product_code),
+            escape_csv(&p.category_name),
+            p.quantity_sold,
+            p.total_revenue
+        ));
+    }
+
+    csv.push_str("\nStock bajo\n");
+    csv.push_str("producto,codigo,categoria,existencia,minimo,sugerido\n");
+    for p in low {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            escape_csv(&p.product_name),
+            escape_csv(&p.product_code),
+            escape_csv(&p.category_name),
+            p.current_stock,
+            p.minimum_stock,
+            p.suggested_order
+        ));
+    }
+
+    csv
+}
+
+/// Renders the day's sales report, top sellers and low stock.
+fn render_daily_report(app_handle: &AppHandle, date: &str) -> Result<RenderedReport, String> {
+    let from = format!("{} 00:00:00", date);
+    let to = format!("{} 23:59:59", date);
+
+    let sales = get_sales_report(app_handle.state(), from.clone(), to.clone(), None, None, None)?;
+    let top = get_top_selling_products(
+        app_handle.state(),
+        from,
+        to,
+        1,
+        TOP_SELLERS_LIMIT,
+        None,
+        None,
+        None,
+    )?
+    .data;
+    let low = get_low_stock_products(
+        app_handle.state(),
+        1,
+        LOW_STOCK_LIMIT,
+        None,
+        Some("current_stock".to_string()),
+        Some("asc".to_string()),
+    )?
+    .data;
+
+    let settings = {
+        let db: State<Mutex<Connection>> = app_handle.state();
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        fetch_business_settings(&conn).unwrap_or_default()
+    };
+
+    Ok(RenderedReport {
+        subject: format!("{} - Reporte de ventas {}", settings.store_name, date),
+        html: render_html(
+            &settings.store_name,
+            &settings.currency_symbol,
+            date,
+            &sales,
+            &top,
+            &low,
+        ),
+        csv: render_csv(date, &sales, &top, &low),
+    })
+}
+
+// ── Transports ──────────────────────────────────────────────────
+
+async fn send_email(
+    config: &ReportDeliveryConfig,
+    recipients: &[String],
+    report_date: &str,
+    report: &RenderedReport,
+) -> Result<(), String> {
+    let from = config
+        .smtp_from
+        .parse::<Mailbox>()
+        .map_err(|_| format!("Remitente inválido: {}", config.smtp_from))?;
+
+    let mut builder = Message::builder().from(from).subject(report.subject.clone());
+    for recipient in recipients {
+        let mailbox = recipient
+            .trim()
+            .parse::<Mailbox>()
+            .map_err(|_| format!("Destinatario inválido: {}", recipient))?;
+        builder = builder.to(mailbox);
+    }
+
+    let csv_type = ContentType::parse("text/csv; charset=utf-8").map_err(|e| e.to_string())?;
+    let message = builder
+        .multipart(
+            MultiPart::mixed()
+                .singlepart(SinglePart::html(report.html.clone()))
+                .singlepart(
+                    Attachment::new(format!("reporte_{}.csv", report_date))
+                        .body(report.csv.clone(), csv_type),
+                ),
+        )
+        .map_err(|e| format!("Error armando correo: {}", e))?;
+
+    let host = config.smtp_host.trim();
+    let transport = match config.smtp_security.as_str() {
+        "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+        "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+        _ => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)),
+    }
+    .map_err(|e| format!("Servidor SMTP inválido: {}", e))?
+    .port(config.smtp_port)
+    .timeout(Some(SEND_TIMEOUT));
+
+    let transport = match (&config.smtp_username, &config.smtp_password) {
+        (Some(user), Some(pass)) if !user.is_empty() => {
+            transport.credentials(Credentials::new(user.clone(), pass.clone()))
+        }
+        _ => transport,
+    };
+
+    transport
+        .build()
+        .send(message)
+        .await
+        .map_err(|e| format!("Error SMTP: {}", e))?;
+    Ok(())
+}
+
+async fn post_webhook(
+    config: &ReportDeliveryConfig,
+    url: &str,
+    report_date: &str,
+    report: &RenderedReport,
+) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(SEND_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut request = client.post(url).json(&serde_json::json!({
+        "report_date": report_date,
+        "subject": report.subject,
+        "html": report.html,
+        "csv": report.csv,
+    }));
+    if let Some(token) = config.webhook_token.as_deref().filter(|t| !t.is_empty()) {
+        request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Error de red: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("HTTP {}: {}", status, body));
+    }
+    Ok(())
+}
+
+async fn deliver(
+    config: &ReportDeliveryConfig,
+    channel: &str,
+    destination: &str,
+    report_date: &str,
+    report: &RenderedReport,
+) -> Result<(), String> {
+    match channel {
+        "smtp" => {
+            let recipients: Vec<String> = destination
+                .split(',')
+                .map(|r| r.trim().to_string())
+                .filter(|r| !r.is_empty())
+                .collect();
+            send_email(config, &recipients, report_date, report).await
+        }
+        "webhook" => post_webhook(config, destination, report_date, report).await,
+        other => Err(format!("Canal de envío no válido: {}", other)),
+    }
+}
+
+// ── Delivery log ────────────────────────────────────────────────
+
+const DELIVERY_SELECT_SQL: &str =
+    "SELECT id, report_date, source, channel, destination, subject, status, attempts,
+            last_error, next_attempt_at, sent_at, created_at
+     FROM report_deliveries";
+
+fn delivery_from_row(row: &rusqlite::Row) -> rusqlite::Result<ReportDelivery> {
+    Ok(ReportDelivery {
+        id: row.get(0)?,
+        report_date: row.get(1)?,
+        source: row.get(2)?,
+        channel: row.get(3)?,
+        destination: row.get(4)?,
+        subject: row.get(5)?,
+        status: row.get(6)?,
+        attempts: row.get(7)?,
+        last_error: row.get(8)?,
+        next_attempt_at: row.get(9)?,
+        sent_at: row.get(10)?,
+        created_at: row.get(11)?,
+    })
+}
+
+fn fetch_delivery(conn: &Connection, id: i64) -> Result<ReportDelivery, String> {
+    conn.query_row(
+        &format!("{} WHERE id = ?1", DELIVERY_SELECT_SQL),
+        [id],
+        delivery_from_row,
+    )
+    .map_err(|_| format!("Envío no encontrado: {}", id))
+}
+
+/// Renders the report and records a pending delivery for it.
+fn queue_delivery(
+    app_handle: &AppHandle,
+    config: &ReportDeliveryConfig,
+    report_date: &str,
+    source: &str,
+) -> Result<i64, String> {
+    let report = render_daily_report(app_handle, report_date)?;
+
+    let db: State<Mutex<Connection>> = app_handle.state();
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO report_deliveries (
+            report_date, source, channel, destination, subject, html, csv,
+            status, next_attempt_at, created_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'pending', ?8, ?8)",
+        params![
+            report_date,
+            source,
+            config.channel,
+            destination_for(config),
+            report.subject,
+            report.html,
+            report.csv,
+            now_str()
+        ],
+    )
+    .map_err(|e| format!("Error registrando envío: {}", e))?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// Sends one recorded delivery and books the result. Failures are rescheduled
+/// with exponential backoff until `MAX_ATTEMPTS` is reached.
+async fn attempt_delivery(
+    app_handle: &AppHandle,
+    config: &ReportDeliveryConfig,
+    id: i64,
+) -> Result<ReportDelivery, String> {
+    let _guard = DELIVERY_LOCK.lock().await;
+
+    let (channel, destination, report_date, report, status) = {
+        let db: State<Mutex<Connection>> = app_handle.state();
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        conn.query_row(
+            "SELECT channel, destination, report_date, subject, html, csv, status
+             FROM report_deliveries WHERE id = ?1",
+            [id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    RenderedReport {
+                        subject: row.get(3)?,
+                        html: row.get(4)?,
+                        csv: row.get(5)?,
+                    },
+                    row.get::<_, String>(6)?,
+                ))
+            },
+        )
+        .map_err(|_| format!("Envío no encontrado: {}", id))?
+    };
+
+    if status == "sent" {
+        let db: State<Mutex<Connection>> = app_handle.state();
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        return fetch_delivery(&conn, id);
+    }
+
+    let result = deliver(config, &channel, &destination, &report_date, &report).await;
+
+    let db: State<Mutex<Connection>> = app_handle.state();
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    match &result {
+        Ok(()) => {
+            conn.execute(
+                "UPDATE report_deliveries
+                 SET status = 'sent', attempts = attempts + 1, last_error = NULL,
+                     next_attempt_at = NULL, sent_at = ?1
+                 WHERE id = ?2",
+                params![now_str(), id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        Err(error) => {
+            let attempts: i64 = conn
+                .query_row(
+                    "SELECT attempts + 1 FROM report_deliveries WHERE id = ?1",
+                    [id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            let (status, next_attempt_at) = if attempts >= MAX_ATTEMPTS {
+                ("failed", None)
+            } else {
+                let delay = RETRY_BASE_MINUTES * (1 << (attempts - 1));
+                let next = chrono::Local::now() + chrono::Duration::minutes(delay);
+                ("pending", Some(next.format("%Y-%m-%d %H:%M:%S").to_string()))
+            };
+            conn.execute(
+                "UPDATE report_deliveries
+                 SET status = ?1, attempts = ?2, last_error = ?3, next_attempt_at = ?4
+                 WHERE id = ?5",
+                params![status, attempts, error, next_attempt_at, id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    let delivery = fetch_delivery(&conn, id)?;
+    drop(conn);
+
+    let event = if delivery.status == "sent" {
+        "report-delivered"
+    } else {
+        "report-delivery-failed"
+    };
+    let _ = app_handle.emit(event, delivery.id);
+
+    Ok(delivery)
+}
+
+fn scheduled_run_due(conn: &Connection, config: &ReportDeliveryConfig) -> bool {
+    let Ok(send_time) = chrono::NaiveTime::parse_from_str(&config.send_time, "%H:%M") else {
+        return false;
+    };
+    let now = chrono::Local::now();
+    if now.time() < send_time {
+        return false;
+    }
+
+    let today = now.format("%Y-%m-%d").to_string();
+    let already_queued: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM report_deliveries WHERE report_date = ?1 AND source = 'scheduled' LIMIT 1",
+            [today],
+            |row| row.get(0),
+        )
+        .optional()
+        .unwrap_or(None);
+    already_queued.is_none()
+}
+
+pub fn start_report_scheduler(app_handle: AppHandle) {
+    let handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+
+            let config = match load_report_delivery_config(&handle) {
+                Ok(config) => config,
+                Err(_) => continue,
+            };
+
+            let state: State<'_, Mutex<Connection>> = match handle.try_state() {
+                Some(s) => s,
+                None => continue,
+            };
+
+            // Queue today's report once the configured time has passed
+            if config.enabled && validate_config(&config).is_ok() {
+                let due = match state.inner().lock() {
+                    Ok(conn) => scheduled_run_due(&conn, &config),
+                    Err(_) => false,
+                };
+                if due {
+                    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+                    let _ = queue_delivery(&handle, &config, &today, "scheduled");
+                }
+            }
+
+            // Retry pending deliveries whose backoff has elapsed
+            let due_ids: Vec<i64> = match state.inner().lock() {
+                Ok(conn) => conn
+                    .prepare(
+                        "SELECT id FROM report_deliveries
+                         WHERE status = 'pending' AND (next_attempt_at IS NULL OR next_attempt_at <= ?1)
+                         ORDER BY id ASC",
+                    )
+                    .and_then(|mut stmt| {
+                        stmt.query_map([now_str()], |row| row.get(0))?
+                            .collect::<rusqlite::Result<Vec<i64>>>()
+                    })
+                    .unwrap_or_default(),
+                Err(_) => Vec::new(),
+            };
+
+            for id in due_ids {
+                let _ = attempt_delivery(&handle, &config, id).await;
+            }
+        }
+    });
+}
+
+// ── Commands ────────────────────────────────────────────────────
+
+/// Settings for the form; the SMTP password and webhook token are masked.
+#[tauri::command]
+pub fn get_report_delivery_config(
+    app_handle: AppHandle,
+    db: State<'_, Mutex<Connection>>,
+    user_id: String,
+) -> Result<ReportDeliveryConfig, String> {
+    {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        require_permission(&conn, &user_id, "business_settings:edit")?;
+    }
+    Ok(load_report_delivery_config(&app_handle)?.masked())
+}
+
+#[tauri::command]
+pub fn save_report_delivery_config(
+    app_handle: AppHandle,
+    db: State<'_, Mutex<Connection>>,
+    config: ReportDeliveryConfig,
+    user_id: String,
+) -> Result<(), String> {
+    {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        require_permission(&conn, &user_id, "business_settings:edit")?;
+    }
+    let config = config.with_secrets_from(&load_report_delivery_config(&app_handle)?);
+    if config.enabled {
+        validate_config(&config)?;
+    }
+
+    let app_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("No se pudo obtener el directorio de datos: {}", e))?;
+    if !app_dir.exists() {
+        fs::create_dir_all(&app_dir)
+            .map_err(|e| format!("Error al crear directorio de datos: {}", e))?;
+    }
+
+    let path = app_dir.join(CONFIG_FILE);
+    let json = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Error al serializar configuración: {}", e))?;
+    fs::write(&path, json).map_err(|e| {
+        format!(
+            "Error al escribir archivo de configuración en {:?}: {}",
+            path, e
+        )
+    })
+}
+
+/// Sends today's report with the given (unsaved) settings without recording it.
+/// Meant for checking the setup, e.g. against a local SMTP catcher or request bin.
+#[tauri::command]
+pub async fn test_report_delivery(
+    app_handle: AppHandle,
+    config: ReportDeliveryConfig,
+    user_id: String,
+) -> Result<String, String> {
+    {
+        let db: State<Mutex<Connection>> = app_handle.state();
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        require_permission(&conn, &user_id, "business_settings:edit")?;
+    }
+    let config = config.with_secrets_from(&load_report_delivery_config(&app_handle)?);
+    validate_config(&config)?;
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let report = render_daily_report(&app_handle, &today)?;
+    deliver(
+        &config,
+        &config.channel,
+        &destination_for(&config),
+        &today,
+        &report,
+    )
+    .await?;
+
+    Ok(format!("Reporte de prueba enviado a {}", destination_for(&config)))
+}
+
+/// Renders and sends the report for a date right away (today by default).
+#[tauri::command]
+pub async fn send_daily_report_now(
+    app_handle: AppHandle,
+    date: Option<String>,
+    user_id: String,
+) -> Result<ReportDelivery, String> {
+    {
+        let db: State<Mutex<Connection>> = app_handle.state();
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        require_permission(&conn, &user_id, "reports:export")?;
+    }
+
+    let config = load_report_delivery_config(&app_handle)?;
+    validate_config(&config)?;
+
+    let report_date = match date.filter(|d| !d.trim().is_empty()) {
+        Some(d) => chrono::NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d")
+            .map_err(|_| format!("Fecha inválida: {}", d))?
+            .format("%Y-%m-%d")
+            .to_string(),
+        None => chrono::Local::now().format("%Y-%m-%d").to_string(),
+    };
+
+    let id = queue_delivery(&app_handle, &config, &report_date, "manual")?;
+    attempt_delivery(&app_handle, &config, id).await
+}
+
+#[tauri::command]
+pub fn get_report_deliveries(
+    db: State<'_, Mutex<Connection>>,
+    limit: Option<i64>,
+    user_id: String,
+) -> Result<Vec<ReportDelivery>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    require_permission(&conn, &user_id, "reports:export")?;
+
+    let mut stmt = conn
+        .prepare(&format!("{} ORDER BY id DESC LIMIT ?1", DELIVERY_SELECT_SQL))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([limit.unwrap_or(50)], delivery_from_row)
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+/// Retries a pending or failed delivery immediately, restarting its attempt count.
+#[tauri::command]
+pub async fn retry_report_delivery(
+    app_handle: AppHandle,
+    delivery_id: i64,
+    user_id: String,
+) -> Result<ReportDelivery, String> {
+    {
+        let db: State<Mutex<Connection>> = app_handle.state();
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        require_permission(&conn, &user_id, "reports:export")?;
+
+        let delivery = fetch_delivery(&conn, delivery_id)?;
+        if delivery.status == "sent" {
+            return Err("Este reporte ya fue enviado.".to_string());
+        }
+        conn.execute(
+            "UPDATE report_deliveries SET status = 'pending', attempts = 0, next_attempt_at = NULL WHERE id = ?1",
+            [delivery_id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    let config = load_report_delivery_config(&app_handle)?;
+    attempt_delivery(&app_handle, &config, delivery_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    fn report() -> RenderedReport {
+        RenderedReport {
+            subject: "Reporte diario 2026-10-18".to_string(),
+            html: "<p>Ventas</p>".to_string(),
+            csv: "fecha,total\n2026-10-18,100.00\n".to_string(),
+        }
+    }
+
+    /// Minimal SMTP server that accepts one message and returns the transcript.
+    fn smtp_stand_in() -> (u16, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut transcript = String::new();
+            writer.write_all(b"220 stand-in ESMTP\r\n").unwrap();
+
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                    break;
+                }
+                transcript.push_str(&line);
+                let command = line.to_ascii_uppercase();
+                if command.starts_with("EHLO") || command.starts_with("HELO") {
+                    writer.write_all(b"250 stand-in\r\n").unwrap();
+                } else if command.starts_with("DATA") {
+                    writer.write_all(b"354 end with .\r\n").unwrap();
+                    loop {
+                        let mut data = String::new();
+                        if reader.read_line(&mut data).unwrap_or(0) == 0 || data == ".\r\n" {
+                            break;
+                        }
+                        transcript.push_str(&data);
+                    }
+                    writer.write_all(b"250 queued\r\n").unwrap();
+                } else if command.starts_with("QUIT") {
+                    writer.write_all(b"221 bye\r\n").unwrap();
+                    break;
+                } else {
+                    writer.write_all(b"250 OK\r\n").unwrap();
+                }
+            }
+            transcript
+        });
+        (port, handle)
+    }
+
+    /// Minimal HTTP server that answers one request with `status` and
+    /// returns the raw request.
+    fn http_stand_in(status: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/reportes", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+                request.push_str(&line);
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            request.push_str(&String::from_utf8_lossy(&body));
+
+            write!(writer, "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status).unwrap();
+            request
+        });
+        (url, handle)
+    }
+
+    fn smtp_config(port: u16) -> ReportDeliveryConfig {
+        ReportDeliveryConfig {
+            enabled: true,
+            channel: "smtp".to_string(),
+            recipients: vec!["gerencia@example.com".to_string()],
+            smtp_host: "127.0.0.1".to_string(),
+            smtp_port: port,
+            smtp_security: "none".to_string(),
+            smtp_from: "Tienda <caja@example.com>".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn email_reaches_smtp_server_with_csv_attachment() {
+        let (port, server) = smtp_stand_in();
+        let config = smtp_config(port);
+
+        deliver(&config, "smtp", &destination_for(&config), "2026-10-18", &report())
+            .await
+            .unwrap();
+
+        let transcript = server.join().unwrap();
+        assert!(transcript.contains("MAIL FROM:<caja@example.com>"));
+        assert!(transcript.contains("RCPT TO:<gerencia@example.com>"));
+        assert!(transcript.contains("Subject: Reporte diario 2026-10-18"));
+        assert!(transcript.contains("reporte_2026-10-18.csv"));
+    }
+
+    #[tokio::test]
+    async fn webhook_posts_report_with_bearer_token() {
+        let (url, server) = http_stand_in("200 OK");
+        let config = ReportDeliveryConfig {
+            channel: "webhook".to_string(),
+            webhook_url: url.clone(),
+            webhook_token: Some("token-secreto".to_string()),
+            ..Default::default()
+        };
+
+        deliver(&config, "webhook", &url, "2026-10-18", &report()).await.unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /reportes HTTP/1.1"));
+        assert!(request.to_ascii_lowercase().contains("authorization: bearer token-secreto"));
+        assert!(request.contains("\"report_date\":\"2026-10-18\""));
+    }
+
+    #[tokio::test]
+    async fn webhook_error_status_fails_the_delivery() {
+        let (url, server) = http_stand_in("500 Internal Server Error");
+        let config = ReportDeliveryConfig {
+            channel: "webhook".to_string(),
+            webhook_url: url.clone(),
+            ..Default::default()
+        };
+
+        let result = deliver(&config, "webhook", &url, "2026-10-18", &report()).await;
+        server.join().unwrap();
+        assert!(result.unwrap_err().starts_with("HTTP 500"));
+    }
+
+    #[test]
+    fn masked_config_hides_secrets() {
+        let config = ReportDeliveryConfig {
+            smtp_password: Some("secreto".to_string()),
+            webhook_token: Some("token".to_string()),
+            ..Default::default()
+        };
+
+        let masked = config.masked();
+        assert_eq!(masked.smtp_password, None);
+        assert_eq!(masked.webhook_token, None);
+        assert!(masked.has_smtp_password);
+        assert!(masked.has_webhook_token);
+
+        let json = serde_json::to_string(&masked).unwrap();
+        assert!(!json.contains("secreto"));
+        assert!(json.contains("\"hasSmtpPassword\":true"));
+    }
+
+    #[test]
+    fn saving_without_password_keeps_the_stored_one() {
+        let stored = ReportDeliveryConfig {
+            smtp_password: Some("secreto".to_string()),
+            webhook_token: Some("token".to_string()),
+            ..Default::default()
+        };
+
+        let kept = stored.masked().with_secrets_from(&stored);
+        assert_eq!(kept.smtp_password.as_deref(), Some("secreto"));
+        assert_eq!(kept.webhook_token.as_deref(), Some("token"));
+
+        let changed = ReportDeliveryConfig {
+            smtp_password: Some("nuevo".to_string()),
+            webhook_token: Some(String::new()),
+            ..Default::default()
+        }
+        .with_secrets_from(&stored);
+        assert_eq!(changed.smtp_password.as_deref(), Some("nuevo"));
+        assert_eq!(changed.webhook_token, None);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::commands::settings::permissions::require_permission;
 use crate::database::get_current_store_id;
 use rusqlite::{params, Connection, Result};
 use serde::{Deserialize, Serialize};
@@ -35,11 +36,59 @@ pub struct CategoryDataPoint {
     pub color: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PaymentMethodDataPoint {
+    pub method: String,
+    pub total_amount: f64,
+    pub sales_count: i64,
+    pub percentage: f64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SalesReport {
     pub kpis: ReportKpis,
     pub sales_chart: Vec<ChartDataPoint>,
     pub category_chart: Vec<CategoryDataPoint>,
+    pub payment_methods: Vec<PaymentMethodDataPoint>,
+    pub comparison: Option<SalesComparison>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KpiDelta {
+    pub current: f64,
+    pub previous: f64,
+    pub absolute: f64,
+    pub percent: Option<f64>, // None cuando el periodo anterior es 0
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KpiDeltas {
+    pub gross_sales: KpiDelta,
+    pub net_profit: KpiDelta,
+    pub transaction_count: KpiDelta,
+    pub average_ticket: KpiDelta,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductMover {
+    pub product_id: String,
+    pub product_name: String,
+    pub product_code: String,
+    pub current_quantity: f64,
+    pub previous_quantity: f64,
+    pub quantity_delta: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SalesComparison {
+    pub from_date: String,
+    pub to_date: String,
+    pub kpis: ReportKpis,
+    pub sales_chart: Vec<ChartDataPoint>,
+    pub category_chart: Vec<CategoryDataPoint>,
+    pub deltas: KpiDeltas,
+    pub top_gainers: Vec<ProductMover>,
+    pub top_losers: Vec<ProductMover>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -89,6 +138,85 @@ pub struct LowStockProduct {
     pub retail_price: f64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CashierPerformance {
+    pub user_id: String,
+    pub user_name: String,
+    pub user_avatar: Option<String>,
+    pub sales_count: i64,
+    pub revenue: f64,
+    pub average_ticket: f64,
+    pub items_per_ticket: f64,
+    pub discount_count: i64,
+    pub discount_total: f64,
+    pub cancellations_count: i64,
+    pub cancellations_total: f64,
+    pub returns_count: i64,
+    pub returns_total: f64,
+    pub shifts_closed: i64,
+    pub discrepancy_count: i64,
+    pub discrepancy_total: f64,     // neto: sobrante (+) / faltante (-)
+    pub discrepancy_absolute: f64,
+    pub voids_count: i64,
+    pub voids_total: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiscountBreakdownRow {
+    pub key: String,
+    pub label: String,
+    pub manual: f64,
+    pub promotion: f64,
+    pub kit: f64,
+    pub wholesale: f64,
+    pub total_discount: f64,
+    pub net_revenue: f64,
+    pub discount_rate: f64, // % sobre el valor a precio de lista
+    pub sales_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiscountAnalysis {
+    pub totals: DiscountBreakdownRow,
+    pub by_user: Vec<DiscountBreakdownRow>,
+    pub by_product: Vec<DiscountBreakdownRow>,
+    pub by_period: Vec<DiscountBreakdownRow>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ReorderParams {
+    pub window_days: Option<i64>,       // ventana de ventas (default 30)
+    pub target_cover_days: Option<i64>, // cobertura deseada tras recibir (default 14)
+    pub lead_time_days: Option<i64>,    // tiempo de entrega del proveedor (default 3)
+    pub category_ids: Option<Vec<String>>,
+    pub include_all: Option<bool>,      // incluir productos sin pedido sugerido
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReorderSuggestion {
+    pub product_id: String,
+    pub product_name: String,
+    pub product_code: String,
+    pub category_name: String,
+    pub current_stock: i64,
+    pub minimum_stock: i64,
+    pub units_sold: f64,
+    pub daily_average: f64,
+    pub days_of_cover: Option<f64>, // None si no hubo ventas
+    pub suggested_order: i64,
+    pub purchase_price: f64,
+    pub estimated_cost: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HeatmapCell {
+    pub weekday: i64, // 0 = domingo ... 6 = sábado
+    pub hour: i64,
+    pub sales_count: i64,
+    pub revenue: f64,
+    pub average_ticket: f64,
+}
+
 fn fetch_kpis(conn: &Connection, from_date: &str, to_date: &str) -> Result<ReportKpis, String> {
     let sql = r#"
         SELECT 
@@ -243,105 +371,282 @@ fn fetch_categories(
         .map_err(|e| e.to_string())
 }
 
+fn fetch_payment_methods(
+    conn: &Connection,
+    from_date: &str,
+    to_date: &str,
+) -> Result<Vec<PaymentMethodDataPoint>, String> {
+    let sql = r#"
+        SELECT 
+            method,
+            method_total,
+            sales_count,
+            ROUND(
+                CASE 
+                    WHEN SUM(method_total) OVER() > 0 
+                    THEN (method_total * 100.0 / SUM(method_total) OVER())
+                    ELSE 0.0
+                END,
+            2) as percentage
+        FROM (
+            SELECT 
+                sp.method,
+                COALESCE(SUM(sp.amount), 0.0) as method_total,
+                COUNT(DISTINCT sp.sale_id) as sales_count
+            FROM sale_payments sp
+            JOIN sales s ON sp.sale_id = s.id
+            WHERE s.created_at BETWEEN ?1 AND ?2 
+              AND s.status IN ('completed', 'partial_return')
+            GROUP BY sp.method
+        )
+        ORDER BY method_total DESC
+    "#;
+
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![from_date, to_date], |row| {
+            Ok(PaymentMethodDataPoint {
+                method: row.get(0)?,
+                total_amount: row.get(1)?,
+                sales_count: row.get(2)?,
+                percentage: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_sales_report(
     db: State<Mutex<Connection>>,
     from_date: String,
     to_date: String,
+    compare_mode: Option<String>,
+    compare_from: Option<String>,
+    compare_to: Option<String>,
 ) -> Result<SalesReport, String> {
     let conn = db.lock().map_err(|e| e.to_string())?;
 
     let kpis = fetch_kpis(&conn, &from_date, &to_date)?;
     let sales_chart = fetch_sales_chart(&conn, &from_date, &to_date)?;
     let category_chart = fetch_categories(&conn, &from_date, &to_date)?;
+    let payment_methods = fetch_payment_methods(&conn, &from_date, &to_date)?;
+
+    let comparison = match comparison_range(
+        &from_date,
+        &to_date,
+        compare_mode.as_deref(),
+        compare_from,
+        compare_to,
+    )? {
+        Some((prev_from, prev_to)) => Some(build_comparison(
+            &conn, &kpis, &from_date, &to_date, prev_from, prev_to,
+        )?),
+        None => None,
+    };
 
     Ok(SalesReport {
         kpis,
         sales_chart,
         category_chart,
+        payment_methods,
+        comparison,
     })
 }
-#[tauri::command]
-pub fn get_top_selling_products(
-    db: State<Mutex<Connection>>,
-    from_date: String,
-    to_date: String,
-    page: i64,
-    page_size: i64,
-    category_ids: Option<Vec<String>>,
-    sort_by: Option<String>,
-    sort_order: Option<String>,
-) -> Result<PaginatedResult<TopSellingProduct>, String> {
-    let conn = db.lock().map_err(|e| e.to_string())?;
 
-    let offset = (page - 1) * page_size;
+const TOP_MOVERS_LIMIT: usize = 10;
 
-    let category_filter_count = match &category_ids {
-        Some(ids) if !ids.is_empty() => {
-            let placeholders: Vec<String> = ids
-                .iter()
-                .enumerate()
-                .map(|(i, _)| format!("?{}", i + 3))
-                .collect();
-            format!("AND ps.category_id IN ({})", placeholders.join(", "))
+fn parse_report_date(value: &str) -> Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(value.get(..10).unwrap_or(value), "%Y-%m-%d")
+        .map_err(|_| format!("Fecha inválida: {}", value))
+}
+
+/// Resolves the comparison range: 'previous_period' (same length, right before),
+/// 'last_year' (same dates a year earlier) or 'custom'.
+fn comparison_range(
+    from_date: &str,
+    to_date: &str,
+    mode: Option<&str>,
+    compare_from: Option<String>,
+    compare_to: Option<String>,
+) -> Result<Option<(String, String)>, String> {
+    let (from, to) = match mode {
+        None | Some("") | Some("none") => return Ok(None),
+        Some("custom") => {
+            let (Some(f), Some(t)) = (compare_from, compare_to) else {
+                return Err("Indique las fechas del periodo de comparación.".to_string());
+            };
+            (parse_report_date(&f)?, parse_report_date(&t)?)
         }
-        _ => String::new(),
+        Some("previous_period") => {
+            let from = parse_report_date(from_date)?;
+            let to = parse_report_date(to_date)?;
+            let days = (to - from).num_days().max(0) + 1;
+            let prev_to = from - chrono::Duration::days(1);
+            (prev_to - chrono::Duration::days(days - 1), prev_to)
+        }
+        Some("last_year") => {
+            let year = chrono::Months::new(12);
+            let from = parse_report_date(from_date)?
+                .checked_sub_months(year)
+                .ok_or("Fecha fuera de rango.")?;
+            let to = parse_report_date(to_date)?
+                .checked_sub_months(year)
+                .ok_or("Fecha fuera de rango.")?;
+            (from, to)
+        }
+        Some(other) => return Err(format!("Modo de comparación no soportado: {}", other)),
     };
 
-    let category_filter_data = match &category_ids {
-        Some(ids) if !ids.is_empty() => {
-            let placeholders: Vec<String> = ids
-                .iter()
-                .enumerate()
-                .map(|(i, _)| format!("?{}", i + 5))
-                .collect();
-            format!("AND ps.category_id IN ({})", placeholders.join(", "))
-        }
-        _ => String::new(),
+    if from > to {
+        return Err("La fecha inicial de comparación es posterior a la final.".to_string());
+    }
+
+    Ok(Some((
+        from.format("%Y-%m-%d").to_string(),
+        to.format("%Y-%m-%d").to_string(),
+    )))
+}
+
+fn kpi_delta(current: f64, previous: f64) -> KpiDelta {
+    KpiDelta {
+        current,
+        previous,
+        absolute: current - previous,
+        percent: if previous.abs() > f64::EPSILON {
+            Some((current - previous) * 100.0 / previous.abs())
+        } else {
+            None
+        },
+    }
+}
+
+fn build_comparison(
+    conn: &Connection,
+    current: &ReportKpis,
+    from_date: &str,
+    to_date: &str,
+    prev_from: String,
+    prev_to: String,
+) -> Result<SalesComparison, String> {
+    let kpis = fetch_kpis(conn, &prev_from, &prev_to)?;
+    let sales_chart = fetch_sales_chart(conn, &prev_from, &prev_to)?;
+    let category_chart = fetch_categories(conn, &prev_from, &prev_to)?;
+
+    let deltas = KpiDeltas {
+        gross_sales: kpi_delta(current.gross_sales, kpis.gross_sales),
+        net_profit: kpi_delta(current.net_profit, kpis.net_profit),
+        transaction_count: kpi_delta(
+            current.transaction_count as f64,
+            kpis.transaction_count as f64,
+        ),
+        average_ticket: kpi_delta(current.average_ticket, kpis.average_ticket),
     };
 
-    let count_sql = format!(
-        r#"
-        WITH product_sales AS (
+    let movers = fetch_product_movers(conn, from_date, to_date, &prev_from, &prev_to)?;
+
+    let mut top_gainers: Vec<ProductMover> = Vec::new();
+    let mut top_losers: Vec<ProductMover> = Vec::new();
+    for mover in movers {
+        if mover.quantity_delta > 0.001 {
+            top_gainers.push(mover);
+        } else if mover.quantity_delta < -0.001 {
+            top_losers.push(mover);
+        }
+    }
+    top_gainers.sort_by(|a, b| b.quantity_delta.total_cmp(&a.quantity_delta));
+    top_losers.sort_by(|a, b| a.quantity_delta.total_cmp(&b.quantity_delta));
+    top_gainers.truncate(TOP_MOVERS_LIMIT);
+    top_losers.truncate(TOP_MOVERS_LIMIT);
+
+    Ok(SalesComparison {
+        from_date: prev_from,
+        to_date: prev_to,
+        kpis,
+        sales_chart,
+        category_chart,
+        deltas,
+        top_gainers,
+        top_losers,
+    })
+}
+
+/// Units sold per product (net of returns) in both ranges.
+fn fetch_product_movers(
+    conn: &Connection,
+    from_date: &str,
+    to_date: &str,
+    prev_from: &str,
+    prev_to: &str,
+) -> Result<Vec<ProductMover>, String> {
+    let sql = r#"
+        WITH net_items AS (
             SELECT 
                 si.product_id,
-                p.category_id
+                s.created_at,
+                si.quantity - COALESCE(ri.returned_qty, 0.0) as quantity
             FROM sale_items si
             JOIN sales s ON si.sale_id = s.id
-            JOIN products p ON si.product_id = p.id
             LEFT JOIN (
                 SELECT sale_item_id, SUM(quantity) as returned_qty
                 FROM return_items
                 GROUP BY sale_item_id
             ) ri ON ri.sale_item_id = si.id
-            WHERE s.created_at BETWEEN ?1 AND ?2
-              AND s.status IN ('completed', 'partial_return')
-              AND (si.quantity - COALESCE(ri.returned_qty, 0.0)) > 0.001
-            GROUP BY si.product_id, p.category_id
+            WHERE s.status IN ('completed', 'partial_return')
+              AND (s.created_at BETWEEN ?1 AND ?2 OR s.created_at BETWEEN ?3 AND ?4)
         )
-        SELECT COUNT(*) FROM product_sales ps
-        WHERE 1=1 {}
-        "#,
-        category_filter_count
-    );
-
-    let mut count_params: Vec<Box<dyn rusqlite::types::ToSql>> =
-        vec![Box::new(from_date.clone()), Box::new(to_date.clone())];
+        SELECT 
+            p.id,
+            p.name,
+            p.code,
+            COALESCE(SUM(CASE WHEN ni.created_at BETWEEN ?1 AND ?2 THEN ni.quantity END), 0.0) as current_quantity,
+            COALESCE(SUM(CASE WHEN ni.created_at BETWEEN ?3 AND ?4 THEN ni.quantity END), 0.0) as previous_quantity
+        FROM net_items ni
+        JOIN products p ON ni.product_id = p.id
+        GROUP BY p.id, p.name, p.code
+    "#;
 
-    if let Some(ids) = &category_ids {
-        for id in ids {
-            count_params.push(Box::new(id.clone()));
-        }
-    }
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![from_date, to_date, prev_from, prev_to], |row| {
+            let current_quantity: f64 = row.get(3)?;
+            let previous_quantity: f64 = row.get(4)?;
+            Ok(ProductMover {
+                product_id: row.get(0)?,
+                product_name: row.get(1)?,
+                product_code: row.get(2)?,
+                current_quantity,
+                previous_quantity,
+                quantity_delta: current_quantity - previous_quantity,
+            })
+        })
+        .map_err(|e| e.to_string())?;
 
-    let count_params_refs: Vec<&dyn rusqlite::types::ToSql> =
-        count_params.iter().map(|p| p.as_ref()).collect();
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
 
-    let total_count: i64 = conn
-        .query_row(&count_sql, count_params_refs.as_slice(), |row| row.get(0))
-        .unwrap_or(0);
+/// Data query for the top sellers report: ?1/?2 dates, ?3/?4 limit/offset, categories from ?5.
+pub(crate) fn top_selling_data_sql(
+    category_ids: &Option<Vec<String>>,
+    sort_by: Option<&str>,
+    sort_order: Option<&str>,
+) -> String {
+    let category_filter_data = match category_ids {
+        Some(ids) if !ids.is_empty() => {
+            let placeholders: Vec<String> = ids
+                .iter()
+                .enumerate()
+                .map(|(i, _)| format!("?{}", i + 5))
+                .collect();
+            format!("AND ps.category_id IN ({})", placeholders.join(", "))
+        }
+        _ => String::new(),
+    };
 
-    let data_sql = format!(
+    format!(
         r#"
         WITH product_sales AS (
             SELECT 
@@ -390,14 +695,14 @@ pub fn get_top_selling_products(
         LIMIT ?3 OFFSET ?4
     "#,
         category_filter_data,
-        match sort_by.as_deref() {
+        match sort_by {
             Some("product_name") => "ps.product_name",
             Some("category_name") => "ps.category_name",
             Some("quantity_sold") => "ps.quantity_sold",
             Some("percentage_of_total") => "percentage_of_total",
             _ => "ps.total_revenue",
         },
-        match sort_order.as_deref() {
+        match sort_order {
             Some("desc") => "DESC",
             Some("asc") => "ASC",
             _ =>
@@ -407,58 +712,25 @@ pub fn get_top_selling_products(
                     "ASC"
                 },
         }
-    );
-
-    let mut stmt = conn.prepare(&data_sql).map_err(|e| e.to_string())?;
-
-    let mut data_params: Vec<Box<dyn rusqlite::types::ToSql>> = vec![
-        Box::new(from_date),
-        Box::new(to_date),
-        Box::new(page_size),
-        Box::new(offset),
-    ];
-
-    if let Some(ids) = &category_ids {
-        for id in ids {
-            data_params.push(Box::new(id.clone()));
-        }
-    }
-
-    let data_params_refs: Vec<&dyn rusqlite::types::ToSql> =
-        data_params.iter().map(|p| p.as_ref()).collect();
-
-    let rows = stmt
-        .query_map(data_params_refs.as_slice(), |row| {
-            Ok(TopSellingProduct {
-                ranking: row.get(0)?,
-                product_name: row.get(1)?,
-                product_code: row.get(2)?,
-                category_id: row.get(3)?,
-                category_name: row.get(4)?,
-                category_color: row.get(5)?,
-                quantity_sold: row.get(6)?,
-                total_revenue: row.get(7)?,
-                percentage_of_total: row.get(8)?,
-            })
-        })
-        .map_err(|e| e.to_string())?;
-
-    let data = rows
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
-    let total_pages = (total_count as f64 / page_size as f64).ceil() as i64;
+    )
+}
 
-    Ok(PaginatedResult {
-        data,
-        total: total_count,
-        page,
-        page_size,
-        total_pages,
+pub(crate) fn top_selling_from_row(row: &rusqlite::Row) -> rusqlite::Result<TopSellingProduct> {
+    Ok(TopSellingProduct {
+        ranking: row.get(0)?,
+        product_name: row.get(1)?,
+        product_code: row.get(2)?,
+        category_id: row.get(3)?,
+        category_name: row.get(4)?,
+        category_color: row.get(5)?,
+        quantity_sold: row.get(6)?,
+        total_revenue: row.get(7)?,
+        percentage_of_total: row.get(8)?,
     })
 }
 
 #[tauri::command]
-pub fn get_dead_stock_report(
+pub fn get_top_selling_products(
     db: State<Mutex<Connection>>,
     from_date: String,
     to_date: String,
@@ -467,9 +739,8 @@ pub fn get_dead_stock_report(
     category_ids: Option<Vec<String>>,
     sort_by: Option<String>,
     sort_order: Option<String>,
-) -> Result<PaginatedResult<DeadStockProduct>, String> {
+) -> Result<PaginatedResult<TopSellingProduct>, String> {
     let conn = db.lock().map_err(|e| e.to_string())?;
-    let store_id = get_current_store_id(&conn)?;
 
     let offset = (page - 1) * page_size;
 
@@ -478,57 +749,40 @@ pub fn get_dead_stock_report(
             let placeholders: Vec<String> = ids
                 .iter()
                 .enumerate()
-                .map(|(i, _)| format!("?{}", i + 4))
-                .collect();
-            format!("AND p.category_id IN ({})", placeholders.join(", "))
-        }
-        _ => String::new(),
-    };
-
-    let category_filter_data = match &category_ids {
-        Some(ids) if !ids.is_empty() => {
-            let placeholders: Vec<String> = ids
-                .iter()
-                .enumerate()
-                .map(|(i, _)| format!("?{}", i + 6))
+                .map(|(i, _)| format!("?{}", i + 3))
                 .collect();
-            format!("AND p.category_id IN ({})", placeholders.join(", "))
+            format!("AND ps.category_id IN ({})", placeholders.join(", "))
         }
         _ => String::new(),
     };
 
     let count_sql = format!(
         r#"
-        SELECT COUNT(p.id)
-        FROM products p
-        JOIN store_inventory inv ON p.id = inv.product_id
-        WHERE inv.store_id = ?3
-          AND inv.stock > 0
-          AND p.is_active = 1
-          AND p.deleted_at IS NULL
-          AND p.id NOT IN (
-              SELECT DISTINCT si.product_id
-              FROM sale_items si
-              JOIN sales s ON si.sale_id = s.id
-              LEFT JOIN (
-                  SELECT sale_item_id, SUM(quantity) as returned_qty
-                  FROM return_items
-                  GROUP BY sale_item_id
-              ) ri ON ri.sale_item_id = si.id
-              WHERE s.created_at BETWEEN ?1 AND ?2
-                AND s.status IN ('completed', 'partial_return')
-                AND (si.quantity - COALESCE(ri.returned_qty, 0.0)) > 0.001
-          )
-          {}
+        WITH product_sales AS (
+            SELECT 
+                si.product_id,
+                p.category_id
+            FROM sale_items si
+            JOIN sales s ON si.sale_id = s.id
+            JOIN products p ON si.product_id = p.id
+            LEFT JOIN (
+                SELECT sale_item_id, SUM(quantity) as returned_qty
+                FROM return_items
+                GROUP BY sale_item_id
+            ) ri ON ri.sale_item_id = si.id
+            WHERE s.created_at BETWEEN ?1 AND ?2
+              AND s.status IN ('completed', 'partial_return')
+              AND (si.quantity - COALESCE(ri.returned_qty, 0.0)) > 0.001
+            GROUP BY si.product_id, p.category_id
+        )
+        SELECT COUNT(*) FROM product_sales ps
+        WHERE 1=1 {}
         "#,
         category_filter_count
     );
 
-    let mut count_params: Vec<Box<dyn rusqlite::types::ToSql>> = vec![
-        Box::new(from_date.clone()),
-        Box::new(to_date.clone()),
-        Box::new(store_id.clone()),
-    ];
+    let mut count_params: Vec<Box<dyn rusqlite::types::ToSql>> =
+        vec![Box::new(from_date.clone()), Box::new(to_date.clone())];
 
     if let Some(ids) = &category_ids {
         for id in ids {
@@ -543,7 +797,63 @@ pub fn get_dead_stock_report(
         .query_row(&count_sql, count_params_refs.as_slice(), |row| row.get(0))
         .unwrap_or(0);
 
-    let data_sql = format!(
+    let data_sql = top_selling_data_sql(&category_ids, sort_by.as_deref(), sort_order.as_deref());
+
+    let mut stmt = conn.prepare(&data_sql).map_err(|e| e.to_string())?;
+
+    let mut data_params: Vec<Box<dyn rusqlite::types::ToSql>> = vec![
+        Box::new(from_date),
+        Box::new(to_date),
+        Box::new(page_size),
+        Box::new(offset),
+    ];
+
+    if let Some(ids) = &category_ids {
+        for id in ids {
+            data_params.push(Box::new(id.clone()));
+        }
+    }
+
+    let data_params_refs: Vec<&dyn rusqlite::types::ToSql> =
+        data_params.iter().map(|p| p.as_ref()).collect();
+
+    let rows = stmt
+        .query_map(data_params_refs.as_slice(), top_selling_from_row)
+        .map_err(|e| e.to_string())?;
+
+    let data = rows
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let total_pages = (total_count as f64 / page_size as f64).ceil() as i64;
+
+    Ok(PaginatedResult {
+        data,
+        total: total_count,
+        page,
+        page_size,
+        total_pages,
+    })
+}
+
+/// Data query for the dead stock report: ?1/?2 dates, ?3 store, ?4/?5 limit/offset, categories from ?6.
+pub(crate) fn dead_stock_data_sql(
+    category_ids: &Option<Vec<String>>,
+    sort_by: Option<&str>,
+    sort_order: Option<&str>,
+) -> String {
+    let category_filter_data = match category_ids {
+        Some(ids) if !ids.is_empty() => {
+            let placeholders: Vec<String> = ids
+                .iter()
+                .enumerate()
+                .map(|(i, _)| format!("?{}", i + 6))
+                .collect();
+            format!("AND p.category_id IN ({})", placeholders.join(", "))
+        }
+        _ => String::new(),
+    };
+
+    format!(
         r#"
         SELECT 
             p.name as product_name,
@@ -586,7 +896,7 @@ pub fn get_dead_stock_report(
         LIMIT ?4 OFFSET ?5
     "#,
         category_filter_data,
-        match sort_by.as_deref() {
+        match sort_by {
             Some("product_name") => "p.name",
             Some("category_name") => "category_name",
             Some("current_stock") => "inv.stock",
@@ -594,7 +904,7 @@ pub fn get_dead_stock_report(
             Some("last_sale_date") => "last_sale_date",
             _ => "stagnant_value",
         },
-        match sort_order.as_deref() {
+        match sort_order {
             Some("desc") => "DESC",
             Some("asc") => "ASC",
             _ =>
@@ -604,8 +914,99 @@ pub fn get_dead_stock_report(
                     "ASC"
                 },
         }
+    )
+}
+
+pub(crate) fn dead_stock_from_row(row: &rusqlite::Row) -> rusqlite::Result<DeadStockProduct> {
+    Ok(DeadStockProduct {
+        product_name: row.get(0)?,
+        product_code: row.get(1)?,
+        category_id: row.get(2)?,
+        category_name: row.get(3)?,
+        category_color: row.get(4)?,
+        current_stock: row.get(5)?,
+        purchase_price: row.get(6)?,
+        stagnant_value: row.get(7)?,
+        last_sale_date: row.get(8)?,
+    })
+}
+
+#[tauri::command]
+pub fn get_dead_stock_report(
+    db: State<Mutex<Connection>>,
+    from_date: String,
+    to_date: String,
+    page: i64,
+    page_size: i64,
+    category_ids: Option<Vec<String>>,
+    sort_by: Option<String>,
+    sort_order: Option<String>,
+) -> Result<PaginatedResult<DeadStockProduct>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let store_id = get_current_store_id(&conn)?;
+
+    let offset = (page - 1) * page_size;
+
+    let category_filter_count = match &category_ids {
+        Some(ids) if !ids.is_empty() => {
+            let placeholders: Vec<String> = ids
+                .iter()
+                .enumerate()
+                .map(|(i, _)| format!("?{}", i + 4))
+                .collect();
+            format!("AND p.category_id IN ({})", placeholders.join(", "))
+        }
+        _ => String::new(),
+    };
+
+    let count_sql = format!(
+        r#"
+        SELECT COUNT(p.id)
+        FROM products p
+        JOIN store_inventory inv ON p.id = inv.product_id
+        WHERE inv.store_id = ?3
+          AND inv.stock > 0
+          AND p.is_active = 1
+          AND p.deleted_at IS NULL
+          AND p.id NOT IN (
+              SELECT DISTINCT si.product_id
+              FROM sale_items si
+              JOIN sales s ON si.sale_id = s.id
+              LEFT JOIN (
+                  SELECT sale_item_id, SUM(quantity) as returned_qty
+                  FROM return_items
+                  GROUP BY sale_item_id
+              ) ri ON ri.sale_item_id = si.id
+              WHERE s.created_at BETWEEN ?1 AND ?2
+                AND s.status IN ('completed', 'partial_return')
+                AND (si.quantity - COALESCE(ri.returned_qty, 0.0)) > 0.001
+          )
+          {}
+        "#,
+        category_filter_count
     );
 
+    let mut count_params: Vec<Box<dyn rusqlite::types::ToSql>> = vec![
+        Box::new(from_date.clone()),
+        Box::new(to_date.clone()),
+        Box::new(store_id.clone()),
+    ];
+
+    if let Some(ids) = &category_ids {
+        for id in ids {
+            count_params.push(Box::new(id.clone()));
+        }
+    }
+
+    let count_params_refs: Vec<&dyn rusqlite::types::ToSql> =
+        count_params.iter().map(|p| p.as_ref()).collect();
+
+    let total_count: i64 = conn
+        .query_row(&count_sql, count_params_refs.as_slice(), |row| row.get(0))
+        .unwrap_or(0);
+
+    let data_sql = dead_stock_data_sql(&category_ids, sort_by.as_deref(), sort_order.as_deref());
+
     let mut stmt = conn.prepare(&data_sql).map_err(|e| e.to_string())?;
 
     let mut data_params: Vec<Box<dyn rusqlite::types::ToSql>> = vec![
@@ -626,19 +1027,7 @@ pub fn get_dead_stock_report(
         data_params.iter().map(|p| p.as_ref()).collect();
 
     let rows = stmt
-        .query_map(data_params_refs.as_slice(), |row| {
-            Ok(DeadStockProduct {
-                product_name: row.get(0)?,
-                product_code: row.get(1)?,
-                category_id: row.get(2)?,
-                category_name: row.get(3)?,
-                category_color: row.get(4)?,
-                current_stock: row.get(5)?,
-                purchase_price: row.get(6)?,
-                stagnant_value: row.get(7)?,
-                last_sale_date: row.get(8)?,
-            })
-        })
+        .query_map(data_params_refs.as_slice(), dead_stock_from_row)
         .map_err(|e| e.to_string())?;
 
     let data = rows
@@ -682,33 +1071,13 @@ pub fn get_inventory_valuation(db: State<Mutex<Connection>>) -> Result<Inventory
     })
     .map_err(|e| e.to_string())
 }
-#[tauri::command]
-pub fn get_low_stock_products(
-    db: State<Mutex<Connection>>,
-    page: i64,
-    page_size: i64,
-    category_ids: Option<Vec<String>>,
-    sort_by: Option<String>,
-    sort_order: Option<String>,
-) -> Result<PaginatedResult<LowStockProduct>, String> {
-    let conn = db.lock().map_err(|e| e.to_string())?;
-    let store_id = get_current_store_id(&conn)?;
-
-    let offset = (page - 1) * page_size;
-
-    let category_filter_count = match &category_ids {
-        Some(ids) if !ids.is_empty() => {
-            let placeholders: Vec<String> = ids
-                .iter()
-                .enumerate()
-                .map(|(i, _)| format!("?{}", i + 2))
-                .collect();
-            format!("AND p.category_id IN ({})", placeholders.join(", "))
-        }
-        _ => String::new(),
-    };
-
-    let category_filter_data = match &category_ids {
+/// Data query for the low stock report: ?1 store, ?2/?3 limit/offset, categories from ?4.
+pub(crate) fn low_stock_data_sql(
+    category_ids: &Option<Vec<String>>,
+    sort_by: Option<&str>,
+    sort_order: Option<&str>,
+) -> String {
+    let category_filter_data = match category_ids {
         Some(ids) if !ids.is_empty() => {
             let placeholders: Vec<String> = ids
                 .iter()
@@ -720,37 +1089,7 @@ pub fn get_low_stock_products(
         _ => String::new(),
     };
 
-    let count_sql = format!(
-        r#"
-        SELECT COUNT(p.id)
-        FROM products p
-        JOIN store_inventory i ON p.id = i.product_id
-        WHERE p.deleted_at IS NULL
-          AND p.is_active = 1
-          AND i.store_id = ?1
-          AND i.stock <= COALESCE(i.minimum_stock, 5)
-          {}
-    "#,
-        category_filter_count
-    );
-
-    let mut count_params: Vec<Box<dyn rusqlite::types::ToSql>> =
-        vec![Box::new(store_id.clone())];
-
-    if let Some(ids) = &category_ids {
-        for id in ids {
-            count_params.push(Box::new(id.clone()));
-        }
-    }
-
-    let count_params_refs: Vec<&dyn rusqlite::types::ToSql> =
-        count_params.iter().map(|p| p.as_ref()).collect();
-
-    let total_count: i64 = conn
-        .query_row(&count_sql, count_params_refs.as_slice(), |row| row.get(0))
-        .unwrap_or(0);
-
-    let order_column = match sort_by.as_deref() {
+    let order_column = match sort_by {
         Some("product_name") => "p.name",
         Some("current_stock") => "i.stock",
         Some("minimum_stock") => "minimum_stock",
@@ -760,7 +1099,7 @@ pub fn get_low_stock_products(
         _ => "c.name",
     };
 
-    let order_direction = match sort_order.as_deref() {
+    let order_direction = match sort_order {
         Some("desc") => "DESC",
         Some("asc") => "ASC",
         _ => {
@@ -772,7 +1111,7 @@ pub fn get_low_stock_products(
         }
     };
 
-    let sql = format!(
+    format!(
         r#"
         SELECT 
             p.name as product_name,
@@ -798,8 +1137,82 @@ pub fn get_low_stock_products(
     "#,
         category_filter_data,
         order_column, order_direction
+    )
+}
+
+pub(crate) fn low_stock_from_row(row: &rusqlite::Row) -> rusqlite::Result<LowStockProduct> {
+    Ok(LowStockProduct {
+        product_name: row.get(0)?,
+        product_code: row.get(1)?,
+        category_id: row.get(2)?,
+        category_name: row.get(3)?,
+        category_color: row.get(4)?,
+        current_stock: row.get(5)?,
+        minimum_stock: row.get(6)?,
+        suggested_order: row.get(7)?,
+        purchase_price: row.get(8)?,
+        retail_price: row.get(9)?,
+    })
+}
+
+#[tauri::command]
+pub fn get_low_stock_products(
+    db: State<Mutex<Connection>>,
+    page: i64,
+    page_size: i64,
+    category_ids: Option<Vec<String>>,
+    sort_by: Option<String>,
+    sort_order: Option<String>,
+) -> Result<PaginatedResult<LowStockProduct>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    let store_id = get_current_store_id(&conn)?;
+
+    let offset = (page - 1) * page_size;
+
+    let category_filter_count = match &category_ids {
+        Some(ids) if !ids.is_empty() => {
+            let placeholders: Vec<String> = ids
+                .iter()
+                .enumerate()
+                .map(|(i, _)| format!("?{}", i + 2))
+                .collect();
+            format!("AND p.category_id IN ({})", placeholders.join(", "))
+        }
+        _ => String::new(),
+    };
+
+    let count_sql = format!(
+        r#"
+        SELECT COUNT(p.id)
+        FROM products p
+        JOIN store_inventory i ON p.id = i.product_id
+        WHERE p.deleted_at IS NULL
+          AND p.is_active = 1
+          AND i.store_id = ?1
+          AND i.stock <= COALESCE(i.minimum_stock, 5)
+          {}
+    "#,
+        category_filter_count
     );
 
+    let mut count_params: Vec<Box<dyn rusqlite::types::ToSql>> =
+        vec![Box::new(store_id.clone())];
+
+    if let Some(ids) = &category_ids {
+        for id in ids {
+            count_params.push(Box::new(id.clone()));
+        }
+    }
+
+    let count_params_refs: Vec<&dyn rusqlite::types::ToSql> =
+        count_params.iter().map(|p| p.as_ref()).collect();
+
+    let total_count: i64 = conn
+        .query_row(&count_sql, count_params_refs.as_slice(), |row| row.get(0))
+        .unwrap_or(0);
+
+    let sql = low_stock_data_sql(&category_ids, sort_by.as_deref(), sort_order.as_deref());
+
     let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
 
     let mut data_params: Vec<Box<dyn rusqlite::types::ToSql>> = vec![
@@ -818,20 +1231,7 @@ pub fn get_low_stock_products(
         data_params.iter().map(|p| p.as_ref()).collect();
 
     let rows = stmt
-        .query_map(data_params_refs.as_slice(), |row| {
-            Ok(LowStockProduct {
-                product_name: row.get(0)?,
-                product_code: row.get(1)?,
-                category_id: row.get(2)?,
-                category_name: row.get(3)?,
-                category_color: row.get(4)?,
-                current_stock: row.get(5)?,
-                minimum_stock: row.get(6)?,
-                suggested_order: row.get(7)?,
-                purchase_price: row.get(8)?,
-                retail_price: row.get(9)?,
-            })
-        })
+        .query_map(data_params_refs.as_slice(), low_stock_from_row)
         .map_err(|e| e.to_string())?;
 
     let data = rows
@@ -847,3 +1247,569 @@ pub fn get_low_stock_products(
         total_pages,
     })
 }
+
+/// Sales by weekday x hour, always returning the full 7x24 grid.
+#[tauri::command]
+pub fn get_sales_heatmap(
+    db: State<Mutex<Connection>>,
+    from_date: String,
+    to_date: String,
+    category_ids: Option<Vec<String>>,
+    cashier_id: Option<String>,
+) -> Result<Vec<HeatmapCell>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+
+    let mut query_params: Vec<Box<dyn rusqlite::types::ToSql>> =
+        vec![Box::new(from_date), Box::new(to_date)];
+
+    let mut filters = String::new();
+    if let Some(ids) = category_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        let placeholders: Vec<String> = ids
+            .iter()
+            .enumerate()
+            .map(|(i, _)| format!("?{}", i + query_params.len() + 1))
+            .collect();
+        filters.push_str(&format!(" AND p.category_id IN ({})", placeholders.join(", ")));
+        for id in ids {
+            query_params.push(Box::new(id.clone()));
+        }
+    }
+    if let Some(cashier) = cashier_id.filter(|c| !c.is_empty()) {
+        filters.push_str(&format!(" AND s.user_id = ?{}", query_params.len() + 1));
+        query_params.push(Box::new(cashier));
+    }
+
+    // created_at ya se guarda en hora local
+    let sql = format!(
+        r#"
+        SELECT 
+            CAST(strftime('%w', s.created_at) AS INTEGER) as weekday,
+            CAST(strftime('%H', s.created_at) AS INTEGER) as hour,
+            COUNT(DISTINCT s.id) as sales_count,
+            COALESCE(SUM(
+                si.total - COALESCE(ri.returned_subtotal, 0.0)
+            ), 0.0) as revenue
+        FROM sale_items si
+        JOIN sales s ON si.sale_id = s.id
+        JOIN products p ON si.product_id = p.id
+        LEFT JOIN (
+            SELECT sale_item_id, SUM(quantity) as returned_qty, SUM(subtotal) as returned_subtotal
+            FROM return_items
+            GROUP BY sale_item_id
+        ) ri ON ri.sale_item_id = si.id
+        WHERE s.created_at BETWEEN ?1 AND ?2 
+          AND s.status IN ('completed', 'partial_return')
+          AND (si.quantity - COALESCE(ri.returned_qty, 0.0)) > 0.001
+          {}
+        GROUP BY weekday, hour
+    "#,
+        filters
+    );
+
+    let mut grid: Vec<HeatmapCell> = (0..7)
+        .flat_map(|weekday| {
+            (0..24).map(move |hour| HeatmapCell {
+                weekday,
+                hour,
+                sales_count: 0,
+                revenue: 0.0,
+                average_ticket: 0.0,
+            })
+        })
+        .collect();
+
+    let params_refs: Vec<&dyn rusqlite::types::ToSql> =
+        query_params.iter().map(|p| p.as_ref()).collect();
+
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params_refs.as_slice(), |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, f64>(3)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    for row in rows {
+        let (weekday, hour, sales_count, revenue) = row.map_err(|e| e.to_string())?;
+        if !(0..7).contains(&weekday) || !(0..24).contains(&hour) {
+            continue;
+        }
+        let cell = &mut grid[(weekday * 24 + hour) as usize];
+        cell.sales_count = sales_count;
+        cell.revenue = revenue;
+        cell.average_ticket = if sales_count > 0 {
+            revenue / sales_count as f64
+        } else {
+            0.0
+        };
+    }
+
+    Ok(grid)
+}
+
+/// Per-user activity over a date range. Cancellations of closed-shift sales
+/// are reported as voids (reversals), not as cancellations.
+#[tauri::command]
+pub fn get_cashier_performance(
+    db: State<Mutex<Connection>>,
+    user_id: String,
+    from_date: String,
+    to_date: String,
+    cashier_id: Option<String>,
+) -> Result<Vec<CashierPerformance>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    require_permission(&conn, &user_id, "reports:view")?;
+
+    let sql = r#"
+        WITH sales_agg AS (
+            SELECT 
+                s.user_id,
+                COUNT(DISTINCT s.id) as sales_count,
+                COALESCE(SUM(si.total - COALESCE(ri.returned_subtotal, 0.0)), 0.0) as revenue,
+                COALESCE(SUM(si.quantity - COALESCE(ri.returned_qty, 0.0)), 0.0) as items
+            FROM sale_items si
+            JOIN sales s ON si.sale_id = s.id
+            LEFT JOIN (
+                SELECT sale_item_id, SUM(quantity) as returned_qty, SUM(subtotal) as returned_subtotal
+                FROM return_items
+                GROUP BY sale_item_id
+            ) ri ON ri.sale_item_id = si.id
+            WHERE date(s.created_at) BETWEEN date(?1) AND date(?2)
+              AND s.status IN ('completed', 'partial_return')
+              AND (si.quantity - COALESCE(ri.returned_qty, 0.0)) > 0.001
+            GROUP BY s.user_id
+        ),
+        discount_agg AS (
+            SELECT user_id, COUNT(*) as discount_count, SUM(discount_amount) as discount_total
+            FROM sales
+            WHERE date(created_at) BETWEEN date(?1) AND date(?2)
+              AND status IN ('completed', 'partial_return')
+              AND COALESCE(discount_amount, 0) > 0.001
+            GROUP BY user_id
+        ),
+        cancel_agg AS (
+            SELECT cancelled_by as user_id, COUNT(*) as cancel_count, SUM(total) as cancel_total
+            FROM sales s
+            WHERE s.status = 'cancelled'
+              AND date(s.cancelled_at) BETWEEN date(?1) AND date(?2)
+              AND NOT EXISTS (SELECT 1 FROM sale_reversals sr WHERE sr.sale_id = s.id)
+            GROUP BY cancelled_by
+        ),
+        void_agg AS (
+            SELECT user_id, COUNT(*) as void_count, SUM(total) as void_total
+            FROM sale_reversals
+            WHERE date(created_at) BETWEEN date(?1) AND date(?2)
+            GROUP BY user_id
+        ),
+        return_agg AS (
+            SELECT user_id, COUNT(*) as return_count, SUM(total) as return_total
+            FROM returns
+            WHERE date(created_at) BETWEEN date(?1) AND date(?2)
+            GROUP BY user_id
+        ),
+        shift_agg AS (
+            SELECT 
+                closing_user_id as user_id,
+                COUNT(*) as shifts_closed,
+                SUM(CASE WHEN ABS(COALESCE(cash_difference, 0)) >= 0.01 THEN 1 ELSE 0 END) as discrepancy_count,
+                COALESCE(SUM(cash_difference), 0.0) as discrepancy_total,
+                COALESCE(SUM(ABS(cash_difference)), 0.0) as discrepancy_absolute
+            FROM cash_register_shifts
+            WHERE status = 'closed'
+              AND date(closing_date) BETWEEN date(?1) AND date(?2)
+            GROUP BY closing_user_id
+        )
+        SELECT 
+            u.id,
+            u.full_name,
+            u.avatar_url,
+            COALESCE(sa.sales_count, 0),
+            COALESCE(sa.revenue, 0.0),
+            COALESCE(sa.items, 0.0),
+            COALESCE(da.discount_count, 0),
+            COALESCE(da.discount_total, 0.0),
+            COALESCE(ca.cancel_count, 0),
+            COALESCE(ca.cancel_total, 0.0),
+            COALESCE(ra.return_count, 0),
+            COALESCE(ra.return_total, 0.0),
+            COALESCE(sh.shifts_closed, 0),
+            COALESCE(sh.discrepancy_count, 0),
+            COALESCE(sh.discrepancy_total, 0.0),
+            COALESCE(sh.discrepancy_absolute, 0.0),
+            COALESCE(va.void_count, 0),
+            COALESCE(va.void_total, 0.0)
+        FROM users u
+        LEFT JOIN sales_agg sa ON sa.user_id = u.id
+        LEFT JOIN discount_agg da ON da.user_id = u.id
+        LEFT JOIN cancel_agg ca ON ca.user_id = u.id
+        LEFT JOIN void_agg va ON va.user_id = u.id
+        LEFT JOIN return_agg ra ON ra.user_id = u.id
+        LEFT JOIN shift_agg sh ON sh.user_id = u.id
+        WHERE (?3 IS NULL OR u.id = ?3)
+          AND (sa.user_id IS NOT NULL OR da.user_id IS NOT NULL OR ca.user_id IS NOT NULL
+               OR va.user_id IS NOT NULL OR ra.user_id IS NOT NULL OR sh.user_id IS NOT NULL)
+        ORDER BY COALESCE(sa.revenue, 0.0) DESC, u.full_name ASC
+    "#;
+
+    let cashier_id = cashier_id.filter(|c| !c.is_empty());
+
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![from_date, to_date, cashier_id], |row| {
+            let sales_count: i64 = row.get(3)?;
+            let revenue: f64 = row.get(4)?;
+            let items: f64 = row.get(5)?;
+            let (average_ticket, items_per_ticket) = if sales_count > 0 {
+                (revenue / sales_count as f64, items / sales_count as f64)
+            } else {
+                (0.0, 0.0)
+            };
+
+            Ok(CashierPerformance {
+                user_id: row.get(0)?,
+                user_name: row.get(1)?,
+                user_avatar: row.get(2)?,
+                sales_count,
+                revenue,
+                average_ticket,
+                items_per_ticket,
+                discount_count: row.get(6)?,
+                discount_total: row.get(7)?,
+                cancellations_count: row.get(8)?,
+                cancellations_total: row.get(9)?,
+                returns_count: row.get(10)?,
+                returns_total: row.get(11)?,
+                shifts_closed: row.get(12)?,
+                discrepancy_count: row.get(13)?,
+                discrepancy_total: row.get(14)?,
+                discrepancy_absolute: row.get(15)?,
+                voids_count: row.get(16)?,
+                voids_total: row.get(17)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// Discount money given away per sale line, split by cause. Promotion, kit and
+/// wholesale amounts are measured against the product's current retail price;
+/// every amount is scaled by the share of the line that was not returned.
+const DISCOUNT_LINES_CTE: &str = r#"
+    WITH discount_lines AS (
+        SELECT 
+            s.id as sale_id,
+            s.user_id,
+            COALESCE(u.full_name, 'Desconocido') as user_name,
+            si.product_id,
+            si.product_name,
+            {period} as period,
+            CASE WHEN si.quantity > 0
+                THEN (si.quantity - COALESCE(ri.returned_qty, 0.0)) / si.quantity
+                ELSE 0.0
+            END as kept,
+            COALESCE(si.discount_amount, 0.0) as manual,
+            CASE WHEN si.promotion_id IS NOT NULL
+                THEN MAX(COALESCE(p.retail_price, 0.0) * si.quantity - si.subtotal, 0.0)
+                ELSE 0.0
+            END as promotion,
+            CASE WHEN si.price_type = 'kit_item'
+                THEN COALESCE(p.retail_price, 0.0) * si.quantity
+                ELSE 0.0
+            END as kit,
+            CASE WHEN si.price_type = 'wholesale' AND si.promotion_id IS NULL
+                THEN MAX((COALESCE(p.retail_price, 0.0) - si.unit_price) * si.quantity, 0.0)
+                ELSE 0.0
+            END as wholesale,
+            COALESCE(si.total, si.subtotal) - COALESCE(ri.returned_subtotal, 0.0) as net_revenue
+        FROM sale_items si
+        JOIN sales s ON si.sale_id = s.id
+        JOIN products p ON si.product_id = p.id
+        LEFT JOIN users u ON s.user_id = u.id
+        LEFT JOIN (
+            SELECT sale_item_id, SUM(quantity) as returned_qty, SUM(subtotal) as returned_subtotal
+            FROM return_items
+            GROUP BY sale_item_id
+        ) ri ON ri.sale_item_id = si.id
+        WHERE date(s.created_at) BETWEEN date(?1) AND date(?2)
+          AND s.status IN ('completed', 'partial_return')
+          AND (?3 IS NULL OR s.user_id = ?3)
+          AND (si.quantity - COALESCE(ri.returned_qty, 0.0)) > 0.001
+    )
+"#;
+
+fn fetch_discount_breakdown(
+    conn: &Connection,
+    period_expr: &str,
+    group_by: &str,
+    from_date: &str,
+    to_date: &str,
+    cashier_id: Option<&str>,
+) -> Result<Vec<DiscountBreakdownRow>, String> {
+    let (key_expr, label_expr, order_by) = match group_by {
+        "user" => ("user_id", "MAX(user_name)", "discount_total DESC"),
+        "product" => ("product_id", "MAX(product_name)", "discount_total DESC"),
+        _ => ("period", "period", "row_key ASC"),
+    };
+
+    let sql = format!(
+        r#"{}
+        SELECT 
+            {} as row_key,
+            {} as row_label,
+            COALESCE(SUM(manual * kept), 0.0) as manual_total,
+            COALESCE(SUM(promotion * kept), 0.0) as promotion_total,
+            COALESCE(SUM(kit * kept), 0.0) as kit_total,
+            COALESCE(SUM(wholesale * kept), 0.0) as wholesale_total,
+            COALESCE(SUM(net_revenue), 0.0) as net_revenue_total,
+            COUNT(DISTINCT CASE WHEN manual + promotion + kit + wholesale > 0.001 THEN sale_id END) as sales_count,
+            COALESCE(SUM((manual + promotion + kit + wholesale) * kept), 0.0) as discount_total
+        FROM discount_lines
+        GROUP BY 1
+        HAVING discount_total > 0.001
+        ORDER BY {}
+    "#,
+        DISCOUNT_LINES_CTE.replace("{period}", period_expr),
+        key_expr,
+        label_expr,
+        order_by
+    );
+
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![from_date, to_date, cashier_id], |row| {
+            let manual: f64 = row.get(2)?;
+            let promotion: f64 = row.get(3)?;
+            let kit: f64 = row.get(4)?;
+            let wholesale: f64 = row.get(5)?;
+            let net_revenue: f64 = row.get(6)?;
+            let total_discount = manual + promotion + kit + wholesale;
+            let list_value = net_revenue + total_discount;
+
+            Ok(DiscountBreakdownRow {
+                key: row.get(0)?,
+                label: row.get(1)?,
+                manual,
+                promotion,
+                kit,
+                wholesale,
+                total_discount,
+                net_revenue,
+                discount_rate: if list_value > 0.0 {
+                    total_discount * 100.0 / list_value
+                } else {
+                    0.0
+                },
+                sales_count: row.get(7)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// Discounts by cause (manual %, promotion, kit, wholesale vs retail),
+/// broken down by user, product and period ('day', 'week' or 'month').
+#[tauri::command]
+pub fn get_discount_analysis(
+    db: State<Mutex<Connection>>,
+    user_id: String,
+    from_date: String,
+    to_date: String,
+    period: Option<String>,
+    cashier_id: Option<String>,
+) -> Result<DiscountAnalysis, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    require_permission(&conn, &user_id, "reports:view")?;
+
+    let period_expr = match period.as_deref() {
+        Some("week") => "strftime('%Y-W%W', s.created_at)",
+        Some("month") => "strftime('%Y-%m', s.created_at)",
+        _ => "date(s.created_at)",
+    };
+    let cashier_id = cashier_id.filter(|c| !c.is_empty());
+    let cashier = cashier_id.as_deref();
+
+    let by_user =
+        fetch_discount_breakdown(&conn, period_expr, "user", &from_date, &to_date, cashier)?;
+    let by_product =
+        fetch_discount_breakdown(&conn, period_expr, "product", &from_date, &to_date, cashier)?;
+    let by_period =
+        fetch_discount_breakdown(&conn, period_expr, "period", &from_date, &to_date, cashier)?;
+
+    let mut totals = DiscountBreakdownRow {
+        key: "total".to_string(),
+        label: "Total".to_string(),
+        manual: 0.0,
+        promotion: 0.0,
+        kit: 0.0,
+        wholesale: 0.0,
+        total_discount: 0.0,
+        net_revenue: 0.0,
+        discount_rate: 0.0,
+        sales_count: 0,
+    };
+    for row in &by_period {
+        totals.manual += row.manual;
+        totals.promotion += row.promotion;
+        totals.kit += row.kit;
+        totals.wholesale += row.wholesale;
+        totals.total_discount += row.total_discount;
+        totals.net_revenue += row.net_revenue;
+        totals.sales_count += row.sales_count;
+    }
+    let list_value = totals.net_revenue + totals.total_discount;
+    if list_value > 0.0 {
+        totals.discount_rate = totals.total_discount * 100.0 / list_value;
+    }
+
+    Ok(DiscountAnalysis {
+        totals,
+        by_user,
+        by_product,
+        by_period,
+    })
+}
+
+/// Demand-based reorder quantities: enough stock to cover lead time plus the
+/// target cover at the average daily sales (net of returns), never below minimum stock.
+pub(crate) fn compute_reorder_suggestions(
+    conn: &Connection,
+    params: &ReorderParams,
+) -> Result<Vec<ReorderSuggestion>, String> {
+    let window_days = params.window_days.unwrap_or(30).max(1);
+    let target_cover_days = params.target_cover_days.unwrap_or(14).max(0);
+    let lead_time_days = params.lead_time_days.unwrap_or(3).max(0);
+    let include_all = params.include_all.unwrap_or(false);
+
+    let store_id = get_current_store_id(conn)?;
+    let since = (chrono::Local::now() - chrono::Duration::days(window_days))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+
+    let category_filter = match &params.category_ids {
+        Some(ids) if !ids.is_empty() => {
+            let placeholders: Vec<String> = ids
+                .iter()
+                .enumerate()
+                .map(|(i, _)| format!("?{}", i + 3))
+                .collect();
+            format!("AND p.category_id IN ({})", placeholders.join(", "))
+        }
+        _ => String::new(),
+    };
+
+    let sql = format!(
+        r#"
+        SELECT 
+            p.id,
+            p.name,
+            p.code,
+            COALESCE(c.name, 'Sin Categoría') as category_name,
+            i.stock,
+            COALESCE(i.minimum_stock, 5) as minimum_stock,
+            COALESCE(p.purchase_price, 0.0) as purchase_price,
+            COALESCE((
+                SELECT SUM(si.quantity - COALESCE(ri.returned_qty, 0.0))
+                FROM sale_items si
+                JOIN sales s ON si.sale_id = s.id
+                LEFT JOIN (
+                    SELECT sale_item_id, SUM(quantity) as returned_qty
+                    FROM return_items
+                    GROUP BY sale_item_id
+                ) ri ON ri.sale_item_id = si.id
+                WHERE si.product_id = p.id
+                  AND s.created_at >= ?2
+                  AND s.status IN ('completed', 'partial_return')
+            ), 0.0) as units_sold
+        FROM products p
+        JOIN store_inventory i ON p.id = i.product_id
+        LEFT JOIN categories c ON p.category_id = c.id
+        WHERE p.deleted_at IS NULL
+          AND p.is_active = 1
+          AND i.store_id = ?1
+          {}
+        ORDER BY p.name ASC
+    "#,
+        category_filter
+    );
+
+    let mut query_params: Vec<Box<dyn rusqlite::types::ToSql>> =
+        vec![Box::new(store_id), Box::new(since)];
+    if let Some(ids) = &params.category_ids {
+        for id in ids {
+            query_params.push(Box::new(id.clone()));
+        }
+    }
+    let params_refs: Vec<&dyn rusqlite::types::ToSql> =
+        query_params.iter().map(|p| p.as_ref()).collect();
+
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params_refs.as_slice(), |row| {
+            let current_stock: i64 = row.get(4)?;
+            let minimum_stock: i64 = row.get(5)?;
+            let purchase_price: f64 = row.get(6)?;
+            let units_sold: f64 = row.get::<_, f64>(7)?.max(0.0);
+
+            let daily_average = units_sold / window_days as f64;
+            let days_of_cover = if daily_average > 0.0 {
+                Some(current_stock.max(0) as f64 / daily_average)
+            } else {
+                None
+            };
+
+            let demand_stock = (daily_average * (target_cover_days + lead_time_days) as f64).ceil() as i64;
+            let target_stock = demand_stock.max(minimum_stock);
+            let suggested_order = (target_stock - current_stock).max(0);
+
+            Ok(ReorderSuggestion {
+                product_id: row.get(0)?,
+                product_name: row.get(1)?,
+                product_code: row.get(2)?,
+                category_name: row.get(3)?,
+                current_stock,
+                minimum_stock,
+                units_sold,
+                daily_average,
+                days_of_cover,
+                suggested_order,
+                purchase_price,
+                estimated_cost: suggested_order as f64 * purchase_price,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut suggestions = Vec::new();
+    for row in rows {
+        let suggestion = row.map_err(|e| e.to_string())?;
+        if include_all || suggestion.suggested_order > 0 {
+            suggestions.push(suggestion);
+        }
+    }
+
+    // Lo más urgente primero: menor cobertura; sin ventas al final
+    suggestions.sort_by(|a, b| match (a.days_of_cover, b.days_of_cover) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.product_name.cmp(&b.product_name),
+    });
+
+    Ok(suggestions)
+}
+
+#[tauri::command]
+pub fn get_reorder_suggestions(
+    db: State<Mutex<Connection>>,
+    params: Option<ReorderParams>,
+) -> Result<Vec<ReorderSuggestion>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    compute_reorder_suggestions(&conn, &params.unwrap_or_default())
+}
@@ -1,11 +1,14 @@
 use chrono;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use tauri::State;
 use uuid::Uuid;
 
+use crate::commands::cash_register::payments::fetch_payment_split;
+use crate::commands::sales::returns::ensure_cash_available;
 use crate::commands::settings::business::get_store_id;
+use crate::commands::settings::permissions::require_permission;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CancelSaleRequest {
@@ -18,6 +21,7 @@ pub struct CancelSaleRequest {
 pub struct CancelSaleResponse {
     pub sale_id: String,
     pub folio: String,
+    pub is_reversal: bool,
 }
 
 #[tauri::command]
@@ -34,8 +38,7 @@ pub fn cancel_sale(
     let tx = conn.transaction().map_err(|e| e.to_string())?;
 
     // Validate sale is 'completed'
-    let (status, folio, payment_method, total, customer_id, sale_shift_id): (
-        String,
+    let (status, folio, total, customer_id, sale_shift_id): (
         String,
         String,
         f64,
@@ -43,7 +46,7 @@ pub fn cancel_sale(
         Option<String>,
     ) = tx
         .query_row(
-            "SELECT status, folio, payment_method, total, customer_id, cash_register_shift_id FROM sales WHERE id = ?1",
+            "SELECT status, folio, total, customer_id, cash_register_shift_id FROM sales WHERE id = ?1",
             [&payload.sale_id],
             |row| {
                 Ok((
@@ -52,7 +55,6 @@ pub fn cancel_sale(
                     row.get(2)?,
                     row.get(3)?,
                     row.get(4)?,
-                    row.get(5)?,
                 ))
             },
         )
@@ -65,26 +67,59 @@ pub fn cancel_sale(
         ));
     }
 
-    // Validate same active shift
-    let active_shift_id: Option<i64> = tx
+    // Exchange sales were partly paid with returned goods; cancelling them would lose that credit
+    let exchange_amount: f64 = tx
         .query_row(
-            "SELECT id FROM cash_register_shifts WHERE status = 'open' LIMIT 1",
-            [],
+            "SELECT exchange_amount FROM sales WHERE id = ?1",
+            [&payload.sale_id],
             |row| row.get(0),
         )
-        .ok();
+        .unwrap_or(0.0);
+
+    if exchange_amount > 0.0 {
+        return Err(
+            "No se puede cancelar la venta de un cambio. Procese una devolución en su lugar.".to_string(),
+        );
+    }
+
+    // Resolve the shift the cancellation is booked into
+    let (active_shift_id, active_initial_cash): (i64, f64) = tx
+        .query_row(
+            "SELECT id, initial_cash FROM cash_register_shifts WHERE status = 'open' LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or("Se requiere un turno abierto para cancelar ventas.".to_string())?;
 
     let sale_shift_as_i64 = sale_shift_id
         .as_ref()
         .and_then(|s| s.parse::<i64>().ok());
 
-    match (&active_shift_id, &sale_shift_as_i64) {
-        (Some(active), Some(sale_shift)) if active == sale_shift => {},
-        _ => {
+    // Sales from a closed shift are voided with a reversing entry in the open shift,
+    // leaving the closed shift untouched
+    let is_reversal = sale_shift_as_i64 != Some(active_shift_id);
+    if is_reversal {
+        let sale_shift_status: Option<String> = match sale_shift_as_i64 {
+            Some(id) => tx
+                .query_row(
+                    "SELECT status FROM cash_register_shifts WHERE id = ?1",
+                    [id],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|e| e.to_string())?,
+            None => None,
+        };
+
+        if sale_shift_status.as_deref() != Some("closed") {
             return Err(
-                "Solo se puede cancelar una venta dentro del mismo turno de caja.".to_string(),
+                "Solo se puede cancelar una venta del turno actual o de un turno cerrado.".to_string(),
             );
         }
+
+        require_permission(&tx, &payload.user_id, "history:cancel_closed_shift")?;
     }
 
     let now_local = chrono::Local::now()
@@ -93,8 +128,8 @@ pub fn cancel_sale(
 
     // Update sale status
     tx.execute(
-        "UPDATE sales SET status = 'cancelled', cancellation_reason = ?1, cancelled_by = ?2, cancelled_at = ?3, updated_at = ?3 WHERE id = ?4",
-        params![reason, payload.user_id, now_local, payload.sale_id],
+        "UPDATE sales SET status = 'cancelled', cancellation_reason = ?1, cancelled_by = ?2, cancelled_at = ?3, cancelled_shift_id = ?4, updated_at = ?3 WHERE id = ?5",
+        params![reason, payload.user_id, now_local, active_shift_id, payload.sale_id],
     )
     .map_err(|e| format!("Error actualizando estado de venta: {}", e))?;
 
@@ -168,14 +203,16 @@ pub fn cancel_sale(
     drop(stmt_update);
     drop(stmt_movement);
 
-    // Revert credit balance if credit sale
-    if payment_method == "credit" {
+    let payments = fetch_payment_split(&tx, &payload.sale_id)?;
+
+    // Revert credit balance for the part charged to the customer account
+    if payments.credit > 0.0 {
         if let Some(cid) = &customer_id {
             let now_local = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
             let rows = tx
                 .execute(
                     "UPDATE customers SET current_balance = current_balance - ?1, updated_at = ?2 WHERE id = ?3",
-                    params![total, now_local, cid],
+                    params![payments.credit, now_local, cid],
                 )
                 .map_err(|e| format!("Error revirtiendo saldo de cliente: {}", e))?;
 
@@ -218,11 +255,51 @@ pub fn cancel_sale(
         .map_err(|e| format!("Error revirtiendo vale {}: {}", voucher_id, e))?;
     }
 
+    // Reversing entry in the open shift
+    if is_reversal {
+        let voucher_amount: f64 = vouchers_used.iter().map(|(_, amount)| amount).sum();
+        let cash_amount = payments.cash;
+        let card_amount = payments.card;
+        let credit_amount = payments.credit;
+
+        if cash_amount > 0.0 {
+            ensure_cash_available(
+                &tx,
+                Some((active_shift_id, active_initial_cash)),
+                cash_amount,
+            )?;
+        }
+
+        tx.execute(
+            "INSERT INTO sale_reversals (
+                id, sale_id, cash_register_shift_id, original_shift_id, total,
+                cash_amount, card_amount, credit_amount, voucher_amount,
+                reason, user_id, created_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                Uuid::new_v4().to_string(),
+                payload.sale_id,
+                active_shift_id,
+                sale_shift_as_i64,
+                total,
+                cash_amount,
+                card_amount,
+                credit_amount,
+                voucher_amount,
+                reason,
+                payload.user_id,
+                now_local
+            ],
+        )
+        .map_err(|e| format!("Error registrando reverso de la venta: {}", e))?;
+    }
+
     // Commit
     tx.commit().map_err(|e| e.to_string())?;
 
     Ok(CancelSaleResponse {
         sale_id: payload.sale_id,
         folio,
+        is_reversal,
     })
 }
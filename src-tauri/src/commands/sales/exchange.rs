@@ -0,0 +1,109 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::State;
+
+use crate::commands::cash_register::sales::{record_sale, SaleRequest};
+use crate::commands::sales::returns::{record_return, settle_refund, ProcessReturnRequest};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProcessExchangeRequest {
+    /// Items coming back from the original sale. `refund_method` only applies to the
+    /// difference when the returned value exceeds the new sale.
+    pub return_request: ProcessReturnRequest,
+    /// Replacement items. Tenders only need to cover the difference.
+    pub sale_request: SaleRequest,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExchangeResponse {
+    pub return_id: String,
+    pub sale_id: String,
+    pub folio: String,
+    pub return_total: f64,
+    pub sale_total: f64,
+    pub amount_due: f64,
+    pub refund_total: f64,
+    pub refund_method: String,
+    pub voucher_code: String,
+    pub change: f64,
+}
+
+#[tauri::command]
+pub fn process_exchange(
+    app_handle: tauri::AppHandle,
+    db: State<Mutex<Connection>>,
+    payload: ProcessExchangeRequest,
+) -> Result<ExchangeResponse, String> {
+    if payload.sale_request.tenders().iter().any(|t| t.method == "credit") {
+        return Err("Los cambios no admiten pago a crédito de la diferencia.".to_string());
+    }
+    if payload.return_request.user_id != payload.sale_request.user_id {
+        return Err("La devolución y la venta del cambio deben registrarse con el mismo usuario.".to_string());
+    }
+
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    // Returned goods go back to stock first so a same-product swap passes the stock check
+    let returned = record_return(&tx, &payload.return_request)?;
+    let sale = record_sale(&tx, &payload.sale_request, returned.total)?;
+
+    let refund_total = (returned.total - sale.exchange_applied).max(0.0);
+
+    tx.execute(
+        "UPDATE returns SET exchange_amount = ?1, exchange_sale_id = ?2 WHERE id = ?3",
+        params![sale.exchange_applied, sale.id, returned.return_id],
+    )
+    .map_err(|e| format!("Error vinculando devolución con la venta: {}", e))?;
+
+    let voucher_code = settle_refund(
+        &tx,
+        &payload.return_request,
+        &returned.return_id,
+        refund_total,
+    )?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+
+    let amount_due = sale.total - sale.exchange_applied;
+    let change = if sale.total_paid > sale.total {
+        sale.total_paid - sale.total
+    } else {
+        0.0
+    };
+
+    if payload.sale_request.should_print {
+        let app_handle_clone = app_handle.clone();
+        let return_id_clone = returned.return_id.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let _ = crate::printer_utils::print_exchange_from_db(app_handle_clone, return_id_clone);
+        });
+    } else {
+        let app_handle_clone = app_handle.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let _ = crate::printer_utils::kick_drawer_direct(&app_handle_clone, false);
+        });
+    }
+
+    if !voucher_code.is_empty() {
+        let app_handle_clone = app_handle.clone();
+        let sale_id_clone = payload.return_request.sale_id.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let _ = crate::printer_utils::print_voucher_from_db(app_handle_clone, sale_id_clone);
+        });
+    }
+
+    Ok(ExchangeResponse {
+        return_id: returned.return_id,
+        sale_id: sale.id,
+        folio: sale.folio,
+        return_total: returned.total,
+        sale_total: sale.total,
+        amount_due,
+        refund_total,
+        refund_method: payload.return_request.refund_method,
+        voucher_code,
+        change,
+    })
+}
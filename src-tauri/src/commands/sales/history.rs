@@ -2,6 +2,7 @@ use rusqlite::{Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use tauri::{AppHandle, Manager, State};
+use crate::commands::cash_register::payments::{fetch_sale_payments, SalePaymentView};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SalesFilter {
@@ -65,6 +66,7 @@ pub struct SaleDetailView {
   pub returns: Vec<ReturnSummary>,
   pub voucher: Option<VoucherInfo>,
   pub customer_name: Option<String>,
+  pub payments: Vec<SalePaymentView>,
 }
 
 #[derive(Serialize)]
@@ -108,13 +110,8 @@ pub struct VoucherInfo {
   pub expires_at: Option<String>,
 }
 
-#[tauri::command]
-pub fn get_sales_history(
-  db: State<'_, Mutex<Connection>>,
-  filter: SalesFilter,
-) -> Result<PaginatedSalesResponse, String> {
-  let conn = db.lock().map_err(|e| e.to_string())?;
-
+/// WHERE clause (over `sales s`) and its parameters for a history filter.
+pub(crate) fn sales_filter_sql(filter: &SalesFilter) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
   let mut where_clauses = vec!["s.sale_date IS NOT NULL".to_string()];
   let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
@@ -176,17 +173,10 @@ pub fn get_sales_history(
     }
   }
 
-  let where_sql = where_clauses.join(" AND ");
-
-  let count_sql = format!("SELECT COUNT(*) FROM sales s WHERE {}", where_sql);
-  let total_rows: i64 = conn
-    .query_row(
-      &count_sql,
-      rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
-      |row| row.get(0),
-    )
-    .map_err(|e| format!("Error contando ventas: {}", e))?;
+  (where_clauses.join(" AND "), params)
+}
 
+pub(crate) fn sales_order_sql(filter: &SalesFilter) -> String {
   let sort_column = match filter.sort_by.as_deref().unwrap_or("folio") {
     "sale_date" => "s.sale_date",
     "total" => "s.total",
@@ -201,6 +191,29 @@ pub fn get_sales_history(
     _ => "DESC",
   };
 
+  format!("{} {}", sort_column, sort_direction)
+}
+
+#[tauri::command]
+pub fn get_sales_history(
+  db: State<'_, Mutex<Connection>>,
+  filter: SalesFilter,
+) -> Result<PaginatedSalesResponse, String> {
+  let conn = db.lock().map_err(|e| e.to_string())?;
+
+  let (where_sql, params) = sales_filter_sql(&filter);
+
+  let count_sql = format!("SELECT COUNT(*) FROM sales s WHERE {}", where_sql);
+  let total_rows: i64 = conn
+    .query_row(
+      &count_sql,
+      rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
+      |row| row.get(0),
+    )
+    .map_err(|e| format!("Error contando ventas: {}", e))?;
+
+  let order_sql = sales_order_sql(&filter);
+
   let limit = filter.page_size;
   let offset = (filter.page - 1) * filter.page_size;
 
@@ -211,9 +224,9 @@ pub fn get_sales_history(
      FROM sales s
      LEFT JOIN users u ON s.user_id = u.id
      WHERE {}
-     ORDER BY {} {}
+     ORDER BY {}
      LIMIT {} OFFSET {}",
-    where_sql, sort_column, sort_direction, limit, offset
+    where_sql, order_sql, limit, offset
   );
 
   let mut stmt = conn.prepare(&list_sql).map_err(|e| e.to_string())?;
@@ -305,7 +318,6 @@ pub fn get_sale_details(
         .query_row(voucher_sql, [&sale_id], |row| row.get(0))
         .unwrap_or(0.0);
 
-      let change = if cash > total { cash - total + voucher_amount } else { 0.0 };
       Ok(SaleDetailView {
         id: row.get(0)?,
         folio: row.get(1)?,
@@ -319,7 +331,7 @@ pub fn get_sale_details(
         cash_amount: cash,
         card_amount: row.get(10).unwrap_or(0.0),
         voucher_amount: voucher_amount,
-        change_returned: change,
+        change_returned: 0.0,
         notes: row.get(11)?,
         cancellation_reason: row.get(14).unwrap_or(None),
         cancelled_at: row.get(15).unwrap_or(None),
@@ -330,6 +342,7 @@ pub fn get_sale_details(
         items: Vec::new(),
         returns: Vec::new(),
         voucher: None,
+        payments: Vec::new(),
       })
     })
     .map_err(|e| format!("Venta no encontrada: {}", e))?;
@@ -444,5 +457,12 @@ pub fn get_sale_details(
     })
   }).optional().map_err(|e| format!("Error consultando vale: {}", e))?;
 
+  sale.payments = fetch_sale_payments(&conn, &sale_id)?;
+  sale.change_returned = sale
+    .payments
+    .iter()
+    .map(|p| (p.tendered - p.amount).max(0.0))
+    .sum();
+
   Ok(sale)
 }
@@ -1,3 +1,5 @@
 pub mod cancel;
+pub mod exchange;
 pub mod history;
+pub mod no_receipt;
 pub mod returns;
\ No newline at end of file
@@ -0,0 +1,423 @@
+use rusqlite::types::ToSql;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::commands::sales::returns::{
+    ensure_cash_available, ensure_products_and_update_inventory, generate_voucher_code,
+    ReturnItemRequest, ReturnResponse,
+};
+use crate::commands::settings::business::get_store_id;
+use crate::commands::settings::permissions::require_permission;
+use crate::database::DynamicQuery;
+
+#[derive(Debug, Deserialize)]
+pub struct ReturnCandidateFilters {
+    pub product_id: Option<String>,
+    pub customer_id: Option<String>,
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReturnCandidate {
+    pub sale_id: String,
+    pub folio: String,
+    pub sale_date: String,
+    pub customer_name: Option<String>,
+    pub sale_item_id: String,
+    pub product_id: String,
+    pub product_name: String,
+    pub quantity: f64,
+    pub returned_quantity: f64,
+    pub available_quantity: f64,
+    pub unit_price: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoReceiptItemRequest {
+    pub product_id: String,
+    pub quantity: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NoReceiptReturnRequest {
+    pub customer_id: String,
+    pub reason: String,
+    pub notes: String,
+    pub user_id: String,
+    pub refund_method: String, // 'store_voucher', 'cash'
+    pub items: Vec<NoReceiptItemRequest>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NoReceiptLimits {
+    pub max_returns_per_day: i64,
+    pub max_amount_per_day: f64,
+    pub returns_today: i64,
+    pub amount_today: f64,
+}
+
+fn get_setting_or<T: std::str::FromStr>(conn: &Connection, key: &str, default: T) -> T {
+    conn.query_row(
+        "SELECT value FROM system_settings WHERE key = ?1",
+        [key],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(default)
+}
+
+/// Lowest net unit price the product sold for in the configured window,
+/// falling back to the current retail price when it has no recent sales.
+fn lowest_recent_price(conn: &Connection, product_id: &str) -> Result<f64, String> {
+    let days: i64 = get_setting_or(conn, "no_receipt_price_days", 30);
+    let since = (chrono::Local::now() - chrono::Duration::days(days))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+
+    let lowest: Option<f64> = conn
+        .query_row(
+            "SELECT MIN(si.total / si.quantity)
+             FROM sale_items si
+             JOIN sales s ON s.id = si.sale_id
+             WHERE si.product_id = ?1
+               AND s.created_at >= ?2
+               AND s.status != 'cancelled'
+               AND si.quantity > 0
+               AND si.total > 0",
+            params![product_id, since],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    match lowest {
+        Some(price) => Ok(price),
+        None => conn
+            .query_row(
+                "SELECT retail_price FROM products WHERE id = ?1",
+                [product_id],
+                |row| row.get(0),
+            )
+            .map_err(|_| format!("Producto no encontrado: {}", product_id)),
+    }
+}
+
+fn fetch_limits(conn: &Connection, customer_id: &str) -> Result<NoReceiptLimits, String> {
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+
+    let (returns_today, amount_today): (i64, f64) = conn
+        .query_row(
+            "SELECT COUNT(*), COALESCE(SUM(total), 0)
+             FROM returns
+             WHERE customer_id = ?1 AND sale_id IS NULL AND return_date LIKE ?2",
+            params![customer_id, format!("{}%", today)],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(NoReceiptLimits {
+        max_returns_per_day: get_setting_or(conn, "no_receipt_daily_max_returns", 1),
+        max_amount_per_day: get_setting_or(conn, "no_receipt_daily_max_amount", 500.0),
+        returns_today,
+        amount_today,
+    })
+}
+
+/// Finds sale lines that can still be returned, to link a ticketless return to its sale.
+#[tauri::command]
+pub fn find_return_candidates(
+    db: State<Mutex<Connection>>,
+    filters: ReturnCandidateFilters,
+    user_id: String,
+) -> Result<Vec<ReturnCandidate>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    require_permission(&conn, &user_id, "history:return_no_receipt")?;
+
+    let mut dq = DynamicQuery::new();
+
+    if let Some(product_id) = filters.product_id.filter(|v| !v.trim().is_empty()) {
+        dq.add_condition("si.product_id = ?");
+        dq.add_param(product_id);
+    }
+    if let Some(customer_id) = filters.customer_id.filter(|v| !v.trim().is_empty()) {
+        dq.add_condition("s.customer_id = ?");
+        dq.add_param(customer_id);
+    }
+    if dq.sql_parts.is_empty() {
+        return Err("Indique un producto o un cliente para buscar la venta.".to_string());
+    }
+    if let Some(date_from) = filters.date_from.filter(|v| !v.trim().is_empty()) {
+        dq.add_condition("s.sale_date >= ?");
+        dq.add_param(format!("{} 00:00:00", date_from.trim()));
+    }
+    if let Some(date_to) = filters.date_to.filter(|v| !v.trim().is_empty()) {
+        dq.add_condition("s.sale_date <= ?");
+        dq.add_param(format!("{} 23:59:59", date_to.trim()));
+    }
+
+    let sql = format!(
+        "SELECT s.id, s.folio, s.sale_date, c.name, si.id, si.product_id, si.product_name,
+                si.quantity, COALESCE(ri.returned_qty, 0), si.total / si.quantity
+         FROM sale_items si
+         JOIN sales s ON s.id = si.sale_id
+         LEFT JOIN customers c ON c.id = s.customer_id
+         LEFT JOIN (
+            SELECT sale_item_id, SUM(quantity) AS returned_qty
+            FROM return_items
+            WHERE sale_item_id IS NOT NULL
+            GROUP BY sale_item_id
+         ) ri ON ri.sale_item_id = si.id
+         WHERE s.status IN ('completed', 'partial_return')
+           AND si.quantity > 0
+           AND (si.quantity - COALESCE(ri.returned_qty, 0)) > 0.001
+           AND {}
+         ORDER BY s.sale_date DESC
+         LIMIT 100",
+        dq.sql_parts.join(" AND ")
+    );
+
+    let params_refs: Vec<&dyn ToSql> = dq.params.iter().map(|p| p.as_ref() as &dyn ToSql).collect();
+
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let candidates = stmt
+        .query_map(rusqlite::params_from_iter(params_refs.iter()), |row| {
+            let quantity: f64 = row.get(7)?;
+            let returned_quantity: f64 = row.get(8)?;
+            Ok(ReturnCandidate {
+                sale_id: row.get(0)?,
+                folio: row.get(1)?,
+                sale_date: row.get(2)?,
+                customer_name: row.get(3)?,
+                sale_item_id: row.get(4)?,
+                product_id: row.get(5)?,
+                product_name: row.get(6)?,
+                quantity,
+                returned_quantity,
+                available_quantity: quantity - returned_quantity,
+                unit_price: row.get(9)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(candidates)
+}
+
+/// Price a ticketless return would pay for one unit of the product.
+#[tauri::command]
+pub fn get_no_receipt_return_price(
+    db: State<Mutex<Connection>>,
+    product_id: String,
+    user_id: String,
+) -> Result<f64, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    require_permission(&conn, &user_id, "history:return_no_receipt")?;
+    lowest_recent_price(&conn, &product_id)
+}
+
+#[tauri::command]
+pub fn get_no_receipt_limits(
+    db: State<Mutex<Connection>>,
+    customer_id: String,
+    user_id: String,
+) -> Result<NoReceiptLimits, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    require_permission(&conn, &user_id, "history:return_no_receipt")?;
+    fetch_limits(&conn, &customer_id)
+}
+
+/// Return not linked to any sale, priced at the lowest recent selling price.
+#[tauri::command]
+pub fn process_no_receipt_return(
+    app_handle: tauri::AppHandle,
+    db: State<Mutex<Connection>>,
+    payload: NoReceiptReturnRequest,
+) -> Result<ReturnResponse, String> {
+    if payload.items.is_empty() {
+        return Err("No hay productos para devolver".to_string());
+    }
+    if payload.reason.trim().is_empty() {
+        return Err("Debe especificar un motivo de devolución".to_string());
+    }
+    if payload.items.iter().any(|i| i.quantity <= 0.0) {
+        return Err("Las cantidades deben ser mayores a 0".to_string());
+    }
+
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    require_permission(&tx, &payload.user_id, "history:return_no_receipt")?;
+
+    let open_shift: Option<(i64, f64)> = tx
+        .query_row(
+            "SELECT id, initial_cash FROM cash_register_shifts WHERE status = 'open' LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    // Price every line at the lowest recent price
+    let mut validated_items: Vec<(ReturnItemRequest, String, String, String)> = Vec::new();
+    for item in &payload.items {
+        let (name, code): (String, String) = tx
+            .query_row(
+                "SELECT name, code FROM products WHERE id = ?1",
+                [&item.product_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|_| format!("Producto no encontrado: {}", item.product_id))?;
+
+        let unit_price = lowest_recent_price(&tx, &item.product_id)?;
+
+        validated_items.push((
+            ReturnItemRequest {
+                sale_item_id: String::new(),
+                product_id: item.product_id.clone(),
+                quantity: item.quantity,
+                unit_price,
+            },
+            name,
+            code,
+            item.product_id.clone(),
+        ));
+    }
+
+    let return_total: f64 = validated_items
+        .iter()
+        .map(|(i, _, _, _)| i.unit_price * i.quantity)
+        .sum();
+    if return_total <= 0.0 {
+        return Err("El total de devolución debe ser > 0".to_string());
+    }
+
+    // Daily limits per customer
+    let limits = fetch_limits(&tx, &payload.customer_id)?;
+    if limits.returns_today >= limits.max_returns_per_day {
+        return Err(format!(
+            "El cliente alcanzó el límite de {} devoluciones sin ticket por día.",
+            limits.max_returns_per_day
+        ));
+    }
+    if limits.amount_today + return_total > limits.max_amount_per_day + 0.01 {
+        return Err(format!(
+            "Límite diario sin ticket excedido. Disponible hoy: ${:.2}, Solicitado: ${:.2}",
+            (limits.max_amount_per_day - limits.amount_today).max(0.0),
+            return_total
+        ));
+    }
+
+    let shift_id = match payload.refund_method.as_str() {
+        "store_voucher" => open_shift.map(|(id, _)| id),
+        "cash" => {
+            require_permission(&tx, &payload.user_id, "history:refund_cash")?;
+            Some(ensure_cash_available(&tx, open_shift, return_total)?)
+        }
+        _ => {
+            return Err(
+                "Las devoluciones sin ticket solo se reembolsan en efectivo o con vale.".to_string(),
+            )
+        }
+    };
+
+    let now_local = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let return_id = Uuid::new_v4().to_string();
+    let folio: i64 = tx
+        .query_row(
+            "SELECT COALESCE(MAX(folio), 0) + 1 FROM returns",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(1);
+
+    tx.execute(
+        "INSERT INTO returns (id, folio, sale_id, return_date, total, reason, notes, refund_method, user_id, customer_id, cash_register_shift_id, created_at)
+         VALUES (?1, ?2, NULL, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?3)",
+        params![
+            return_id,
+            folio,
+            now_local,
+            return_total,
+            payload.reason.trim(),
+            payload.notes.trim(),
+            payload.refund_method,
+            payload.user_id,
+            payload.customer_id,
+            shift_id,
+        ],
+    )
+    .map_err(|e| format!("Error creando registro de devolución: {}", e))?;
+
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT INTO return_items (id, return_id, sale_item_id, product_id, quantity, unit_price, subtotal)
+                 VALUES (?1, ?2, NULL, ?3, ?4, ?5, ?6)",
+            )
+            .map_err(|e| e.to_string())?;
+
+        for (item, _, _, product_id) in &validated_items {
+            stmt.execute(params![
+                Uuid::new_v4().to_string(),
+                return_id,
+                product_id,
+                item.quantity,
+                item.unit_price,
+                item.unit_price * item.quantity
+            ])
+            .map_err(|e| format!("Error creando item de devolución: {}", e))?;
+        }
+    }
+
+    // Dedicated kardex reason so ticketless returns stand out in the audit trail
+    let store_id = get_store_id(&tx)?;
+    ensure_products_and_update_inventory(
+        &tx,
+        &validated_items,
+        &store_id,
+        &return_id,
+        &payload.user_id,
+        "RETURN_NO_RECEIPT",
+    )?;
+
+    let voucher_code = if payload.refund_method == "store_voucher" {
+        // 'D' + return folio keeps these apart from sale folios
+        let code = generate_voucher_code(&format!("D{:08}", folio));
+        tx.execute(
+            "INSERT INTO store_vouchers (id, sale_id, return_id, code, initial_balance, current_balance, is_active, created_at, updated_at)
+             VALUES (?1, NULL, ?2, ?3, ?4, ?4, 1, ?5, ?5)",
+            params![Uuid::new_v4().to_string(), return_id, code, return_total, now_local],
+        )
+        .map_err(|e| format!("Error creando vale: {}", e))?;
+        code
+    } else {
+        String::new()
+    };
+
+    tx.commit().map_err(|e| e.to_string())?;
+
+    if !voucher_code.is_empty() {
+        let app_handle_clone = app_handle.clone();
+        let code_clone = voucher_code.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let _ = crate::printer_utils::print_voucher_by_code(app_handle_clone, code_clone);
+        });
+    } else {
+        let app_handle_clone = app_handle.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let _ = crate::printer_utils::kick_drawer_direct(&app_handle_clone, false);
+        });
+    }
+
+    Ok(ReturnResponse {
+        return_id,
+        voucher_code,
+        total: return_total,
+        refund_method: payload.refund_method,
+    })
+}
@@ -4,7 +4,10 @@ use std::collections::HashMap;
 use std::sync::Mutex;
 use tauri::State;
 use uuid::Uuid;
+use crate::commands::cash_register::payments::fetch_payment_split;
+use crate::commands::cash_register::shifts::calculate_shift_totals;
 use crate::commands::settings::business::get_store_id;
+use crate::commands::settings::permissions::require_permission;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ReturnItemRequest {
@@ -21,6 +24,8 @@ pub struct ProcessReturnRequest {
     pub notes: String,
     pub user_id: String,
     pub items: Vec<ReturnItemRequest>,
+    #[serde(default = "default_refund_method")]
+    pub refund_method: String, // 'store_voucher', 'cash', 'card', 'credit'
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -28,9 +33,14 @@ pub struct ReturnResponse {
     pub return_id: String,
     pub voucher_code: String,
     pub total: f64,
+    pub refund_method: String,
 }
 
-fn generate_voucher_code(sale_folio: &str) -> String {
+fn default_refund_method() -> String {
+    "store_voucher".to_string()
+}
+
+pub(crate) fn generate_voucher_code(sale_folio: &str) -> String {
     format!("V{}", sale_folio)
 }
 
@@ -378,12 +388,132 @@ fn manage_store_voucher(
     }
 }
 
-fn ensure_products_and_update_inventory(
+/// Validates the refund method against the user's permissions and the original
+/// sale's payment split. Returns the open shift (if any) the refund is booked to.
+fn validate_refund_method(
+    tx: &Connection,
+    payload: &ProcessReturnRequest,
+    return_id: &str,
+    return_total: f64,
+) -> Result<Option<i64>, String> {
+    let open_shift: Option<(i64, f64)> = tx
+        .query_row(
+            "SELECT id, initial_cash FROM cash_register_shifts WHERE status = 'open' LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    let (permission, label) = match payload.refund_method.as_str() {
+        _ if return_total <= 0.001 => return Ok(open_shift.map(|(id, _)| id)),
+        "store_voucher" => return Ok(open_shift.map(|(id, _)| id)),
+        "cash" => ("history:refund_cash", "efectivo"),
+        "card" => ("history:refund_card", "tarjeta/transferencia"),
+        "credit" => ("history:refund_credit", "crédito"),
+        other => return Err(format!("Método de reembolso no válido: {}", other)),
+    };
+
+    require_permission(tx, &payload.user_id, permission)?;
+
+    let payments = fetch_payment_split(tx, &payload.sale_id)?;
+    let paid_with_method = match payload.refund_method.as_str() {
+        "cash" => payments.cash,
+        "card" => payments.card,
+        "credit" => payments.credit,
+        _ => 0.0,
+    };
+
+    let already_refunded: f64 = tx
+        .query_row(
+            "SELECT COALESCE(SUM(total - exchange_amount), 0) FROM returns
+             WHERE sale_id = ?1 AND refund_method = ?2 AND id != ?3",
+            params![payload.sale_id, payload.refund_method, return_id],
+            |row| row.get(0),
+        )
+        .unwrap_or(0.0);
+
+    let available = (paid_with_method - already_refunded).max(0.0);
+    if return_total > available + 0.01 {
+        return Err(format!(
+            "El reembolso en {} excede lo pagado con ese método. Disponible: ${:.2}, Solicitado: ${:.2}",
+            label, available, return_total
+        ));
+    }
+
+    if payload.refund_method != "cash" {
+        return Ok(open_shift.map(|(id, _)| id));
+    }
+
+    ensure_cash_available(tx, open_shift, return_total).map(Some)
+}
+
+/// Checks that the open shift's drawer can pay out `amount` and returns the shift id.
+pub(crate) fn ensure_cash_available(
+    tx: &Connection,
+    open_shift: Option<(i64, f64)>,
+    amount: f64,
+) -> Result<i64, String> {
+    let (shift_id, initial_cash) = open_shift
+        .ok_or("Se requiere un turno abierto para reembolsar en efectivo.".to_string())?;
+
+    let totals = calculate_shift_totals(tx, shift_id, initial_cash);
+    if totals.total_cash < amount - 0.01 {
+        return Err(format!(
+            "Efectivo insuficiente en caja. Disponible: ${:.2}, Solicitado: ${:.2}",
+            totals.total_cash, amount
+        ));
+    }
+
+    Ok(shift_id)
+}
+
+/// Reduces the customer's outstanding balance for returns on credit sales
+fn refund_to_credit_balance(
+    tx: &Connection,
+    sale_id: &str,
+    return_total: f64,
+) -> Result<(), String> {
+    let customer_id: String = tx
+        .query_row(
+            "SELECT customer_id FROM sales WHERE id = ?1 AND customer_id IS NOT NULL",
+            [sale_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| "La venta no tiene un cliente asociado.".to_string())?;
+
+    let current_balance: f64 = tx
+        .query_row(
+            "SELECT current_balance FROM customers WHERE id = ?1",
+            [&customer_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| "Cliente no encontrado.".to_string())?;
+
+    if current_balance < return_total - 0.01 {
+        return Err(format!(
+            "El saldo del cliente (${:.2}) es menor al reembolso (${:.2}). Utilice otro método de reembolso.",
+            current_balance, return_total
+        ));
+    }
+
+    let now_local = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    tx.execute(
+        "UPDATE customers SET current_balance = MAX(current_balance - ?1, 0), updated_at = ?2 WHERE id = ?3",
+        params![return_total, now_local, customer_id],
+    )
+    .map_err(|e| format!("Error actualizando saldo cliente: {}", e))?;
+
+    Ok(())
+}
+
+pub(crate) fn ensure_products_and_update_inventory(
     tx: &Connection,
     validated_items: &[(ReturnItemRequest, String, String, String)],
     store_id: &str,
     return_id: &str,
     user_id: &str,
+    reason: &str,
 ) -> Result<(), String> {
     let product_ids: Vec<&String> = validated_items.iter().map(|(_, _, _, pid)| pid).collect();
     if product_ids.is_empty() {
@@ -406,7 +536,7 @@ fn ensure_products_and_update_inventory(
             "INSERT INTO inventory_movements (
             id, product_id, store_id, user_id, type, reason,
             quantity, previous_stock, new_stock, reference, created_at
-        ) VALUES (?1, ?2, ?3, ?4, 'IN', 'RETURN', ?5, ?6, ?7, ?8, ?9)",
+        ) VALUES (?1, ?2, ?3, ?4, 'IN', ?5, ?6, ?7, ?8, ?9, ?10)",
         )
         .map_err(|e| e.to_string())?;
 
@@ -438,6 +568,7 @@ fn ensure_products_and_update_inventory(
                 actual_product_id,
                 store_id,
                 user_id,
+                reason,
                 qty_i64,
                 current_stock,
                 new_stock,
@@ -473,7 +604,7 @@ fn create_return_records(
         .unwrap_or(1);
 
     tx.execute(
-        "INSERT INTO returns (id, folio, sale_id, return_date, total, reason, notes, refund_method, user_id, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'store_voucher', ?8, ?4)",
+        "INSERT INTO returns (id, folio, sale_id, return_date, total, reason, notes, refund_method, user_id, customer_id, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, (SELECT customer_id FROM sales WHERE id = ?3), ?4)",
         params![
             return_id,
             folio,
@@ -482,6 +613,7 @@ fn create_return_records(
             return_total,
             payload.reason.trim(),
             payload.notes.trim(),
+            payload.refund_method,
             payload.user_id,
         ]
     ).map_err(|e| format!("Error creando registro de devolución: {}", e))?;
@@ -584,14 +716,18 @@ fn update_sale_status(tx: &Connection, sale_id: &str) -> Result<(), String> {
     Ok(())
 }
 
-#[tauri::command]
-pub fn process_return(
-    app_handle: tauri::AppHandle,
-    db: State<Mutex<Connection>>,
-    payload: ProcessReturnRequest,
-) -> Result<ReturnResponse, String> {
-    let mut conn = db.lock().map_err(|e| e.to_string())?;
+/// Outcome of a return recorded inside a caller-owned transaction.
+pub(crate) struct RecordedReturn {
+    pub return_id: String,
+    pub total: f64,
+}
 
+/// Validates and records a return (kit/promotion rules, inventory, kardex, sale status) on `tx`.
+/// The refund itself is paid out separately through `settle_refund`.
+pub(crate) fn record_return(
+    tx: &Connection,
+    payload: &ProcessReturnRequest,
+) -> Result<RecordedReturn, String> {
     if payload.items.is_empty() {
         return Err("No hay productos para devolver".to_string());
     }
@@ -599,8 +735,6 @@ pub fn process_return(
         return Err("Debe especificar un motivo de devolución".to_string());
     }
 
-    let tx = conn.transaction().map_err(|e| e.to_string())?;
-
     let mut validated_items: Vec<(ReturnItemRequest, String, String, String)> = Vec::new();
     let mut available_quantities: HashMap<String, f64> = HashMap::new();
 
@@ -693,10 +827,10 @@ pub fn process_return(
     }
 
     // Complex Validations
-    validate_kit_instances(&tx, &payload.sale_id, &payload.items, &available_quantities)?;
-    validate_promotion_instances(&tx, &payload.sale_id, &payload.items, &available_quantities)?;
+    validate_kit_instances(tx, &payload.sale_id, &payload.items, &available_quantities)?;
+    validate_promotion_instances(tx, &payload.sale_id, &payload.items, &available_quantities)?;
 
-    // Calculate Total & Process Voucher
+    // Calculate Total
     let return_total: f64 = validated_items
         .iter()
         .map(|(i, _, _, _)| i.unit_price * i.quantity)
@@ -705,24 +839,73 @@ pub fn process_return(
         return Err("El total de devolución debe ser > 0".to_string());
     }
 
-    let voucher_code = manage_store_voucher(&tx, &payload.sale_id, return_total)?;
-
     // Update Inventory + Registrar movimientos de devolución
-    let store_id = get_store_id(&tx)?;
+    let store_id = get_store_id(tx)?;
     let return_id = Uuid::new_v4().to_string();
     ensure_products_and_update_inventory(
-        &tx,
+        tx,
         &validated_items,
         &store_id,
         &return_id,
         &payload.user_id,
+        "RETURN",
     )?;
 
     // Create Return Records
-    create_return_records(&tx, &payload, return_total, &validated_items, &return_id)?;
+    create_return_records(tx, payload, return_total, &validated_items, &return_id)?;
 
     // Update Sale Status
-    update_sale_status(&tx, &payload.sale_id)?;
+    update_sale_status(tx, &payload.sale_id)?;
+
+    Ok(RecordedReturn {
+        return_id,
+        total: return_total,
+    })
+}
+
+/// Pays out `refund_amount` of a recorded return through the payload's refund method.
+/// Returns the store voucher code when the refund was issued as a voucher.
+pub(crate) fn settle_refund(
+    tx: &Connection,
+    payload: &ProcessReturnRequest,
+    return_id: &str,
+    refund_amount: f64,
+) -> Result<String, String> {
+    let shift_id = validate_refund_method(tx, payload, return_id, refund_amount)?;
+
+    let voucher_code = if refund_amount <= 0.001 {
+        String::new()
+    } else {
+        match payload.refund_method.as_str() {
+            "store_voucher" => manage_store_voucher(tx, &payload.sale_id, refund_amount)?,
+            "credit" => {
+                refund_to_credit_balance(tx, &payload.sale_id, refund_amount)?;
+                String::new()
+            }
+            _ => String::new(),
+        }
+    };
+
+    tx.execute(
+        "UPDATE returns SET cash_register_shift_id = ?1 WHERE id = ?2",
+        params![shift_id, return_id],
+    )
+    .map_err(|e| format!("Error registrando turno de la devolución: {}", e))?;
+
+    Ok(voucher_code)
+}
+
+#[tauri::command]
+pub fn process_return(
+    app_handle: tauri::AppHandle,
+    db: State<Mutex<Connection>>,
+    payload: ProcessReturnRequest,
+) -> Result<ReturnResponse, String> {
+    let mut conn = db.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let recorded = record_return(&tx, &payload)?;
+    let voucher_code = settle_refund(&tx, &payload, &recorded.return_id, recorded.total)?;
 
     tx.commit().map_err(|e| e.to_string())?;
 
@@ -733,11 +916,17 @@ pub fn process_return(
         tauri::async_runtime::spawn_blocking(move || {
             let _ = crate::printer_utils::print_voucher_from_db(app_handle_clone, sale_id_clone);
         });
+    } else if payload.refund_method == "cash" {
+        let app_handle_clone = app_handle.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let _ = crate::printer_utils::kick_drawer_direct(&app_handle_clone, false);
+        });
     }
 
     Ok(ReturnResponse {
-        return_id,
+        return_id: recorded.return_id,
         voucher_code,
-        total: return_total,
+        total: recorded.total,
+        refund_method: payload.refund_method,
     })
 }
@@ -1,3 +1,4 @@
+use crate::barcode_utils::validate_internal_prefix;
 use crate::printer_utils;
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
@@ -27,6 +28,13 @@ pub struct BusinessSettings {
     pub discount_preset_options: String,
     pub max_discount_percentage: f64,
     pub max_open_tickets: i64,
+    /// Prefix (20-29) for in-store EAN-13 codes
+    pub internal_barcode_prefix: String,
+    /// Give new products without a barcode an in-store one (opt-in)
+    pub auto_internal_barcode: bool,
+    /// Idle rows of the customer display; an empty top row shows the store name
+    pub customer_display_top_line: String,
+    pub customer_display_bottom_line: String,
 }
 
 impl Default for BusinessSettings {
@@ -50,6 +58,10 @@ impl Default for BusinessSettings {
             discount_preset_options: "5,10".to_string(),
             max_discount_percentage: 20.0,
             max_open_tickets: 5,
+            internal_barcode_prefix: "200".to_string(),
+            auto_internal_barcode: false,
+            customer_display_top_line: String::new(),
+            customer_display_bottom_line: "Bienvenido".to_string(),
         }
     }
 }
@@ -158,6 +170,22 @@ pub fn fetch_business_settings(conn: &Connection) -> Result<BusinessSettings, St
             .get("max_open_tickets")
             .and_then(|v| v.parse().ok())
             .unwrap_or(5),
+        internal_barcode_prefix: settings_map
+            .get("internal_barcode_prefix")
+            .cloned()
+            .unwrap_or("200".to_string()),
+        auto_internal_barcode: settings_map
+            .get("auto_internal_barcode")
+            .map(|v| v == "true")
+            .unwrap_or(false),
+        customer_display_top_line: settings_map
+            .get("customer_display_top_line")
+            .cloned()
+            .unwrap_or_default(),
+        customer_display_bottom_line: settings_map
+            .get("customer_display_bottom_line")
+            .cloned()
+            .unwrap_or("Bienvenido".to_string()),
     })
 }
 
@@ -182,6 +210,10 @@ pub struct BusinessSettingsPatch {
     pub discount_preset_options: Option<String>,
     pub max_discount_percentage: Option<f64>,
     pub max_open_tickets: Option<i64>,
+    pub internal_barcode_prefix: Option<String>,
+    pub auto_internal_barcode: Option<bool>,
+    pub customer_display_top_line: Option<String>,
+    pub customer_display_bottom_line: Option<String>,
 }
 
 #[tauri::command]
@@ -189,6 +221,10 @@ pub fn update_business_settings(
     state: State<'_, Mutex<Connection>>,
     settings: BusinessSettingsPatch,
 ) -> Result<(), String> {
+    if let Some(prefix) = &settings.internal_barcode_prefix {
+        validate_internal_prefix(prefix.trim())?;
+    }
+
     let conn = state.lock().map_err(|e| e.to_string())?;
 
     conn.execute_batch("BEGIN TRANSACTION;")
@@ -254,6 +290,18 @@ pub fn update_business_settings(
     if let Some(v) = settings.max_open_tickets {
         params.push(("max_open_tickets", v.to_string()));
     }
+    if let Some(v) = settings.internal_barcode_prefix {
+        params.push(("internal_barcode_prefix", v.trim().to_string()));
+    }
+    if let Some(v) = settings.auto_internal_barcode {
+        params.push(("auto_internal_barcode", v.to_string()));
+    }
+    if let Some(v) = settings.customer_display_top_line {
+        params.push(("customer_display_top_line", v));
+    }
+    if let Some(v) = settings.customer_display_bottom_line {
+        params.push(("customer_display_bottom_line", v));
+    }
 
     // Check for logical_store_name change to migrate inventory
     let old_store_id: Option<String> = if settings.logical_store_name.is_some() {
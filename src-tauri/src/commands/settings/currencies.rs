@@ -0,0 +1,178 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::commands::settings::permissions::require_permission;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Currency {
+    pub code: String,
+    pub name: String,
+    pub symbol: String,
+    pub is_active: bool,
+    /// Today's rate (base currency per unit), if it has been captured.
+    pub rate: Option<f64>,
+    pub rate_date: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CurrencyInput {
+    pub code: String,
+    pub name: String,
+    pub symbol: String,
+    pub is_active: bool,
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+fn fetch_currency(conn: &Connection, code: &str) -> Result<Currency, String> {
+    conn.query_row(
+        "SELECT c.code, c.name, c.symbol, c.is_active, er.rate, er.rate_date
+         FROM currencies c
+         LEFT JOIN exchange_rates er ON er.currency_code = c.code AND er.rate_date = ?2
+         WHERE c.code = ?1",
+        params![code, today()],
+        |row| {
+            Ok(Currency {
+                code: row.get(0)?,
+                name: row.get(1)?,
+                symbol: row.get(2)?,
+                is_active: row.get(3)?,
+                rate: row.get(4)?,
+                rate_date: row.get(5)?,
+            })
+        },
+    )
+    .map_err(|_| format!("Moneda no encontrada: {}", code))
+}
+
+/// Rate captured today for an active currency. Foreign tenders are rejected without it.
+pub fn today_exchange_rate(conn: &Connection, code: &str) -> Result<f64, String> {
+    let currency = fetch_currency(conn, code)?;
+    if !currency.is_active {
+        return Err(format!("La moneda {} no está habilitada.", code));
+    }
+    currency
+        .rate
+        .filter(|r| *r > 0.0)
+        .ok_or(format!("No se ha capturado el tipo de cambio de hoy para {}.", code))
+}
+
+#[tauri::command]
+pub fn get_currencies(db: State<Mutex<Connection>>) -> Result<Vec<Currency>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT c.code, c.name, c.symbol, c.is_active, er.rate, er.rate_date
+             FROM currencies c
+             LEFT JOIN exchange_rates er ON er.currency_code = c.code AND er.rate_date = ?1
+             ORDER BY c.code ASC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([today()], |row| {
+            Ok(Currency {
+                code: row.get(0)?,
+                name: row.get(1)?,
+                symbol: row.get(2)?,
+                is_active: row.get(3)?,
+                rate: row.get(4)?,
+                rate_date: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn save_currency(
+    db: State<Mutex<Connection>>,
+    currency: CurrencyInput,
+    user_id: String,
+) -> Result<Currency, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+
+    require_permission(&conn, &user_id, "cash_register:exchange_rate")?;
+
+    let code = currency.code.trim().to_uppercase();
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err("El código de moneda debe tener 3 letras (ej. USD).".to_string());
+    }
+    if currency.name.trim().is_empty() || currency.symbol.trim().is_empty() {
+        return Err("El nombre y el símbolo de la moneda son obligatorios.".to_string());
+    }
+
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    conn.execute(
+        "INSERT INTO currencies (code, name, symbol, is_active, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+         ON CONFLICT(code) DO UPDATE SET
+            name = excluded.name, symbol = excluded.symbol,
+            is_active = excluded.is_active, updated_at = excluded.updated_at",
+        params![
+            code,
+            currency.name.trim(),
+            currency.symbol.trim(),
+            currency.is_active,
+            now
+        ],
+    )
+    .map_err(|e| format!("Error guardando moneda: {}", e))?;
+
+    fetch_currency(&conn, &code)
+}
+
+#[tauri::command]
+pub fn set_exchange_rate(
+    db: State<Mutex<Connection>>,
+    currency_code: String,
+    rate: f64,
+    user_id: String,
+) -> Result<Currency, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+
+    require_permission(&conn, &user_id, "cash_register:exchange_rate")?;
+
+    if !rate.is_finite() || rate <= 0.0 {
+        return Err("El tipo de cambio debe ser mayor a cero.".to_string());
+    }
+
+    let currency_code = currency_code.trim().to_uppercase();
+    let exists: Option<String> = conn
+        .query_row(
+            "SELECT code FROM currencies WHERE code = ?1",
+            [&currency_code],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    if exists.is_none() {
+        return Err(format!("Moneda no encontrada: {}", currency_code));
+    }
+
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    conn.execute(
+        "INSERT INTO exchange_rates (id, currency_code, rate_date, rate, user_id, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+         ON CONFLICT(currency_code, rate_date) DO UPDATE SET
+            rate = excluded.rate, user_id = excluded.user_id, updated_at = excluded.updated_at",
+        params![
+            Uuid::new_v4().to_string(),
+            currency_code,
+            today(),
+            rate,
+            user_id,
+            now
+        ],
+    )
+    .map_err(|e| format!("Error guardando tipo de cambio: {}", e))?;
+
+    fetch_currency(&conn, &currency_code)
+}
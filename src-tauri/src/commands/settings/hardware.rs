@@ -1,5 +1,7 @@
+use crate::customer_display::CustomerDisplayConfig;
+use crate::payment_terminal::PaymentTerminalConfig;
+use crate::printer_transport::open_transport;
 use chrono::Local;
-use printers::common::base::job::PrinterJobOptions;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use tauri::{command, AppHandle, Manager};
@@ -14,6 +16,54 @@ pub struct HardwareConfig {
     pub cash_drawer_port: Option<String>,
     pub auto_open_cash_drawer: bool,
     pub padding_lines: Option<u32>,
+    #[serde(default)]
+    pub payment_terminal: PaymentTerminalConfig,
+    /// 'spooler', 'tcp' (raw 9100) or 'serial'
+    #[serde(default = "default_printer_transport")]
+    pub printer_transport: String,
+    /// host[:port] for the tcp transport
+    #[serde(default)]
+    pub printer_address: Option<String>,
+    /// COM3, /dev/ttyUSB0... for the serial transport
+    #[serde(default)]
+    pub printer_serial_port: Option<String>,
+    /// Baud rate of the serial transport (8N1)
+    #[serde(default = "default_printer_serial_baud")]
+    pub printer_serial_baud: u32,
+    /// Refuse sales that print a ticket while the printer reports paper-end
+    #[serde(default)]
+    pub block_sale_on_paper_out: bool,
+    /// 'native' (GS ( k) or 'raster' for printers without QR support
+    #[serde(default = "default_qr_mode")]
+    pub qr_mode: String,
+    /// Dots per QR module, 1-16
+    #[serde(default = "default_qr_module_size")]
+    pub qr_module_size: u8,
+    /// 'L', 'M', 'Q' or 'H'
+    #[serde(default = "default_qr_error_correction")]
+    pub qr_error_correction: String,
+    #[serde(default)]
+    pub customer_display: CustomerDisplayConfig,
+}
+
+fn default_printer_transport() -> String {
+    "spooler".to_string()
+}
+
+fn default_printer_serial_baud() -> u32 {
+    9600
+}
+
+fn default_qr_mode() -> String {
+    "native".to_string()
+}
+
+fn default_qr_module_size() -> u8 {
+    6
+}
+
+fn default_qr_error_correction() -> String {
+    "M".to_string()
 }
 
 impl Default for HardwareConfig {
@@ -26,6 +76,16 @@ impl Default for HardwareConfig {
             cash_drawer_port: Some("COM1".to_string()),
             auto_open_cash_drawer: true,
             padding_lines: Some(0),
+            payment_terminal: PaymentTerminalConfig::default(),
+            printer_transport: default_printer_transport(),
+            printer_address: None,
+            printer_serial_port: None,
+            printer_serial_baud: default_printer_serial_baud(),
+            block_sale_on_paper_out: false,
+            qr_mode: default_qr_mode(),
+            qr_module_size: default_qr_module_size(),
+            qr_error_correction: default_qr_error_correction(),
+            customer_display: CustomerDisplayConfig::default(),
         }
     }
 }
@@ -85,52 +145,64 @@ pub fn get_system_printers() -> Result<Vec<String>, String> {
     Ok(names)
 }
 
+/// Sends a test ticket. `config` lets the settings screen test unsaved values;
+/// `printer_name` always wins for the spooler transport.
 #[command]
-pub fn test_printer_connection(printer_name: String) -> Result<String, String> {
-    let printers = printers::get_printers();
-    if let Some(printer) = printers.iter().find(|p| p.name == printer_name) {
-        let now = Local::now().format("%d/%m/%Y %H:%M:%S").to_string();
-
-        // Comandos ESC/POS
-        // ESC @ = Inicializar impresora (Borra buffer, resetea modos)
-        let init_cmd = b"\x1B@";
-        // ESC 2 = Espaciado de línea por defecto (aprox 3.75mm o 1/6 pulgada)
-        // Fundamental para evitar que se encimen las líneas
-        let default_line_spacing = b"\x1B2";
-
-        // Ticket básico de prueba
-        let test_ticket = format!(
-            "\n\
-            --------------------------------\n\
-                   PRUEBA DE CONEXION       \n\
-            --------------------------------\n\
-            Dispositivo: {}\n\
-            Fecha:       {}\n\
-            \n\
-            [ OK ] Sistema de impresion\n\
-            [ OK ] Conexion establecida\n\
-            \n\
-            Si puedes leer esto, tu\n\
-            impresora funciona correctamente.\n\
-            \n\
-            Chulada POS\n\
-            --------------------------------\n\
-            \n\n\n",
-            printer_name, now
-        );
-
-        // Construir el trabajo de impresión concatenando bytes
-        let mut job_content = Vec::new();
-        job_content.extend_from_slice(init_cmd);
-        job_content.extend_from_slice(default_line_spacing);
-        job_content.extend_from_slice(test_ticket.as_bytes());
-
-        match printer.print(&job_content, PrinterJobOptions::none()) {
-            Ok(_) => Ok(format!("Ticket de prueba enviado a {}", printer_name)),
-            Err(e) => Err(format!("Error al imprimir: {:?}", e)),
-        }
-    } else {
-        Err(format!("Impresora '{}' no encontrada", printer_name))
+pub fn test_printer_connection(
+    app_handle: AppHandle,
+    printer_name: String,
+    config: Option<HardwareConfig>,
+) -> Result<String, String> {
+    let mut config = match config {
+        Some(config) => config,
+        None => load_settings(app_handle)?,
+    };
+    if !printer_name.is_empty() {
+        config.printer_name = Some(printer_name);
+    }
+
+    let transport = open_transport(&config)?
+        .ok_or("No hay una impresora configurada".to_string())?;
+    let destination = transport.describe();
+    let now = Local::now().format("%d/%m/%Y %H:%M:%S").to_string();
+
+    // Comandos ESC/POS
+    // ESC @ = Inicializar impresora (Borra buffer, resetea modos)
+    let init_cmd = b"\x1B@";
+    // ESC 2 = Espaciado de línea por defecto (aprox 3.75mm o 1/6 pulgada)
+    // Fundamental para evitar que se encimen las líneas
+    let default_line_spacing = b"\x1B2";
+
+    // Ticket básico de prueba
+    let test_ticket = format!(
+        "\n\
+        --------------------------------\n\
+               PRUEBA DE CONEXION       \n\
+        --------------------------------\n\
+        Dispositivo: {}\n\
+        Fecha:       {}\n\
+        \n\
+        [ OK ] Sistema de impresion\n\
+        [ OK ] Conexion establecida\n\
+        \n\
+        Si puedes leer esto, tu\n\
+        impresora funciona correctamente.\n\
+        \n\
+        Chulada POS\n\
+        --------------------------------\n\
+        \n\n\n",
+        destination, now
+    );
+
+    // Construir el trabajo de impresión concatenando bytes
+    let mut job_content = Vec::new();
+    job_content.extend_from_slice(init_cmd);
+    job_content.extend_from_slice(default_line_spacing);
+    job_content.extend_from_slice(test_ticket.as_bytes());
+
+    match transport.send(&job_content) {
+        Ok(_) => Ok(format!("Ticket de prueba enviado a {}", destination)),
+        Err(e) => Err(format!("Error al imprimir: {}", e)),
     }
 }
 
@@ -150,23 +222,30 @@ fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, String> {
 }
 
 #[command]
-pub fn test_cash_drawer(printer_name: String, command_hex: String) -> Result<String, String> {
-    if printer_name.is_empty() || printer_name == "none" {
-        return Err("No hay impresora configurada para probar el cajón".to_string());
+pub fn test_cash_drawer(
+    app_handle: AppHandle,
+    printer_name: String,
+    command_hex: String,
+    config: Option<HardwareConfig>,
+) -> Result<String, String> {
+    let mut config = match config {
+        Some(config) => config,
+        None => load_settings(app_handle)?,
+    };
+    if !printer_name.is_empty() {
+        config.printer_name = Some(printer_name);
     }
-    let printers = printers::get_printers();
 
-    if let Some(printer) = printers.iter().find(|p| p.name == printer_name) {
-        let bytes = hex_to_bytes(&command_hex)?;
+    let transport = open_transport(&config)?
+        .ok_or("No hay impresora configurada para probar el cajón".to_string())?;
+    let bytes = hex_to_bytes(&command_hex)?;
 
-        match printer.print(&bytes, PrinterJobOptions::none()) {
-            Ok(_) => Ok(format!("Comando enviado a {}", printer_name)),
-            Err(e) => Err(format!("Error al enviar comando al cajón: {:?}", e)),
-        }
-    } else {
-        Err(format!("Impresora '{}' no encontrada. El cajón debe estar conectado a una impresora configurada.", printer_name))
+    match transport.send(&bytes) {
+        Ok(_) => Ok(format!("Comando enviado a {}", transport.describe())),
+        Err(e) => Err(format!("Error al enviar comando al cajón: {}", e)),
     }
 }
+
 #[command]
 pub fn open_cash_drawer(app_handle: AppHandle) -> Result<(), String> {
     crate::printer_utils::kick_drawer_direct(&app_handle, false)
@@ -1,5 +1,7 @@
 // Commands related to user settings
 pub mod business;
+pub mod currencies;
 pub mod hardware;
 pub mod permissions;
+pub mod receipt_templates;
 pub mod users;
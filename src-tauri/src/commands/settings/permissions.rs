@@ -249,6 +249,42 @@ fn insert_permissions(tx: &Transaction, to_insert: &[&RolePermission]) -> SqlRes
     Ok(())
 }
 
+/// Verifica en BD si el rol del usuario tiene el permiso indicado (el admin siempre lo tiene).
+pub fn user_has_permission(conn: &Connection, user_id: &str, permission: &str) -> Result<bool, String> {
+    let role_id: String = conn
+        .query_row(
+            "SELECT role_id FROM users WHERE id = ?1 AND deleted_at IS NULL",
+            [user_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| "Usuario no encontrado".to_string())?;
+
+    if role_id == ADMIN_ROLE_ID {
+        return Ok(true);
+    }
+
+    let count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM role_permissions rp
+             INNER JOIN permissions p ON p.id = rp.permission_id
+             WHERE rp.role_id = ?1 AND p.name = ?2 AND p.is_active = 1",
+            params![role_id, permission],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Error SQL: {}", e))?;
+
+    Ok(count > 0)
+}
+
+/// Igual que `user_has_permission`, pero regresa un error listo para mostrar.
+pub fn require_permission(conn: &Connection, user_id: &str, permission: &str) -> Result<(), String> {
+    if user_has_permission(conn, user_id, permission)? {
+        Ok(())
+    } else {
+        Err(format!("No tienes permiso para realizar esta acción ({}).", permission))
+    }
+}
+
 // ─── IDs de roles nativos
 const ROLE_ADMIN:   &str = "550e8400-e29b-41d4-a716-446655440001";
 const ROLE_MANAGER: &str = "550e8400-e29b-41d4-a716-446655440002";
@@ -310,6 +346,13 @@ const DEFAULT_ROLE_PERMISSIONS: &[(&str, &str)] = &[
     (ROLE_ADMIN, "650e8400-e29b-41d4-a716-446655440131"), // ticket_settings:view
     (ROLE_ADMIN, "650e8400-e29b-41d4-a716-446655440132"), // ticket_settings:edit
     (ROLE_ADMIN, "650e8400-e29b-41d4-a716-446655440133"), // ticket_settings:print
+    (ROLE_ADMIN, "650e8400-e29b-41d4-a716-446655440134"), // history:refund_cash
+    (ROLE_ADMIN, "650e8400-e29b-41d4-a716-446655440135"), // history:refund_card
+    (ROLE_ADMIN, "650e8400-e29b-41d4-a716-446655440136"), // history:refund_credit
+    (ROLE_ADMIN, "650e8400-e29b-41d4-a716-446655440137"), // history:return_no_receipt
+    (ROLE_ADMIN, "650e8400-e29b-41d4-a716-446655440138"), // history:cancel_closed_shift
+    (ROLE_ADMIN, "650e8400-e29b-41d4-a716-446655440139"), // cash_register:exchange_rate
+    (ROLE_ADMIN, "650e8400-e29b-41d4-a716-446655440140"), // cash_register:z_report
     (ROLE_ADMIN, "650e8400-e29b-41d5-a716-446655440128"), // hardware_settings:upload_backups
     (ROLE_ADMIN, "650e8400-e29b-41d5-a716-446655440129"), // hardware_settings:download_backups
     (ROLE_ADMIN, "960e8400-e29b-41d4-a716-446655440001"), // customers:view
@@ -372,6 +415,13 @@ const DEFAULT_ROLE_PERMISSIONS: &[(&str, &str)] = &[
     (ROLE_MANAGER, "650e8400-e29b-41d4-a716-446655440131"), // ticket_settings:view
     (ROLE_MANAGER, "650e8400-e29b-41d4-a716-446655440132"), // ticket_settings:edit
     (ROLE_MANAGER, "650e8400-e29b-41d4-a716-446655440133"), // ticket_settings:print
+    (ROLE_MANAGER, "650e8400-e29b-41d4-a716-446655440134"), // history:refund_cash
+    (ROLE_MANAGER, "650e8400-e29b-41d4-a716-446655440135"), // history:refund_card
+    (ROLE_MANAGER, "650e8400-e29b-41d4-a716-446655440136"), // history:refund_credit
+    (ROLE_MANAGER, "650e8400-e29b-41d4-a716-446655440137"), // history:return_no_receipt
+    (ROLE_MANAGER, "650e8400-e29b-41d4-a716-446655440138"), // history:cancel_closed_shift
+    (ROLE_MANAGER, "650e8400-e29b-41d4-a716-446655440139"), // cash_register:exchange_rate
+    (ROLE_MANAGER, "650e8400-e29b-41d4-a716-446655440140"), // cash_register:z_report
     (ROLE_MANAGER, "650e8400-e29b-41d5-a716-446655440128"), // hardware_settings:upload_backups
     (ROLE_MANAGER, "960e8400-e29b-41d4-a716-446655440001"), // customers:view
     (ROLE_MANAGER, "960e8400-e29b-41d4-a716-446655440002"), // customers:create
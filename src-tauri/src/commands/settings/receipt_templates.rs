@@ -0,0 +1,668 @@
+use crate::commands::cash_register::details::ShiftDetailsDto;
+use crate::commands::cash_register::payments::SalePaymentView;
+use crate::commands::settings::business::fetch_business_settings;
+use crate::commands::settings::hardware::{load_settings, HardwareConfig};
+use crate::commands::settings::permissions::require_permission;
+use crate::printer_utils::{
+    payment_template_context, sale_template_context, shift_template_context,
+    voucher_template_context, PaymentReceiptData, TicketData, TicketItem, VoucherReceiptData,
+};
+use crate::receipt_template::{PlainTextSink, ReceiptTemplate, TemplateContext};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+
+pub const DOCUMENT_TYPES: [&str; 4] = ["sale", "voucher", "payment", "shift"];
+
+#[derive(Debug, Serialize)]
+pub struct ReceiptTemplateDto {
+    pub document_type: String,
+    pub content: String,
+    pub is_active: bool,
+    /// False when `content` is the starter template (nothing saved yet)
+    pub is_custom: bool,
+    pub updated_at: Option<String>,
+}
+
+const STORE_HEADER: &str = "@section header
+@logo
+@align center
+@if store_name
+@bold on
+{{store_name}}
+@bold off
+@endif
+@if store_address
+{{store_address}}
+@endif
+
+@if ticket_header
+{{ticket_header}}
+
+@endif
+@endsection
+";
+
+const STORE_FOOTER: &str = "@section footer
+@align center
+@if ticket_footer
+
+{{ticket_footer}}
+
+@endif
+@endsection
+@cut
+";
+
+const SALE_BODY: &str = "@align left
+Folio: {{folio}}
+Fecha: {{date}}
+TOTAL ARTICULOS: {{item_count|money|right:10}}
+@if customer_name
+Cliente: {{customer_name}}
+@if customer_code
+No. Cliente: {{customer_code}}
+@endif
+@endif
+@separator -
+@cols 4 * 9> | CANT | DESCRIPCION | IMPORTE
+@separator -
+@each lines
+@cols 4 * 9> | {{quantity|qty}} | {{description}} | {{total|money}}
+@endeach
+@each combos
+@bold on
+COMBO: {{name}}
+@bold off
+@each items
+  {{quantity|qty}}x {{description}}
+@endeach
+  Precio Promo: {{total|money|right:10}}
+
+@endeach
+@separator -
+@align right
+SUBTOTAL: {{subtotal|money|right:10}}
+@if discount
+DESCUENTO: {{discount|money|right:10}}
+@endif
+@bold on
+TOTAL: {{total|money|right:10}}
+@bold off
+@each payments
+@if currency
+{{method}} {{currency}}: {{foreign_amount|money|right:10}}
+T.C. {{exchange_rate}} = {{tendered|money|right:10}}
+@else
+{{method}}: {{tendered|money|right:10}}
+@endif
+@if card_last4
+{{card_brand}} ****{{card_last4}}
+@endif
+@if auth_code
+AUT: {{auth_code}}
+@else
+@if reference
+REF: {{reference}}
+@endif
+@endif
+@endeach
+CAMBIO: {{change|money|right:10}}
+";
+
+const VOUCHER_BODY: &str = "@separator =
+@align center
+@bold on
+@size double
+VALE DE TIENDA
+@size normal
+@bold off
+
+@bold on
+Codigo: {{code}}
+@bold off
+@barcode {{code}}
+@separator -
+@bold on
+@size tall
+SALDO: ${{balance|money}}
+@size normal
+@bold off
+
+@separator -
+@align left
+@if sale_folio
+Venta original: {{sale_folio}}
+@else
+@if return_folio
+Devolucion sin ticket: {{return_folio}}
+@endif
+@endif
+Fecha emision: {{created_at}}
+@align center
+Este vale es valido para compras
+en tienda. No es canjeable
+por efectivo.
+";
+
+const PAYMENT_BODY: &str = "@separator -
+@align center
+@bold on
+@size double
+COMPROBANTE DE ABONO
+@size normal
+@bold off
+
+@bold on
+Folio: {{folio}}
+@bold off
+
+@separator -
+@bold on
+@size double
+${{amount|money}}
+@size normal
+@bold off
+
+@separator -
+@align left
+Fecha: {{date}}
+No. Cliente: {{customer_code}}
+Cliente: {{customer_name}}
+Metodo: {{method}}
+
+@if cash_amount
+  Efectivo: ${{cash_amount|money}}
+@endif
+@if card_amount
+  Tarjeta:  ${{card_amount|money}}
+@endif
+";
+
+const SHIFT_BODY: &str = "@align center
+@bold on
+@size tall
+CORTE DE CAJA
+@size normal
+@bold off
+Impreso: {{printed_at}}
+
+@align left
+@if code
+Folio: {{code}}
+@else
+Folio: -
+@endif
+Apertura: {{opening_date}}
+@if closing_date
+Cierre: {{closing_date}}
+@endif
+@if opening_user
+Abre: {{opening_user}}
+@else
+Abre: Desc.
+@endif
+@if closing_user
+Cierra: {{closing_user}}
+@else
+Cierra: Desc.
+@endif
+@if total_sales
+@align center
+@bold on
+TOTAL DE VENTAS
+@bold off
+@align left
+Cantidad de ventas: {{sales_count|int}}
+@separator -
+@align center
+@bold on
+RESUMEN DE VENTAS
+@bold off
+@align left
+@if total_cash_sales
+@row Ventas Efectivo: | ${{total_cash_sales|money}}
+@endif
+@if total_card_sales
+@row Ventas Tarjeta: | ${{total_card_sales|money}}
+@endif
+@if total_credit_sales
+@row Ventas Credito: | ${{total_credit_sales|money}}
+@endif
+@if total_voucher_sales
+@row Ventas Cupones: | ${{total_voucher_sales|money}}
+@endif
+@if total_gift_card_sales
+@row Ventas Tarj. Regalo: | ${{total_gift_card_sales|money}}
+@endif
+@if total_points_sales
+@row Ventas Puntos: | ${{total_points_sales|money}}
+@endif
+@if total_exchange_sales
+@row Ventas por Cambio: | ${{total_exchange_sales|money}}
+@endif
+@bold on
+@row Total Ventas: | ${{total_sales|money}}
+@bold off
+@separator -
+@endif
+@if total_debt_payments
+@align center
+@bold on
+ABONOS A DEUDAS
+@bold off
+@align left
+@if debt_payments_cash
+@row Abonos Efectivo: | ${{debt_payments_cash|money}}
+@endif
+@if debt_payments_card
+@row Abonos Tarjeta: | ${{debt_payments_card|money}}
+@endif
+@bold on
+@row Total Abonos: | ${{total_debt_payments|money}}
+@bold off
+@endif
+@if movements_in
+@separator -
+@align center
+@bold on
+ENTRADAS DE EFECTIVO
+@bold off
+@align left
+@each movements_in
+@row {{concept}} | ${{amount|money}}
+@if note
+  Nota: {{note}}
+@endif
+@endeach
+@bold on
+@row Total Entradas: | ${{total_movements_in|money}}
+@bold off
+@endif
+@if movements_out
+@separator -
+@align center
+@bold on
+SALIDAS DE EFECTIVO
+@bold off
+@align left
+@each movements_out
+@row {{concept}} | ${{amount|money}}
+@if note
+  Nota: {{note}}
+@endif
+@endeach
+@bold on
+@row Total Salidas: | ${{total_movements_out|money}}
+@bold off
+@separator -
+@endif
+@if has_cash_activity
+@align center
+@bold on
+TOTAL EFECTIVO
+@bold off
+@align left
+@row Fondo Inicial: | +${{initial_cash|money}}
+@row Ventas Efectivo: | +${{total_cash_sales|money}}
+@row Abonos Efectivo: | +${{debt_payments_cash|money}}
+@row Entradas Efectivo: | +${{total_movements_in|money}}
+@row Salidas Efectivo: | -${{total_movements_out|money}}
+@if total_cash_refunds
+@row Reembolsos Efectivo: | -${{total_cash_refunds|money}}
+@endif
+@if cash_reversals
+@row Cancel. Turnos Ant.: | -${{cash_reversals|money}}
+@endif
+@each foreign_cash
+@row Cobrado en {{currency}}: | -${{base_amount|money}}
+@endeach
+@bold on
+@row Total Efectivo: | ${{total_cash|money}}
+@separator -
+@if cash_withdrawal
+@row Monto a Retirar: | ${{cash_withdrawal|money}}
+@endif
+@bold off
+@endif
+@if has_foreign_cash
+@separator -
+@align center
+@bold on
+MONEDA EXTRANJERA
+@bold off
+@align left
+@each foreign_cash
+@row Efectivo {{currency}}: | {{amount|money}}
+@endeach
+@each currency_counts
+@row Contado {{currency}}: | {{counted|money}}
+@row Diferencia {{currency}}: | {{difference|money}}
+@endeach
+@endif
+@if has_card_activity
+@separator -
+@align center
+@bold on
+TOTAL TARJETA
+@bold off
+@align left
+@row Ventas Tarjeta: | +${{total_card_sales|money}}
+@row Abonos Tarjeta: | +${{debt_payments_card|money}}
+@if total_card_refunds
+@row Reembolsos Tarjeta: | -${{total_card_refunds|money}}
+@endif
+@if card_reversals
+@row Cancel. Turnos Ant.: | -${{card_reversals|money}}
+@endif
+@bold on
+@row Total Tarjeta: | ${{total_card|money}}
+@bold off
+@endif
+@if notes
+@separator -
+@align left
+@bold on
+NOTAS DEL CIERRE:
+@bold off
+{{notes}}
+@endif
+";
+
+/// Built-in layout for a document type; printed whenever no custom
+/// template is active and offered as the starting point for editing.
+pub fn default_template(document_type: &str) -> String {
+    let (body, footer) = match document_type {
+        "sale" => (SALE_BODY, STORE_FOOTER),
+        "voucher" => (VOUCHER_BODY, STORE_FOOTER),
+        "payment" => (PAYMENT_BODY, STORE_FOOTER),
+        // The shift summary has no store footer
+        _ => (SHIFT_BODY, "@cut\n"),
+    };
+    format!("{}{}{}", STORE_HEADER, body, footer)
+}
+
+fn validate_document_type(document_type: &str) -> Result<(), String> {
+    if DOCUMENT_TYPES.contains(&document_type) {
+        Ok(())
+    } else {
+        Err(format!("Tipo de documento inválido: {}", document_type))
+    }
+}
+
+/// Content of the active custom template, if any.
+pub(crate) fn fetch_active_template(
+    conn: &Connection,
+    document_type: &str,
+) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT content FROM receipt_templates WHERE document_type = ?1 AND is_active = 1",
+        [document_type],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_receipt_templates(
+    db: State<'_, Mutex<Connection>>,
+) -> Result<Vec<ReceiptTemplateDto>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+
+    DOCUMENT_TYPES
+        .iter()
+        .map(|document_type| {
+            let stored: Option<(String, bool, String)> = conn
+                .query_row(
+                    "SELECT content, is_active, updated_at FROM receipt_templates WHERE document_type = ?1",
+                    [document_type],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .optional()
+                .map_err(|e| e.to_string())?;
+
+            Ok(match stored {
+                Some((content, is_active, updated_at)) => ReceiptTemplateDto {
+                    document_type: document_type.to_string(),
+                    content,
+                    is_active,
+                    is_custom: true,
+                    updated_at: Some(updated_at),
+                },
+                None => ReceiptTemplateDto {
+                    document_type: document_type.to_string(),
+                    content: default_template(document_type),
+                    is_active: false,
+                    is_custom: false,
+                    updated_at: None,
+                },
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub fn save_receipt_template(
+    db: State<'_, Mutex<Connection>>,
+    user_id: String,
+    document_type: String,
+    content: String,
+    is_active: bool,
+) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    require_permission(&conn, &user_id, "ticket_settings:edit")?;
+    validate_document_type(&document_type)?;
+    ReceiptTemplate::parse(&content)?;
+
+    conn.execute(
+        "INSERT INTO receipt_templates (document_type, content, is_active, updated_by, updated_at)
+         VALUES (?1, ?2, ?3, ?4, datetime('now', 'localtime'))
+         ON CONFLICT(document_type) DO UPDATE SET
+            content = excluded.content,
+            is_active = excluded.is_active,
+            updated_by = excluded.updated_by,
+            updated_at = excluded.updated_at",
+        params![document_type, content, is_active, user_id],
+    )
+    .map_err(|e| format!("Error guardando plantilla: {}", e))?;
+
+    Ok(())
+}
+
+/// Deletes the custom template so the built-in layout is printed again.
+#[tauri::command]
+pub fn reset_receipt_template(
+    db: State<'_, Mutex<Connection>>,
+    user_id: String,
+    document_type: String,
+) -> Result<(), String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    require_permission(&conn, &user_id, "ticket_settings:edit")?;
+    validate_document_type(&document_type)?;
+
+    conn.execute(
+        "DELETE FROM receipt_templates WHERE document_type = ?1",
+        [&document_type],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+fn sample_payment(method: &str, amount: f64, tendered: f64) -> SalePaymentView {
+    SalePaymentView {
+        id: String::new(),
+        method: method.to_string(),
+        amount,
+        tendered,
+        reference: None,
+        currency_code: None,
+        foreign_amount: None,
+        exchange_rate: None,
+        auth_code: None,
+        card_last4: None,
+        card_brand: None,
+    }
+}
+
+fn sample_item(quantity: f64, description: &str, unit_price: f64, promotion: Option<&str>) -> TicketItem {
+    TicketItem {
+        quantity,
+        description: description.to_string(),
+        unit_price,
+        total: quantity * unit_price,
+        promotion_id: promotion.map(|_| "promo".to_string()),
+        promotion_name: promotion.map(str::to_string),
+        id: String::new(),
+    }
+}
+
+/// Sample data for previews, using the real store settings.
+fn sample_context(conn: &Connection, document_type: &str) -> Result<TemplateContext, String> {
+    let settings = fetch_business_settings(conn).unwrap_or_default();
+    let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+    Ok(match document_type {
+        "sale" => {
+            let data = TicketData {
+                business_settings: settings,
+                hardware_config: HardwareConfig::default(),
+                folio: "00001234".to_string(),
+                date: now,
+                items: vec![
+                    sample_item(2.0, "Refresco 600 ml", 18.5, None),
+                    sample_item(1.0, "Galletas surtidas", 32.0, Some("2x1 Galletas")),
+                    sample_item(0.75, "Queso fresco (kg)", 140.0, None),
+                ],
+                subtotal: 174.0,
+                discount: 8.7,
+                total: 165.3,
+                payments: vec![
+                    sample_payment("cash", 100.0, 100.0),
+                    sample_payment("card_transfer", 65.3, 65.3),
+                ],
+                change: 0.0,
+                customer_name: Some("Cliente de Ejemplo".to_string()),
+                customer_code: Some("C-0001".to_string()),
+            };
+            sale_template_context(&data)
+        }
+        "voucher" => {
+            let data = VoucherReceiptData {
+                code: "VALE-7F3K2Q".to_string(),
+                balance: 250.0,
+                created_at: now,
+                sale_folio: Some("00001234".to_string()),
+                return_folio: None,
+            };
+            voucher_template_context(&settings, &data)
+        }
+        "payment" => {
+            let data = PaymentReceiptData {
+                folio: "AB-000045".to_string(),
+                amount: 500.0,
+                cash_amount: 300.0,
+                card_amount: 200.0,
+                payment_method: "mixed".to_string(),
+                payment_date: now,
+                customer_name: "Cliente de Ejemplo".to_string(),
+                customer_code: "C-0001".to_string(),
+            };
+            payment_template_context(&settings, &data)
+        }
+        _ => {
+            let details: ShiftDetailsDto = serde_json::from_value(serde_json::json!({
+                "shift": {
+                    "id": 12, "initial_cash": 500.0, "opening_date": now,
+                    "opening_user_id": "", "opening_user_name": "Cajero de Ejemplo",
+                    "status": "closed", "code": "T-000012", "closing_date": now,
+                    "closing_user_name": "Cajero de Ejemplo", "cash_withdrawal": 2300.0,
+                    "notes": "Sin novedades."
+                },
+                "movements": [
+                    { "id": 1, "shift_id": 12, "type_": "IN", "amount": 200.0,
+                      "concept": "Cambio", "description": null, "created_at": now },
+                    { "id": 2, "shift_id": 12, "type_": "OUT", "amount": 150.0,
+                      "concept": "Proveedor", "description": "Pago de garrafones", "created_at": now }
+                ],
+                "total_movements_in": 200.0, "total_movements_out": 150.0,
+                "sales_count": 42, "total_sales": 4850.0, "total_cash_sales": 2450.0,
+                "total_card_sales": 1900.0, "total_credit_sales": 500.0,
+                "total_voucher_sales": 0.0, "total_gift_card_sales": 0.0,
+                "total_points_sales": 0.0, "total_exchange_sales": 0.0,
+                "total_debt_payments": 300.0, "debt_payments_cash": 300.0,
+                "debt_payments_card": 0.0, "total_cash_refunds": 0.0,
+                "total_card_refunds": 0.0, "total_reversals": 0.0,
+                "cash_reversals": 0.0, "card_reversals": 0.0,
+                "foreign_cash": [], "currency_counts": [], "total_cash": 3300.0
+            }))
+            .map_err(|e| e.to_string())?;
+            shift_template_context(&settings, &details)
+        }
+    })
+}
+
+/// Renders a template as plain text with sample data.
+/// Uses `content` when given (unsaved edits), otherwise the stored or starter template.
+#[tauri::command]
+pub fn preview_receipt_template(
+    app_handle: AppHandle,
+    db: State<'_, Mutex<Connection>>,
+    document_type: String,
+    content: Option<String>,
+    printer_width: Option<u32>,
+) -> Result<String, String> {
+    validate_document_type(&document_type)?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
+
+    let content = match content {
+        Some(content) => content,
+        None => conn
+            .query_row(
+                "SELECT content FROM receipt_templates WHERE document_type = ?1",
+                [&document_type],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?
+            .unwrap_or_else(|| default_template(&document_type)),
+    };
+    let template = ReceiptTemplate::parse(&content)?;
+    let context = sample_context(&conn, &document_type)?;
+    drop(conn);
+
+    let width = printer_width.unwrap_or_else(|| {
+        load_settings(app_handle)
+            .ok()
+            .and_then(|config| config.printer_width.parse::<u32>().ok())
+            .unwrap_or(80)
+    });
+
+    let mut sink = PlainTextSink::new(width);
+    template.render(&context, &mut sink);
+    Ok(sink.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_built_in_layout_parses() {
+        for document_type in DOCUMENT_TYPES {
+            if let Err(e) = ReceiptTemplate::parse(&default_template(document_type)) {
+                panic!("'{}': {}", document_type, e);
+            }
+        }
+    }
+
+    #[test]
+    fn oversized_widths_and_feeds_are_rejected() {
+        assert!(ReceiptTemplate::parse("{{total|right:257}}").is_err());
+        assert!(ReceiptTemplate::parse("@cols 300 * | a | b").is_err());
+        assert!(ReceiptTemplate::parse("@feed 21").is_err());
+        assert!(ReceiptTemplate::parse("{{total|right:10}}\n@feed 3").is_ok());
+    }
+}
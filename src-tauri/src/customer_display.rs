@@ -0,0 +1,188 @@
+// Customer-facing display.
+//
+// Two outputs, usable together:
+//   serial - VFD pole display (2 rows x 20 columns by default) on a COM port
+//   window - a second app window, meant for a screen facing the customer
+// Every update is rendered to the same text rows; the window also gets the
+// event itself so it can show more detail.
+//
+// Pole display command sets:
+//   epson  - ESC/POS customer displays (DM-D and compatibles)
+//   cd5220 - ESC Q A / ESC Q B line commands
+//   aedex  - !#1 / !#2 line commands
+
+use serde::{Deserialize, Serialize};
+
+use crate::printer_utils::remove_accents;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CustomerDisplayConfig {
+    /// 'none', 'serial', 'window' or 'both'
+    pub driver: String,
+    /// COM3, /dev/ttyUSB0...
+    pub serial_port: Option<String>,
+    /// Pole displays usually ship at 9600 8N1
+    pub baud_rate: u32,
+    /// 'epson', 'cd5220' or 'aedex'
+    pub command_set: String,
+    pub columns: usize,
+    /// Monitor index for the customer window; `None` opens it as a normal window
+    pub window_monitor: Option<usize>,
+}
+
+impl Default for CustomerDisplayConfig {
+    fn default() -> Self {
+        Self {
+            driver: "none".to_string(),
+            serial_port: None,
+            baud_rate: 9600,
+            command_set: "epson".to_string(),
+            columns: 20,
+            window_monitor: None,
+        }
+    }
+}
+
+impl CustomerDisplayConfig {
+    pub fn uses_serial(&self) -> bool {
+        matches!(self.driver.as_str(), "serial" | "both")
+    }
+
+    pub fn uses_window(&self) -> bool {
+        matches!(self.driver.as_str(), "window" | "both")
+    }
+}
+
+/// Something the customer should see, sent by the sale flow.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum DisplayEvent {
+    /// Idle messages from the business settings
+    Idle,
+    #[serde(rename_all = "camelCase")]
+    LineAdded {
+        description: String,
+        quantity: f64,
+        unit_price: f64,
+        /// Ticket total so far
+        subtotal: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    Total { total: f64 },
+    #[serde(rename_all = "camelCase")]
+    Change { total: f64, paid: f64, change: f64 },
+}
+
+/// Text shown while no sale is in progress.
+pub struct IdleMessages {
+    pub top: String,
+    pub bottom: String,
+}
+
+fn money(symbol: &str, amount: f64) -> String {
+    format!("{}{:.2}", symbol, amount)
+}
+
+fn quantity(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.3}", value).trim_end_matches('0').to_string()
+    }
+}
+
+/// `left` and `right` on one row of `width`; `left` is cut to make room.
+fn fit(left: &str, right: &str, width: usize) -> String {
+    let right_len = right.chars().count();
+    if right_len >= width {
+        return right.chars().take(width).collect();
+    }
+    let room = width - right_len;
+    let left: String = left.chars().take(room.saturating_sub(1)).collect();
+    let pad = width - left.chars().count() - right_len;
+    format!("{}{}{}", left, " ".repeat(pad), right)
+}
+
+fn center(text: &str, width: usize) -> String {
+    let text: String = text.chars().take(width).collect();
+    let pad = width - text.chars().count();
+    format!("{}{}", " ".repeat(pad / 2), text)
+}
+
+/// The two text rows for an event.
+pub fn render_rows(event: &DisplayEvent, idle: &IdleMessages, symbol: &str, width: usize) -> [String; 2] {
+    match event {
+        DisplayEvent::Idle => [center(&idle.top, width), center(&idle.bottom, width)],
+        DisplayEvent::LineAdded {
+            description,
+            quantity: qty,
+            unit_price,
+            subtotal,
+        } => {
+            let label = if *qty == 1.0 {
+                description.clone()
+            } else {
+                format!("{}x {}", quantity(*qty), description)
+            };
+            [
+                fit(&label, &money(symbol, qty * unit_price), width),
+                fit("TOTAL", &money(symbol, *subtotal), width),
+            ]
+        }
+        DisplayEvent::Total { total } => [
+            fit("TOTAL A PAGAR", "", width),
+            fit("", &money(symbol, *total), width),
+        ],
+        DisplayEvent::Change { paid, change, .. } => [
+            fit("PAGO", &money(symbol, *paid), width),
+            fit("CAMBIO", &money(symbol, *change), width),
+        ],
+    }
+}
+
+/// Plain ASCII padded to the display width; other characters become '?'.
+fn vfd_text(text: &str, width: usize) -> Vec<u8> {
+    let mut bytes: Vec<u8> = remove_accents(text)
+        .chars()
+        .take(width)
+        .map(|c| if (' '..='~').contains(&c) { c as u8 } else { b'?' })
+        .collect();
+    bytes.resize(width, b' ');
+    bytes
+}
+
+/// Bytes that replace the pole display contents with `rows`.
+pub fn vfd_commands(config: &CustomerDisplayConfig, rows: &[String; 2]) -> Vec<u8> {
+    let width = config.columns.max(1);
+    let mut out = Vec::new();
+
+    match config.command_set.as_str() {
+        "cd5220" => {
+            out.extend_from_slice(&[0x1B, 0x40, 0x0C]);
+            out.extend_from_slice(&[0x1B, 0x51, 0x41]);
+            out.extend(vfd_text(&rows[0], width));
+            out.push(0x0D);
+            out.extend_from_slice(&[0x1B, 0x51, 0x42]);
+            out.extend(vfd_text(&rows[1], width));
+            out.push(0x0D);
+        }
+        "aedex" => {
+            out.extend_from_slice(b"!#1");
+            out.extend(vfd_text(&rows[0], width));
+            out.push(0x0D);
+            out.extend_from_slice(b"!#2");
+            out.extend(vfd_text(&rows[1], width));
+            out.push(0x0D);
+        }
+        _ => {
+            // ESC @, overwrite mode (US MD1), clear, then US $ x y per row
+            out.extend_from_slice(&[0x1B, 0x40, 0x1F, 0x01, 0x0C]);
+            out.extend_from_slice(&[0x1F, 0x24, 1, 1]);
+            out.extend(vfd_text(&rows[0], width));
+            out.extend_from_slice(&[0x1F, 0x24, 1, 2]);
+            out.extend(vfd_text(&rows[1], width));
+        }
+    }
+    out
+}
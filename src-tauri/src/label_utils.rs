@@ -0,0 +1,269 @@
+// Shelf labels and price tags.
+//
+// Each label shows the product name, retail price, wholesale price and a
+// barcode (CODE128 or EAN-13), any of which can be hidden. Output formats:
+//   zpl    - Zebra label printers
+//   epl    - Eltron/Zebra EPL2 label printers
+//   escpos - receipt printers; one label per cut
+// ZPL and EPL encode the barcode on the printer; ESC/POS prints it as a
+// raster image from `barcode_utils`.
+
+use crate::barcode_utils::{detect_symbology, Symbology};
+use crate::commands::inventory::labels::LabelConfig;
+use crate::printer_utils::{generate_barcode_escpos_as, remove_accents, ReceiptBuilder};
+
+pub struct LabelData {
+    pub name: String,
+    pub price: f64,
+    pub wholesale_price: f64,
+    pub barcode: String,
+}
+
+/// EPL2 resident fonts 1-5: (cell width, cell height) in dots at 203 dpi
+const EPL_FONTS: [(u32, u32); 5] = [(10, 14), (12, 18), (14, 22), (16, 26), (34, 50)];
+
+fn dots_per_mm(config: &LabelConfig) -> f64 {
+    if config.dpi >= 300 {
+        12.0
+    } else {
+        8.0
+    }
+}
+
+/// The 12 data digits for EAN-13 (printers add the check digit), or
+/// `None` when the code is not a valid EAN-13 or UPC-A.
+fn ean13_data(code: &str) -> Option<String> {
+    let code = code.trim();
+    match detect_symbology(code) {
+        Symbology::Ean13 => Some(code[..12].to_string()),
+        Symbology::UpcA => Some(format!("0{}", &code[..11])),
+        _ => None,
+    }
+}
+
+fn format_price(symbol: &str, amount: f64) -> String {
+    format!("{}{:.2}", symbol, amount)
+}
+
+/// Splits a name into at most `max_lines` lines of `width` characters.
+fn wrap_name(name: &str, width: usize, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for word in name.split_whitespace() {
+        let needed = if current.is_empty() { word.chars().count() } else { current.chars().count() + 1 + word.chars().count() };
+        if needed > width && !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+            if lines.len() == max_lines {
+                break;
+            }
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() && lines.len() < max_lines {
+        lines.push(current);
+    }
+
+    lines
+        .into_iter()
+        .map(|line| line.chars().take(width).collect())
+        .collect()
+}
+
+/// Renders `copies` of every label in the configured language.
+pub fn render_labels(config: &LabelConfig, labels: &[LabelData], currency_symbol: &str, copies: u32) -> Vec<u8> {
+    let copies = copies.max(1);
+    match config.language.as_str() {
+        "epl" => render_epl(config, labels, currency_symbol, copies),
+        "escpos" => render_escpos(config, labels, currency_symbol, copies),
+        _ => render_zpl(config, labels, currency_symbol, copies),
+    }
+}
+
+// --- ZPL ---
+
+fn zpl_text(text: &str) -> String {
+    text.replace(['^', '~'], " ")
+}
+
+fn render_zpl(config: &LabelConfig, labels: &[LabelData], symbol: &str, copies: u32) -> Vec<u8> {
+    let d = dots_per_mm(config);
+    let width = (config.width_mm as f64 * d) as u32;
+    let height = (config.height_mm as f64 * d) as u32;
+    let margin = (2.0 * d) as u32;
+    let gap = (d * 0.8) as u32;
+    let inner = width.saturating_sub(margin * 2);
+    let module = if config.dpi >= 300 { 3 } else { 2 };
+
+    let name_h = (height as f64 * 0.12) as u32;
+    let price_h = (height as f64 * 0.22) as u32;
+    let wholesale_h = (height as f64 * 0.10) as u32;
+
+    let mut out = String::new();
+    for label in labels {
+        let mut y = margin;
+        out.push_str(&format!("^XA^CI28^PW{}^LL{}\n", width, height));
+
+        if config.show_name {
+            out.push_str(&format!(
+                "^FO{},{}^A0N,{},{}^FB{},2,0,C^FD{}^FS\n",
+                margin, y, name_h, name_h, inner, zpl_text(&label.name)
+            ));
+            y += name_h * 2 + gap;
+        }
+        if config.show_price {
+            out.push_str(&format!(
+                "^FO{},{}^A0N,{},{}^FB{},1,0,C^FD{}^FS\n",
+                margin, y, price_h, price_h, inner, zpl_text(&format_price(symbol, label.price))
+            ));
+            y += price_h + gap;
+        }
+        if config.show_wholesale_price {
+            out.push_str(&format!(
+                "^FO{},{}^A0N,{},{}^FB{},1,0,C^FDMayoreo: {}^FS\n",
+                margin, y, wholesale_h, wholesale_h, inner,
+                zpl_text(&format_price(symbol, label.wholesale_price))
+            ));
+            y += wholesale_h + gap;
+        }
+        if config.show_barcode && !label.barcode.is_empty() {
+            // Leave room for the human readable line under the bars
+            let bar_h = height.saturating_sub(y + margin + wholesale_h + gap).max(20);
+            match ean13_data(&label.barcode).filter(|_| config.barcode_type == "ean13") {
+                Some(digits) => {
+                    let x = margin + inner.saturating_sub(95 * module) / 2;
+                    out.push_str(&format!("^FO{},{}^BY{}^BEN,{},Y,N^FD{}^FS\n", x, y, module, bar_h, digits));
+                }
+                None => {
+                    let modules = 11 * (label.barcode.len() as u32 + 3) + 2;
+                    let x = margin + inner.saturating_sub(modules * module) / 2;
+                    out.push_str(&format!(
+                        "^FO{},{}^BY{}^BCN,{},Y,N,N^FD{}^FS\n",
+                        x, y, module, bar_h, zpl_text(&label.barcode)
+                    ));
+                }
+            }
+        }
+
+        out.push_str(&format!("^PQ{}\n^XZ\n", copies));
+    }
+    out.into_bytes()
+}
+
+// --- EPL ---
+
+fn epl_text(text: &str) -> String {
+    remove_accents(text).replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// `A` command centered on the label.
+fn epl_centered(out: &mut String, width: u32, y: u32, font: usize, multiplier: u32, text: &str) {
+    let (cell_w, _) = EPL_FONTS[font - 1];
+    let text_w = text.chars().count() as u32 * cell_w * multiplier;
+    let x = width.saturating_sub(text_w) / 2;
+    out.push_str(&format!(
+        "A{},{},0,{},{},{},N,\"{}\"\n",
+        x, y, font, multiplier, multiplier, epl_text(text)
+    ));
+}
+
+fn render_epl(config: &LabelConfig, labels: &[LabelData], symbol: &str, copies: u32) -> Vec<u8> {
+    let d = dots_per_mm(config);
+    let width = (config.width_mm as f64 * d) as u32;
+    let height = (config.height_mm as f64 * d) as u32;
+    let margin = (2.0 * d) as u32;
+    let gap = (d * 0.8) as u32;
+    let module = if config.dpi >= 300 { 3 } else { 2 };
+    let name_chars = (width.saturating_sub(margin * 2) / EPL_FONTS[2].0) as usize;
+
+    let mut out = String::new();
+    for label in labels {
+        let mut y = margin;
+        out.push_str(&format!("\nN\nq{}\nQ{},24\n", width, height));
+
+        if config.show_name {
+            for line in wrap_name(&label.name, name_chars.max(1), 2) {
+                epl_centered(&mut out, width, y, 3, 1, &line);
+                y += EPL_FONTS[2].1;
+            }
+            y += gap;
+        }
+        if config.show_price {
+            epl_centered(&mut out, width, y, 4, 2, &format_price(symbol, label.price));
+            y += EPL_FONTS[3].1 * 2 + gap;
+        }
+        if config.show_wholesale_price {
+            let text = format!("Mayoreo: {}", format_price(symbol, label.wholesale_price));
+            epl_centered(&mut out, width, y, 2, 1, &text);
+            y += EPL_FONTS[1].1 + gap;
+        }
+        if config.show_barcode && !label.barcode.is_empty() {
+            let bar_h = height.saturating_sub(y + margin + EPL_FONTS[1].1).max(20);
+            let (kind, data, modules) = match ean13_data(&label.barcode).filter(|_| config.barcode_type == "ean13") {
+                Some(digits) => ("E30", digits, 95),
+                None => ("1", label.barcode.clone(), 11 * (label.barcode.len() as u32 + 3) + 2),
+            };
+            let x = width.saturating_sub(modules * module) / 2;
+            out.push_str(&format!(
+                "B{},{},0,{},{},{},{},B,\"{}\"\n",
+                x, y, kind, module, module * 2, bar_h, epl_text(&data)
+            ));
+        }
+
+        out.push_str(&format!("P{}\n", copies));
+    }
+    out.into_bytes()
+}
+
+// --- ESC/POS ---
+
+fn render_escpos(config: &LabelConfig, labels: &[LabelData], symbol: &str, copies: u32) -> Vec<u8> {
+    let paper_width = if config.width_mm <= 58 { 58 } else { 80 };
+    let mut builder = ReceiptBuilder::new(paper_width);
+    builder.init();
+
+    for label in labels {
+        for _ in 0..copies {
+            builder.align_center();
+            if config.show_name {
+                builder.set_bold(true);
+                for line in wrap_name(&remove_accents(&label.name), builder.max_chars, 2) {
+                    builder.add_text_ln(&line);
+                }
+                builder.set_bold(false);
+            }
+            if config.show_price {
+                builder.set_size_double_hw();
+                builder.add_text_ln(&remove_accents(&format_price(symbol, label.price)));
+                builder.set_size_normal();
+            }
+            if config.show_wholesale_price {
+                builder.add_text_ln(&format!(
+                    "Mayoreo: {}",
+                    remove_accents(&format_price(symbol, label.wholesale_price))
+                ));
+            }
+            if config.show_barcode && !label.barcode.is_empty() {
+                let code = label.barcode.trim();
+                let symbology = match detect_symbology(code) {
+                    Symbology::Ean13 | Symbology::UpcA if config.barcode_type == "ean13" => Symbology::Ean13,
+                    Symbology::Code128C => Symbology::Code128C,
+                    _ => Symbology::Code128B,
+                };
+                let data = match symbology {
+                    Symbology::Ean13 => ean13_data(code).unwrap_or_default(),
+                    _ => code.to_string(),
+                };
+                if let Ok(cmds) = generate_barcode_escpos_as(&data, symbology, builder.max_width) {
+                    builder.add_image(&cmds);
+                }
+                builder.add_text_ln(&remove_accents(code));
+            }
+            builder.cut();
+        }
+    }
+    builder.build()
+}
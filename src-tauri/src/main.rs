@@ -1,9 +1,16 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod barcode_utils;
 mod commands;
+mod customer_display;
 mod database;
+mod label_utils;
+mod payment_terminal;
+mod printer_transport;
 mod printer_utils;
+mod receipt_document;
+mod receipt_template;
 use tauri::Emitter;
 use std::sync::Mutex;
 use tauri::Manager;
@@ -21,6 +28,18 @@ fn main() {
             // Iniciar scheduler de respaldos en background
             commands::backup::start_backup_scheduler(app.handle().clone());
 
+            // Iniciar scheduler de envío de reportes
+            commands::report_delivery::start_report_scheduler(app.handle().clone());
+
+            // Iniciar cola de impresión (reintentos en background)
+            commands::print_queue::start_print_worker(app.handle().clone());
+
+            // Monitorear estado de la impresora
+            commands::printer_status::start_printer_monitor(app.handle().clone());
+
+            // Pantalla del cliente (visor serie y/o segunda ventana)
+            commands::customer_display::start_customer_display(app.handle().clone());
+
             Ok(())
         })
         .plugin(tauri_plugin_updater::Builder::new().build())
@@ -55,11 +74,28 @@ fn main() {
             commands::settings::hardware::test_printer_connection,
             commands::settings::hardware::test_cash_drawer,
             commands::settings::hardware::open_cash_drawer,
+            commands::settings::receipt_templates::get_receipt_templates,
+            commands::settings::receipt_templates::save_receipt_template,
+            commands::settings::receipt_templates::reset_receipt_template,
+            commands::settings::receipt_templates::preview_receipt_template,
             commands::printer::test_print_ticket,
             commands::printer::print_sale_ticket,
+            commands::printer::render_sale_receipt,
             commands::printer::print_return_voucher,
             commands::printer::print_shift_ticket,
             commands::printer::print_payment_receipt,
+            commands::printer::print_x_report,
+            commands::printer::print_z_report,
+            // Print queue
+            commands::print_queue::get_print_jobs,
+            commands::print_queue::retry_print_job,
+            commands::print_queue::retry_failed_print_jobs,
+            commands::print_queue::discard_print_job,
+            commands::printer_status::get_printer_status,
+            commands::customer_display::update_customer_display,
+            commands::customer_display::open_customer_display,
+            commands::customer_display::get_customer_display_state,
+            commands::customer_display::test_customer_display,
             // Inventory - Products
             commands::inventory::products::get_products,
             commands::inventory::products::get_all_filtered_products,
@@ -70,6 +106,8 @@ fn main() {
             commands::inventory::products::bulk_update_products,
             commands::inventory::products::delete_products,
             commands::inventory::products::check_product_dependencies,
+            commands::inventory::barcodes::generate_internal_barcode,
+            commands::inventory::barcodes::backfill_internal_barcodes,
             // Inventory - Categories
             commands::inventory::categories::get_categories,
             commands::inventory::categories::get_all_categories,
@@ -98,6 +136,12 @@ fn main() {
             commands::inventory::movements::get_inventory_movements,
             commands::inventory::movements::create_inventory_movement,
             commands::inventory::movements::process_bulk_reception,
+            // Inventory - Labels
+            commands::inventory::labels::get_label_config,
+            commands::inventory::labels::save_label_config,
+            commands::inventory::labels::print_product_labels,
+            commands::inventory::labels::get_price_changed_products,
+            commands::inventory::labels::print_price_change_labels,
             // Cash Register
             commands::cash_register::shifts::get_active_shift,
             commands::cash_register::shifts::open_shift,
@@ -106,12 +150,22 @@ fn main() {
             commands::cash_register::details::get_shift_details,
             commands::cash_register::details::get_closed_shifts,
             commands::cash_register::details::get_shifts_history,
+            commands::cash_register::daily_report::get_x_report,
+            commands::cash_register::daily_report::create_z_report,
+            commands::cash_register::daily_report::get_z_reports,
+            commands::cash_register::daily_report::get_z_report,
             commands::cash_register::sales::process_sale,
             commands::cash_register::sales::validate_voucher,
+            commands::cash_register::terminal::charge_card_terminal,
+            commands::cash_register::terminal::void_terminal_charge,
             // Settings - Business
             commands::settings::business::get_business_settings,
             commands::settings::business::update_business_settings,
             commands::settings::business::save_logo_image,
+            // Settings - Currencies
+            commands::settings::currencies::get_currencies,
+            commands::settings::currencies::save_currency,
+            commands::settings::currencies::set_exchange_rate,
             // Customers
             commands::customers::customers::get_customers,
             commands::customers::customers::upsert_customer,
@@ -125,6 +179,12 @@ fn main() {
             commands::sales::history::get_sale_details,
             // Sales - Returns
             commands::sales::returns::process_return,
+            commands::sales::no_receipt::find_return_candidates,
+            commands::sales::no_receipt::get_no_receipt_return_price,
+            commands::sales::no_receipt::get_no_receipt_limits,
+            commands::sales::no_receipt::process_no_receipt_return,
+            // Sales - Exchanges
+            commands::sales::exchange::process_exchange,
             // Sales - Cancel
             commands::sales::cancel::cancel_sale,
             // Reports
@@ -133,11 +193,31 @@ fn main() {
             commands::reports::get_dead_stock_report,
             commands::reports::get_inventory_valuation,
             commands::reports::get_low_stock_products,
+            commands::reports::get_sales_heatmap,
+            commands::reports::get_cashier_performance,
+            commands::reports::get_discount_analysis,
+            commands::reports::get_reorder_suggestions,
+            // Exports
+            commands::exports::export_sales_report,
+            commands::exports::export_top_selling_products,
+            commands::exports::export_dead_stock_report,
+            commands::exports::export_inventory_valuation,
+            commands::exports::export_low_stock_products,
+            commands::exports::export_reorder_list,
+            commands::exports::export_sales_history,
+            commands::exports::export_kardex,
             // Backup
             commands::backup::backup_database,
             commands::backup::restore_latest_backup,
             commands::backup::sync_pending_backups,
-            commands::backup::get_pending_backups_count
+            commands::backup::get_pending_backups_count,
+            // Report delivery
+            commands::report_delivery::get_report_delivery_config,
+            commands::report_delivery::save_report_delivery_config,
+            commands::report_delivery::test_report_delivery,
+            commands::report_delivery::send_daily_report_now,
+            commands::report_delivery::get_report_deliveries,
+            commands::report_delivery::retry_report_delivery
         ])
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
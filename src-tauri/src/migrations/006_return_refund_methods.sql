@@ -0,0 +1,19 @@
+-- 1. Track the shift a refund was paid from (cash refunds leave the drawer of the open shift)
+ALTER TABLE "returns" ADD COLUMN "cash_register_shift_id" INTEGER;
+
+CREATE INDEX IF NOT EXISTS "idx_returns_shift" ON "returns" ("cash_register_shift_id");
+
+-- 2. Permissions for each refund method (store voucher stays under history:devolution)
+INSERT OR IGNORE INTO "permissions" VALUES
+('650e8400-e29b-41d4-a716-446655440134','history:refund_cash','Reembolsar en efectivo','Permite devolver efectivo del turno actual','dashboard',1,'2026-10-18 09:00:00',4),
+('650e8400-e29b-41d4-a716-446655440135','history:refund_card','Reembolsar a tarjeta','Permite reversar pagos con tarjeta/transferencia','dashboard',1,'2026-10-18 09:00:00',5),
+('650e8400-e29b-41d4-a716-446655440136','history:refund_credit','Reembolsar a crédito','Permite abonar devoluciones al saldo de crédito del cliente','dashboard',1,'2026-10-18 09:00:00',6);
+
+-- 3. Grant them to admin and manager
+INSERT OR IGNORE INTO "role_permissions" VALUES
+('750e8400-e29b-41d4-a716-446655440134','550e8400-e29b-41d4-a716-446655440001','650e8400-e29b-41d4-a716-446655440134','2026-10-18 09:00:00'),
+('750e8400-e29b-41d4-a716-446655440135','550e8400-e29b-41d4-a716-446655440001','650e8400-e29b-41d4-a716-446655440135','2026-10-18 09:00:00'),
+('750e8400-e29b-41d4-a716-446655440136','550e8400-e29b-41d4-a716-446655440001','650e8400-e29b-41d4-a716-446655440136','2026-10-18 09:00:00'),
+('750e8400-e29b-41d5-a716-446655440134','550e8400-e29b-41d4-a716-446655440002','650e8400-e29b-41d4-a716-446655440134','2026-10-18 09:00:00'),
+('750e8400-e29b-41d5-a716-446655440135','550e8400-e29b-41d4-a716-446655440002','650e8400-e29b-41d4-a716-446655440135','2026-10-18 09:00:00'),
+('750e8400-e29b-41d5-a716-446655440136','550e8400-e29b-41d4-a716-446655440002','650e8400-e29b-41d4-a716-446655440136','2026-10-18 09:00:00');
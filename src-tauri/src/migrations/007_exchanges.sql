@@ -0,0 +1,8 @@
+-- 1. Part of a sale's total covered by goods returned in the same exchange
+ALTER TABLE "sales" ADD COLUMN "exchange_amount" DECIMAL(10, 2) NOT NULL DEFAULT 0;
+
+-- 2. Part of a return applied to the exchange sale (the rest is refunded via refund_method)
+ALTER TABLE "returns" ADD COLUMN "exchange_amount" DECIMAL(10, 2) NOT NULL DEFAULT 0;
+ALTER TABLE "returns" ADD COLUMN "exchange_sale_id" TEXT REFERENCES "sales"("id");
+
+CREATE INDEX IF NOT EXISTS "idx_returns_exchange_sale" ON "returns" ("exchange_sale_id");
@@ -0,0 +1,107 @@
+-- 1. Rebuild returns so sale_id can be NULL (returns without the original ticket)
+CREATE TABLE "returns_dg_tmp" (
+	"id"	TEXT NOT NULL,
+	"folio"	INTEGER NOT NULL UNIQUE,
+	"sale_id"	TEXT,
+	"return_date"	DATETIME DEFAULT CURRENT_TIMESTAMP,
+	"total"	DECIMAL(10, 2) NOT NULL,
+	"reason"	TEXT NOT NULL,
+	"refund_method"	TEXT NOT NULL DEFAULT 'cash',
+	"user_id"	TEXT NOT NULL,
+	"created_at"	DATETIME DEFAULT CURRENT_TIMESTAMP,
+	"is_synced"	BOOLEAN DEFAULT 0,
+	"synced_at"	DATETIME,
+	"notes"	TEXT,
+	"cash_register_shift_id"	INTEGER,
+	"exchange_amount"	DECIMAL(10, 2) NOT NULL DEFAULT 0,
+	"exchange_sale_id"	TEXT,
+	"customer_id"	TEXT,
+	PRIMARY KEY("id"),
+	FOREIGN KEY("sale_id") REFERENCES "sales"("id") ON DELETE CASCADE,
+	FOREIGN KEY("user_id") REFERENCES "users"("id"),
+	FOREIGN KEY("exchange_sale_id") REFERENCES "sales"("id"),
+	FOREIGN KEY("customer_id") REFERENCES "customers"("id")
+);
+
+INSERT INTO "returns_dg_tmp" ("id", "folio", "sale_id", "return_date", "total", "reason", "refund_method", "user_id", "created_at", "is_synced", "synced_at", "notes", "cash_register_shift_id", "exchange_amount", "exchange_sale_id", "customer_id")
+SELECT r."id", r."folio", r."sale_id", r."return_date", r."total", r."reason", r."refund_method", r."user_id", r."created_at", r."is_synced", r."synced_at", r."notes", r."cash_register_shift_id", r."exchange_amount", r."exchange_sale_id", s."customer_id"
+FROM "returns" r
+LEFT JOIN "sales" s ON s."id" = r."sale_id";
+
+DROP TABLE "returns";
+ALTER TABLE "returns_dg_tmp" RENAME TO "returns";
+
+CREATE INDEX IF NOT EXISTS "idx_returns_date" ON "returns" ("return_date");
+CREATE INDEX IF NOT EXISTS "idx_returns_folio" ON "returns" ("folio");
+CREATE INDEX IF NOT EXISTS "idx_returns_sale_id" ON "returns" ("sale_id");
+CREATE INDEX IF NOT EXISTS "idx_returns_shift" ON "returns" ("cash_register_shift_id");
+CREATE INDEX IF NOT EXISTS "idx_returns_exchange_sale" ON "returns" ("exchange_sale_id");
+CREATE INDEX IF NOT EXISTS "idx_returns_customer_date" ON "returns" ("customer_id", "return_date");
+
+-- 2. Rebuild return_items so sale_item_id can be NULL
+CREATE TABLE "return_items_dg_tmp" (
+	"id"	TEXT NOT NULL,
+	"return_id"	TEXT NOT NULL,
+	"sale_item_id"	TEXT,
+	"product_id"	TEXT NOT NULL,
+	"quantity"	DECIMAL(10, 3) NOT NULL,
+	"unit_price"	DECIMAL(10, 2) NOT NULL,
+	"subtotal"	DECIMAL(10, 2) NOT NULL,
+	PRIMARY KEY("id"),
+	FOREIGN KEY("product_id") REFERENCES "products"("id"),
+	FOREIGN KEY("return_id") REFERENCES "returns"("id") ON DELETE CASCADE,
+	FOREIGN KEY("sale_item_id") REFERENCES "sale_items"("id")
+);
+
+INSERT INTO "return_items_dg_tmp" ("id", "return_id", "sale_item_id", "product_id", "quantity", "unit_price", "subtotal")
+SELECT "id", "return_id", "sale_item_id", "product_id", "quantity", "unit_price", "subtotal"
+FROM "return_items";
+
+DROP TABLE "return_items";
+ALTER TABLE "return_items_dg_tmp" RENAME TO "return_items";
+
+CREATE INDEX IF NOT EXISTS "idx_return_items_return_id" ON "return_items" ("return_id");
+CREATE INDEX IF NOT EXISTS "idx_return_items_sale_item_id" ON "return_items" ("sale_item_id");
+
+-- 3. Rebuild store_vouchers so vouchers can come from a return without a sale
+CREATE TABLE "store_vouchers_dg_tmp" (
+	"id"	TEXT NOT NULL,
+	"sale_id"	TEXT,
+	"code"	TEXT NOT NULL UNIQUE,
+	"initial_balance"	DECIMAL(10, 2) NOT NULL,
+	"current_balance"	DECIMAL(10, 2) NOT NULL,
+	"is_active"	BOOLEAN DEFAULT 1,
+	"created_at"	DATETIME DEFAULT CURRENT_TIMESTAMP,
+	"updated_at"	DATETIME DEFAULT CURRENT_TIMESTAMP,
+	"used_at"	DATETIME,
+	"expires_at"	DATETIME,
+	"return_id"	TEXT,
+	PRIMARY KEY("id"),
+	FOREIGN KEY("sale_id") REFERENCES "sales"("id") ON DELETE CASCADE,
+	FOREIGN KEY("return_id") REFERENCES "returns"("id")
+);
+
+INSERT INTO "store_vouchers_dg_tmp" ("id", "sale_id", "code", "initial_balance", "current_balance", "is_active", "created_at", "updated_at", "used_at", "expires_at")
+SELECT "id", "sale_id", "code", "initial_balance", "current_balance", "is_active", "created_at", "updated_at", "used_at", "expires_at"
+FROM "store_vouchers";
+
+DROP TABLE "store_vouchers";
+ALTER TABLE "store_vouchers_dg_tmp" RENAME TO "store_vouchers";
+
+CREATE INDEX IF NOT EXISTS "idx_store_vouchers_active" ON "store_vouchers" ("is_active");
+CREATE INDEX IF NOT EXISTS "idx_store_vouchers_code" ON "store_vouchers" ("code");
+CREATE INDEX IF NOT EXISTS "idx_store_vouchers_sale_id" ON "store_vouchers" ("sale_id");
+
+-- 4. Settings: price lookback window and daily limits per customer
+INSERT OR IGNORE INTO "system_settings" ("key", "value", "updated_at") VALUES
+('no_receipt_price_days', '30', '2026-10-18 09:00:00'),
+('no_receipt_daily_max_returns', '1', '2026-10-18 09:00:00'),
+('no_receipt_daily_max_amount', '500', '2026-10-18 09:00:00');
+
+-- 5. Permission for the no-receipt flow
+INSERT OR IGNORE INTO "permissions" VALUES
+('650e8400-e29b-41d4-a716-446655440137','history:return_no_receipt','Devolver sin ticket','Permite devoluciones sin el ticket original','dashboard',1,'2026-10-18 09:00:00',7);
+
+INSERT OR IGNORE INTO "role_permissions" VALUES
+('750e8400-e29b-41d4-a716-446655440137','550e8400-e29b-41d4-a716-446655440001','650e8400-e29b-41d4-a716-446655440137','2026-10-18 09:00:00'),
+('750e8400-e29b-41d5-a716-446655440137','550e8400-e29b-41d4-a716-446655440002','650e8400-e29b-41d4-a716-446655440137','2026-10-18 09:00:00');
@@ -0,0 +1,36 @@
+-- 1. Shift in which a cancellation was booked (same shift, or the open shift for a reversal)
+ALTER TABLE "sales" ADD COLUMN "cancelled_shift_id" INTEGER;
+
+UPDATE "sales"
+SET "cancelled_shift_id" = CAST("cash_register_shift_id" AS INTEGER)
+WHERE "status" = 'cancelled' AND "cash_register_shift_id" IS NOT NULL;
+
+-- 2. Reversing entries for sales cancelled after their shift was closed
+CREATE TABLE IF NOT EXISTS "sale_reversals" (
+	"id"	TEXT NOT NULL,
+	"sale_id"	TEXT NOT NULL UNIQUE,
+	"cash_register_shift_id"	INTEGER NOT NULL,
+	"original_shift_id"	INTEGER,
+	"total"	DECIMAL(10, 2) NOT NULL,
+	"cash_amount"	DECIMAL(10, 2) NOT NULL DEFAULT 0,
+	"card_amount"	DECIMAL(10, 2) NOT NULL DEFAULT 0,
+	"credit_amount"	DECIMAL(10, 2) NOT NULL DEFAULT 0,
+	"voucher_amount"	DECIMAL(10, 2) NOT NULL DEFAULT 0,
+	"reason"	TEXT NOT NULL,
+	"user_id"	TEXT NOT NULL,
+	"created_at"	TEXT NOT NULL,
+	PRIMARY KEY("id"),
+	FOREIGN KEY("sale_id") REFERENCES "sales"("id"),
+	FOREIGN KEY("cash_register_shift_id") REFERENCES "cash_register_shifts"("id"),
+	FOREIGN KEY("user_id") REFERENCES "users"("id")
+);
+
+CREATE INDEX IF NOT EXISTS "idx_sale_reversals_shift" ON "sale_reversals" ("cash_register_shift_id");
+
+-- 3. Permission to cancel sales from closed shifts
+INSERT OR IGNORE INTO "permissions" VALUES
+('650e8400-e29b-41d4-a716-446655440138','history:cancel_closed_shift','Cancelar ventas de turnos cerrados','Permite cancelar ventas de turnos anteriores con reverso en el turno actual','dashboard',1,'2026-10-18 09:00:00',8);
+
+INSERT OR IGNORE INTO "role_permissions" VALUES
+('750e8400-e29b-41d4-a716-446655440138','550e8400-e29b-41d4-a716-446655440001','650e8400-e29b-41d4-a716-446655440138','2026-10-18 09:00:00'),
+('750e8400-e29b-41d5-a716-446655440138','550e8400-e29b-41d4-a716-446655440002','650e8400-e29b-41d4-a716-446655440138','2026-10-18 09:00:00');
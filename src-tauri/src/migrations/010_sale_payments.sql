@@ -0,0 +1,50 @@
+-- 1. One row per tender applied to a sale
+CREATE TABLE IF NOT EXISTS "sale_payments" (
+	"id"	TEXT NOT NULL,
+	"sale_id"	TEXT NOT NULL,
+	"method"	TEXT NOT NULL CHECK("method" IN ('cash','debit_card','credit_card','transfer','voucher','gift_card','credit','points')),
+	"amount"	DECIMAL(10, 2) NOT NULL,
+	"tendered"	DECIMAL(10, 2) NOT NULL DEFAULT 0,
+	"reference"	TEXT,
+	"voucher_id"	TEXT,
+	"created_at"	DATETIME DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY("id"),
+	FOREIGN KEY("sale_id") REFERENCES "sales"("id") ON DELETE CASCADE,
+	FOREIGN KEY("voucher_id") REFERENCES "store_vouchers"("id")
+);
+
+CREATE INDEX IF NOT EXISTS "idx_sale_payments_sale_id" ON "sale_payments" ("sale_id");
+CREATE INDEX IF NOT EXISTS "idx_sale_payments_method" ON "sale_payments" ("method");
+
+-- 2. Backfill from the legacy columns
+-- Vouchers
+INSERT INTO "sale_payments" ("id", "sale_id", "method", "amount", "tendered", "reference", "voucher_id", "created_at")
+SELECT sv."id", sv."sale_id", 'voucher', sv."amount", sv."amount", v."code", sv."voucher_id", sv."created_at"
+FROM "sale_vouchers" sv
+LEFT JOIN "store_vouchers" v ON v."id" = sv."voucher_id";
+
+-- Credit account (the whole sale total was charged to the customer balance)
+INSERT INTO "sale_payments" ("id", "sale_id", "method", "amount", "tendered", "created_at")
+SELECT lower(hex(randomblob(16))), "id", 'credit', "total", "total", "created_at"
+FROM "sales"
+WHERE "payment_method" = 'credit';
+
+-- Card/transfer: the legacy column did not distinguish them, booked as debit card
+INSERT INTO "sale_payments" ("id", "sale_id", "method", "amount", "tendered", "created_at")
+SELECT lower(hex(randomblob(16))), "id", 'debit_card', "card_transfer_amount", "card_transfer_amount", "created_at"
+FROM "sales"
+WHERE "payment_method" != 'credit' AND COALESCE("card_transfer_amount", 0) > 0;
+
+-- Cash: whatever the other tenders and the exchange credit did not cover
+INSERT INTO "sale_payments" ("id", "sale_id", "method", "amount", "tendered", "created_at")
+SELECT lower(hex(randomblob(16))), s."id", 'cash', s."cash_due", MAX(COALESCE(s."cash_amount", 0), s."cash_due"), s."created_at"
+FROM (
+	SELECT sa."id", sa."cash_amount", sa."created_at",
+		sa."total"
+		- COALESCE(sa."card_transfer_amount", 0)
+		- COALESCE(sa."exchange_amount", 0)
+		- COALESCE((SELECT SUM(sv."amount") FROM "sale_vouchers" sv WHERE sv."sale_id" = sa."id"), 0) AS "cash_due"
+	FROM "sales" sa
+	WHERE sa."payment_method" != 'credit'
+) s
+WHERE s."cash_due" > 0.005;
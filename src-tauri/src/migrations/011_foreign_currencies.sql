@@ -0,0 +1,56 @@
+-- 1. Secondary currencies accepted as cash (amounts are converted to the base currency)
+CREATE TABLE IF NOT EXISTS "currencies" (
+	"code"	TEXT NOT NULL,
+	"name"	TEXT NOT NULL,
+	"symbol"	TEXT NOT NULL,
+	"is_active"	BOOLEAN NOT NULL DEFAULT 1,
+	"created_at"	DATETIME DEFAULT CURRENT_TIMESTAMP,
+	"updated_at"	DATETIME DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY("code")
+);
+
+INSERT OR IGNORE INTO "currencies" ("code", "name", "symbol", "is_active", "created_at", "updated_at") VALUES
+('USD', 'Dólar estadounidense', 'US$', 1, '2026-10-18 09:00:00', '2026-10-18 09:00:00');
+
+-- 2. Daily exchange rate (base currency units per foreign unit)
+CREATE TABLE IF NOT EXISTS "exchange_rates" (
+	"id"	TEXT NOT NULL,
+	"currency_code"	TEXT NOT NULL,
+	"rate_date"	TEXT NOT NULL,
+	"rate"	DECIMAL(10, 4) NOT NULL,
+	"user_id"	TEXT,
+	"created_at"	DATETIME DEFAULT CURRENT_TIMESTAMP,
+	"updated_at"	DATETIME DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY("id"),
+	UNIQUE("currency_code", "rate_date"),
+	FOREIGN KEY("currency_code") REFERENCES "currencies"("code"),
+	FOREIGN KEY("user_id") REFERENCES "users"("id")
+);
+
+-- 3. Foreign tender details (amount/tendered stay in base currency)
+ALTER TABLE "sale_payments" ADD COLUMN "currency_code" TEXT;
+ALTER TABLE "sale_payments" ADD COLUMN "foreign_amount" DECIMAL(10, 2);
+ALTER TABLE "sale_payments" ADD COLUMN "exchange_rate" DECIMAL(10, 4);
+
+-- 4. Foreign cash counted when closing a shift
+CREATE TABLE IF NOT EXISTS "shift_currency_counts" (
+	"id"	TEXT NOT NULL,
+	"cash_register_shift_id"	INTEGER NOT NULL,
+	"currency_code"	TEXT NOT NULL,
+	"expected_amount"	DECIMAL(10, 2) NOT NULL DEFAULT 0,
+	"counted_amount"	DECIMAL(10, 2),
+	"difference"	DECIMAL(10, 2),
+	"created_at"	DATETIME DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY("id"),
+	UNIQUE("cash_register_shift_id", "currency_code"),
+	FOREIGN KEY("cash_register_shift_id") REFERENCES "cash_register_shifts"("id"),
+	FOREIGN KEY("currency_code") REFERENCES "currencies"("code")
+);
+
+-- 5. Permission to capture the daily exchange rate
+INSERT OR IGNORE INTO "permissions" VALUES
+('650e8400-e29b-41d4-a716-446655440139','cash_register:exchange_rate','Capturar tipo de cambio','Permite registrar el tipo de cambio diario de monedas extranjeras','dashboard',1,'2026-10-18 09:00:00',9);
+
+INSERT OR IGNORE INTO "role_permissions" VALUES
+('750e8400-e29b-41d4-a716-446655440139','550e8400-e29b-41d4-a716-446655440001','650e8400-e29b-41d4-a716-446655440139','2026-10-18 09:00:00'),
+('750e8400-e29b-41d5-a716-446655440139','550e8400-e29b-41d4-a716-446655440002','650e8400-e29b-41d4-a716-446655440139','2026-10-18 09:00:00');
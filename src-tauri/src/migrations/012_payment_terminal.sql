@@ -0,0 +1,26 @@
+-- 1. Every charge sent to the card terminal, whatever the result
+CREATE TABLE IF NOT EXISTS "terminal_transactions" (
+	"id"	TEXT NOT NULL,
+	"method"	TEXT NOT NULL,
+	"amount"	DECIMAL(10, 2) NOT NULL,
+	"status"	TEXT NOT NULL CHECK("status" IN ('approved','declined','timeout')),
+	"auth_code"	TEXT,
+	"card_last4"	TEXT,
+	"card_brand"	TEXT,
+	"message"	TEXT,
+	"terminal_id"	TEXT,
+	"user_id"	TEXT NOT NULL,
+	"sale_id"	TEXT,
+	"created_at"	DATETIME DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY("id"),
+	FOREIGN KEY("user_id") REFERENCES "users"("id"),
+	FOREIGN KEY("sale_id") REFERENCES "sales"("id")
+);
+
+CREATE INDEX IF NOT EXISTS "idx_terminal_transactions_sale_id" ON "terminal_transactions" ("sale_id");
+
+-- 2. Approval data on the card tender
+ALTER TABLE "sale_payments" ADD COLUMN "auth_code" TEXT;
+ALTER TABLE "sale_payments" ADD COLUMN "card_last4" TEXT;
+ALTER TABLE "sale_payments" ADD COLUMN "card_brand" TEXT;
+ALTER TABLE "sale_payments" ADD COLUMN "terminal_transaction_id" TEXT;
@@ -0,0 +1,36 @@
+-- 1. End-of-day Z reports (consecutive, one per business date, never modified)
+CREATE TABLE IF NOT EXISTS "z_reports" (
+	"id"	TEXT NOT NULL,
+	"report_number"	INTEGER NOT NULL UNIQUE,
+	"business_date"	TEXT NOT NULL UNIQUE,
+	"first_folio"	TEXT,
+	"last_folio"	TEXT,
+	"total_sales"	DECIMAL(10, 2) NOT NULL DEFAULT 0,
+	"data"	TEXT NOT NULL,
+	"user_id"	TEXT NOT NULL,
+	"print_count"	INTEGER NOT NULL DEFAULT 0,
+	"created_at"	DATETIME DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY("id"),
+	FOREIGN KEY("user_id") REFERENCES "users"("id")
+);
+
+-- Only the reprint counter may change
+CREATE TRIGGER IF NOT EXISTS "trg_z_reports_no_update"
+BEFORE UPDATE OF "id", "report_number", "business_date", "first_folio", "last_folio", "total_sales", "data", "user_id", "created_at" ON "z_reports"
+BEGIN
+	SELECT RAISE(ABORT, 'Los reportes Z no se pueden modificar');
+END;
+
+CREATE TRIGGER IF NOT EXISTS "trg_z_reports_no_delete"
+BEFORE DELETE ON "z_reports"
+BEGIN
+	SELECT RAISE(ABORT, 'Los reportes Z no se pueden eliminar');
+END;
+
+-- 2. Permission to issue the Z report
+INSERT OR IGNORE INTO "permissions" VALUES
+('650e8400-e29b-41d4-a716-446655440140','cash_register:z_report','Emitir Reporte Z','Permite emitir el cierre diario (reporte Z)','cash_register',1,'2026-10-18 09:00:00',5);
+
+INSERT OR IGNORE INTO "role_permissions" VALUES
+('750e8400-e29b-41d4-a716-446655440140','550e8400-e29b-41d4-a716-446655440001','650e8400-e29b-41d4-a716-446655440140','2026-10-18 09:00:00'),
+('750e8400-e29b-41d5-a716-446655440140','550e8400-e29b-41d4-a716-446655440002','650e8400-e29b-41d4-a716-446655440140','2026-10-18 09:00:00');
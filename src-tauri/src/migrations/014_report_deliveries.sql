@@ -0,0 +1,21 @@
+-- 1. Scheduled report deliveries (rendered content is kept so retries resend the same snapshot)
+CREATE TABLE IF NOT EXISTS "report_deliveries" (
+	"id"	INTEGER,
+	"report_date"	TEXT NOT NULL,
+	"source"	TEXT NOT NULL DEFAULT 'scheduled' CHECK("source" IN ('scheduled','manual')),
+	"channel"	TEXT NOT NULL CHECK("channel" IN ('smtp','webhook')),
+	"destination"	TEXT NOT NULL,
+	"subject"	TEXT NOT NULL,
+	"html"	TEXT NOT NULL,
+	"csv"	TEXT NOT NULL,
+	"status"	TEXT NOT NULL DEFAULT 'pending' CHECK("status" IN ('pending','sent','failed')),
+	"attempts"	INTEGER NOT NULL DEFAULT 0,
+	"last_error"	TEXT,
+	"next_attempt_at"	TEXT,
+	"sent_at"	TEXT,
+	"created_at"	TEXT NOT NULL DEFAULT (datetime('now', 'localtime')),
+	PRIMARY KEY("id" AUTOINCREMENT)
+);
+
+CREATE INDEX IF NOT EXISTS "idx_report_deliveries_status" ON "report_deliveries" ("status", "next_attempt_at");
+CREATE INDEX IF NOT EXISTS "idx_report_deliveries_date" ON "report_deliveries" ("report_date");
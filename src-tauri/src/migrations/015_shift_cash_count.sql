@@ -0,0 +1,5 @@
+-- 1. Base-currency cash counted when closing a shift (difference = counted - expected)
+ALTER TABLE "cash_register_shifts" ADD COLUMN "counted_cash" DECIMAL(10, 2);
+ALTER TABLE "cash_register_shifts" ADD COLUMN "cash_difference" DECIMAL(10, 2);
+
+CREATE INDEX IF NOT EXISTS "idx_shifts_closing_user" ON "cash_register_shifts" ("closing_user_id", "closing_date");
@@ -0,0 +1,16 @@
+-- 1. Print queue: rendered ESC/POS bytes are kept so a failed ticket can be retried as-is
+CREATE TABLE IF NOT EXISTS "print_jobs" (
+	"id"	INTEGER,
+	"document_type"	TEXT NOT NULL,
+	"reference"	TEXT,
+	"payload"	BLOB NOT NULL,
+	"status"	TEXT NOT NULL DEFAULT 'pending' CHECK("status" IN ('pending','printing','printed','failed','discarded')),
+	"attempts"	INTEGER NOT NULL DEFAULT 0,
+	"last_error"	TEXT,
+	"next_attempt_at"	TEXT,
+	"created_at"	TEXT NOT NULL DEFAULT (datetime('now', 'localtime')),
+	"printed_at"	TEXT,
+	PRIMARY KEY("id" AUTOINCREMENT)
+);
+
+CREATE INDEX IF NOT EXISTS "idx_print_jobs_status" ON "print_jobs" ("status", "next_attempt_at");
@@ -0,0 +1,10 @@
+-- 1. Custom receipt layouts per document type; the built-in layout is used when there is no active row
+CREATE TABLE IF NOT EXISTS "receipt_templates" (
+	"document_type"	TEXT NOT NULL CHECK("document_type" IN ('sale','voucher','payment','shift')),
+	"content"	TEXT NOT NULL,
+	"is_active"	BOOLEAN NOT NULL DEFAULT 1,
+	"updated_by"	TEXT,
+	"updated_at"	TEXT NOT NULL DEFAULT (datetime('now', 'localtime')),
+	PRIMARY KEY("document_type"),
+	FOREIGN KEY("updated_by") REFERENCES "users"("id")
+);
@@ -0,0 +1,10 @@
+-- 1. Shelf labels: prices printed on the last label of each product, to find labels that need a reprint
+CREATE TABLE IF NOT EXISTS "label_prints" (
+	"product_id"	TEXT NOT NULL,
+	"retail_price"	REAL NOT NULL,
+	"wholesale_price"	REAL NOT NULL,
+	"printed_at"	TEXT NOT NULL DEFAULT (datetime('now', 'localtime')),
+	"printed_by"	TEXT,
+	PRIMARY KEY("product_id"),
+	FOREIGN KEY("product_id") REFERENCES "products"("id") ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+-- 1. Approved terminal charges cancelled because no sale claimed them
+ALTER TABLE "terminal_transactions" ADD COLUMN "voided_at" DATETIME;
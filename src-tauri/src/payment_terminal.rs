@@ -0,0 +1,400 @@
+// Card terminal integration.
+//
+// Drivers exchange one JSON object per line with the terminal:
+//   -> {"command":"sale","amount_cents":12345,"reference":"...","terminal_id":"CAJA-01"}
+//   <- {"status":"approved","auth_code":"123456","last4":"4242","brand":"VISA"}
+//   <- {"status":"declined","message":"Fondos insuficientes"}
+// No answer within the configured timeout is reported as `TimedOut`, and the
+// charge is then cancelled with the same reference:
+//   -> {"command":"void","amount_cents":12345,"reference":"...","terminal_id":"CAJA-01"}
+//   <- {"status":"voided"}
+
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Longest wait for the answer to a void.
+const VOID_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PaymentTerminalConfig {
+    /// 'none', 'tcp' or 'serial'; development builds also accept 'simulator'
+    pub driver: String,
+    /// host:port for the TCP driver
+    pub address: Option<String>,
+    /// COM3, /dev/ttyUSB0... for the serial driver
+    pub serial_port: Option<String>,
+    /// Serial line speed (8N1)
+    pub baud_rate: u32,
+    pub timeout_secs: u64,
+}
+
+impl Default for PaymentTerminalConfig {
+    fn default() -> Self {
+        Self {
+            driver: "none".to_string(),
+            address: None,
+            serial_port: None,
+            baud_rate: 9600,
+            timeout_secs: 60,
+        }
+    }
+}
+
+pub struct ChargeRequest {
+    pub amount: f64,
+    pub reference: String,
+    pub terminal_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ChargeOutcome {
+    Approved {
+        auth_code: String,
+        last4: String,
+        brand: String,
+    },
+    Declined {
+        message: String,
+    },
+    /// The terminal did not answer in time; the card may or may not have been charged.
+    TimedOut,
+}
+
+pub trait PaymentTerminal: Send {
+    /// Sends the amount to the terminal and waits for the cardholder.
+    /// `Err` means the request never reached the terminal.
+    fn charge(&self, request: &ChargeRequest) -> Result<ChargeOutcome, String>;
+    /// Cancels the charge with the same reference, so a timed-out charge
+    /// that the device approves late is not left unrecorded.
+    fn void(&self, request: &ChargeRequest) -> Result<(), String>;
+}
+
+#[derive(Serialize)]
+struct WireRequest<'a> {
+    command: &'a str,
+    amount_cents: i64,
+    reference: &'a str,
+    terminal_id: &'a str,
+}
+
+#[derive(Deserialize)]
+struct WireResponse {
+    status: String,
+    auth_code: Option<String>,
+    last4: Option<String>,
+    brand: Option<String>,
+    message: Option<String>,
+}
+
+fn to_cents(amount: f64) -> i64 {
+    (amount * 100.0).round() as i64
+}
+
+/// Writes one request line and reads the answer line. The stream must have
+/// a read timeout; an expired one surfaces as `WouldBlock`/`TimedOut`.
+fn exchange<S: Read + Write>(
+    stream: S,
+    command: &str,
+    request: &ChargeRequest,
+) -> std::io::Result<WireResponse> {
+    let mut reader = BufReader::new(stream);
+
+    let line = serde_json::to_string(&WireRequest {
+        command,
+        amount_cents: to_cents(request.amount),
+        reference: &request.reference,
+        terminal_id: &request.terminal_id,
+    })?;
+    let stream = reader.get_mut();
+    stream.write_all(line.as_bytes())?;
+    stream.write_all(b"\n")?;
+    stream.flush()?;
+
+    let mut answer = String::new();
+    if reader.read_line(&mut answer)? == 0 {
+        return Err(std::io::Error::new(
+            ErrorKind::UnexpectedEof,
+            "La terminal cerró la conexión sin responder",
+        ));
+    }
+
+    Ok(serde_json::from_str(answer.trim())?)
+}
+
+fn is_timeout(e: &std::io::Error) -> bool {
+    matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+fn charge_over<S: Read + Write>(stream: S, request: &ChargeRequest) -> Result<ChargeOutcome, String> {
+    let response = match exchange(stream, "sale", request) {
+        Ok(response) => response,
+        Err(e) if is_timeout(&e) => return Ok(ChargeOutcome::TimedOut),
+        Err(e) => return Err(format!("Error de comunicación con la terminal: {}", e)),
+    };
+
+    Ok(match response.status.as_str() {
+        "approved" => ChargeOutcome::Approved {
+            auth_code: response.auth_code.unwrap_or_default(),
+            last4: response.last4.unwrap_or_default(),
+            brand: response.brand.unwrap_or_default(),
+        },
+        _ => ChargeOutcome::Declined {
+            message: response
+                .message
+                .unwrap_or_else(|| "Transacción rechazada".to_string()),
+        },
+    })
+}
+
+fn void_over<S: Read + Write>(stream: S, request: &ChargeRequest) -> Result<(), String> {
+    match exchange(stream, "void", request) {
+        Ok(response) if response.status == "voided" => Ok(()),
+        Ok(response) => Err(response
+            .message
+            .unwrap_or_else(|| "La terminal no confirmó la cancelación".to_string())),
+        Err(e) if is_timeout(&e) => Err("La terminal no respondió a la cancelación".to_string()),
+        Err(e) => Err(format!("Error de comunicación con la terminal: {}", e)),
+    }
+}
+
+pub struct TcpTerminal {
+    pub address: String,
+    pub timeout: Duration,
+}
+
+impl TcpTerminal {
+    fn connect(&self, read_timeout: Duration) -> Result<TcpStream, String> {
+        let addr = self
+            .address
+            .to_socket_addrs()
+            .map_err(|e| format!("Dirección de terminal inválida '{}': {}", self.address, e))?
+            .next()
+            .ok_or(format!("Dirección de terminal inválida '{}'", self.address))?;
+
+        let stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+            .map_err(|e| format!("No se pudo conectar con la terminal ({}): {}", self.address, e))?;
+        stream
+            .set_read_timeout(Some(read_timeout))
+            .map_err(|e| e.to_string())?;
+        Ok(stream)
+    }
+}
+
+impl PaymentTerminal for TcpTerminal {
+    fn charge(&self, request: &ChargeRequest) -> Result<ChargeOutcome, String> {
+        charge_over(self.connect(self.timeout)?, request)
+    }
+
+    fn void(&self, request: &ChargeRequest) -> Result<(), String> {
+        void_over(self.connect(self.timeout.min(VOID_TIMEOUT))?, request)
+    }
+}
+
+pub struct SerialTerminal {
+    pub port: String,
+    pub baud_rate: u32,
+    pub timeout: Duration,
+}
+
+impl SerialTerminal {
+    /// Opens the port with a read timeout; the handle is closed when the
+    /// exchange returns, whatever the outcome.
+    fn open(&self, read_timeout: Duration) -> Result<Box<dyn serialport::SerialPort>, String> {
+        serialport::new(&self.port, self.baud_rate)
+            .timeout(read_timeout)
+            .open()
+            .map_err(|e| format!("No se pudo abrir el puerto {}: {}", self.port, e))
+    }
+}
+
+impl PaymentTerminal for SerialTerminal {
+    fn charge(&self, request: &ChargeRequest) -> Result<ChargeOutcome, String> {
+        charge_over(self.open(self.timeout)?, request)
+    }
+
+    fn void(&self, request: &ChargeRequest) -> Result<(), String> {
+        void_over(self.open(self.timeout.min(VOID_TIMEOUT))?, request)
+    }
+}
+
+pub fn build_terminal(config: &PaymentTerminalConfig) -> Result<Box<dyn PaymentTerminal>, String> {
+    let timeout = Duration::from_secs(config.timeout_secs.max(1));
+    match config.driver.as_str() {
+        "tcp" => {
+            let address = config
+                .address
+                .clone()
+                .filter(|a| !a.trim().is_empty())
+                .ok_or("Configure la dirección de la terminal de pago.".to_string())?;
+            Ok(Box::new(TcpTerminal { address, timeout }))
+        }
+        "serial" => {
+            let port = config
+                .serial_port
+                .clone()
+                .filter(|p| !p.trim().is_empty())
+                .ok_or("Configure el puerto serie de la terminal de pago.".to_string())?;
+            Ok(Box::new(SerialTerminal {
+                port,
+                baud_rate: config.baud_rate,
+                timeout,
+            }))
+        }
+        // Approves with fake auth codes: never available in release builds
+        #[cfg(debug_assertions)]
+        "simulator" => {
+            let address = simulator::address()?.to_string();
+            Ok(Box::new(TcpTerminal { address, timeout }))
+        }
+        #[cfg(not(debug_assertions))]
+        "simulator" => Err("El simulador de terminal solo está disponible en compilaciones de desarrollo.".to_string()),
+        _ => Err("No hay una terminal de pago configurada.".to_string()),
+    }
+}
+
+// --- Simulator ---
+
+/// Local terminal for development and tests.
+#[cfg(any(test, debug_assertions))]
+pub mod simulator {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{SocketAddr, TcpListener, TcpStream};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::OnceLock;
+
+    static SIMULATOR: OnceLock<Result<SocketAddr, String>> = OnceLock::new();
+    static SIMULATOR_AUTH: AtomicU64 = AtomicU64::new(100000);
+
+    /// Address of the in-process simulator, started on first use.
+    pub fn address() -> Result<SocketAddr, String> {
+        SIMULATOR
+            .get_or_init(|| start_simulator("127.0.0.1:0"))
+            .clone()
+    }
+
+    /// Starts a local terminal simulator speaking the same protocol as real devices.
+    /// The cents of the amount pick the result, like test cards on a real acquirer:
+    /// `.51` is declined, `.52` never answers (timeout); anything else is approved.
+    /// Voids are always confirmed.
+    pub fn start_simulator(bind: &str) -> Result<SocketAddr, String> {
+        let listener =
+            TcpListener::bind(bind).map_err(|e| format!("No se pudo iniciar el simulador: {}", e))?;
+        let addr = listener.local_addr().map_err(|e| e.to_string())?;
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                std::thread::spawn(move || simulate_session(stream));
+            }
+        });
+
+        Ok(addr)
+    }
+
+    fn simulate_session(stream: TcpStream) {
+        let Ok(mut writer) = stream.try_clone() else {
+            return;
+        };
+        let reader = BufReader::new(stream);
+
+        for line in reader.lines() {
+            let Ok(line) = line else {
+                return;
+            };
+            let message = serde_json::from_str::<serde_json::Value>(&line).unwrap_or_default();
+            let amount_cents = message.get("amount_cents").and_then(|c| c.as_i64()).unwrap_or(0);
+
+            let answer = match amount_cents.rem_euclid(100) {
+                _ if message.get("command").and_then(|c| c.as_str()) == Some("void") => {
+                    serde_json::json!({ "status": "voided" })
+                }
+                51 => serde_json::json!({ "status": "declined", "message": "Fondos insuficientes" }),
+                52 => continue,
+                _ if amount_cents <= 0 => {
+                    serde_json::json!({ "status": "declined", "message": "Monto inválido" })
+                }
+                _ => serde_json::json!({
+                    "status": "approved",
+                    "auth_code": SIMULATOR_AUTH.fetch_add(1, Ordering::Relaxed).to_string(),
+                    "last4": "4242",
+                    "brand": "VISA",
+                }),
+            };
+
+            if writeln!(writer, "{}", answer).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terminal(timeout: Duration) -> TcpTerminal {
+        TcpTerminal {
+            address: simulator::address().unwrap().to_string(),
+            timeout,
+        }
+    }
+
+    fn request(amount: f64) -> ChargeRequest {
+        ChargeRequest {
+            amount,
+            reference: "V-0001".to_string(),
+            terminal_id: "CAJA-01".to_string(),
+        }
+    }
+
+    #[test]
+    fn simulator_approves_regular_amounts() {
+        let outcome = terminal(Duration::from_secs(5)).charge(&request(120.00)).unwrap();
+        match outcome {
+            ChargeOutcome::Approved { auth_code, last4, brand } => {
+                assert!(!auth_code.is_empty());
+                assert_eq!(last4, "4242");
+                assert_eq!(brand, "VISA");
+            }
+            other => panic!("se esperaba aprobación: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn simulator_declines_51_cents() {
+        let outcome = terminal(Duration::from_secs(5)).charge(&request(10.51)).unwrap();
+        match outcome {
+            ChargeOutcome::Declined { message } => assert_eq!(message, "Fondos insuficientes"),
+            other => panic!("se esperaba rechazo: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn simulator_times_out_on_52_cents_and_accepts_void() {
+        let terminal = terminal(Duration::from_secs(1));
+        let outcome = terminal.charge(&request(10.52)).unwrap();
+        assert!(matches!(outcome, ChargeOutcome::TimedOut));
+        assert_eq!(terminal.void(&request(10.52)), Ok(()));
+    }
+
+    #[test]
+    fn simulator_voids_an_approved_charge() {
+        let terminal = terminal(Duration::from_secs(5));
+        let outcome = terminal.charge(&request(35.00)).unwrap();
+        assert!(matches!(outcome, ChargeOutcome::Approved { .. }));
+        assert_eq!(terminal.void(&request(35.00)), Ok(()));
+    }
+
+    #[test]
+    fn unreachable_terminal_is_an_error() {
+        let terminal = TcpTerminal {
+            address: "invalid address".to_string(),
+            timeout: Duration::from_secs(1),
+        };
+        assert!(terminal.charge(&request(1.00)).is_err());
+    }
+}
@@ -0,0 +1,339 @@
+// Printer transports.
+//
+// Receipts are plain ESC/POS byte streams; a transport only decides how the
+// bytes reach the printer:
+//   spooler - OS print queue (`printers` crate), raw job
+//   tcp     - raw socket to the printer, usually port 9100 (JetDirect)
+//   serial  - serial or USB-serial device (COM3, /dev/ttyUSB0...) at the
+//             configured baud rate, 8N1 without flow control
+// The tcp and serial transports also answer real-time status queries
+// (DLE EOT); the spooler hides the device, so it reports no status.
+
+use crate::commands::settings::hardware::HardwareConfig;
+use printers::common::base::job::PrinterJobOptions;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpStream, ToSocketAddrs};
+use serde::Serialize;
+use std::time::Duration;
+
+pub const DEFAULT_RAW_PORT: u16 = 9100;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+const STATUS_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const STATUS_READ_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// DLE EOT 1 (printer), 2 (offline cause) and 4 (roll paper sensor).
+const STATUS_QUERIES: [u8; 9] = [0x10, 0x04, 0x01, 0x10, 0x04, 0x02, 0x10, 0x04, 0x04];
+
+pub trait PrinterTransport: Send {
+    /// Sends a complete job to the printer.
+    fn send(&self, data: &[u8]) -> Result<(), String>;
+    /// Human readable destination for messages ("EPSON TM-T20", "192.168.1.50:9100"...).
+    fn describe(&self) -> String;
+    /// Real-time status, or `None` when the transport cannot query the device.
+    fn query_status(&self) -> Result<Option<PrinterStatus>, String> {
+        Ok(None)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PrinterStatus {
+    pub online: bool,
+    pub cover_open: bool,
+    pub paper_near_end: bool,
+    pub paper_out: bool,
+    /// Mechanical, auto-cutter or unrecoverable error
+    pub error: bool,
+}
+
+impl PrinterStatus {
+    /// Status for a printer that did not answer at all.
+    pub fn unreachable() -> Self {
+        Self {
+            online: false,
+            cover_open: false,
+            paper_near_end: false,
+            paper_out: false,
+            error: false,
+        }
+    }
+
+    /// Decodes the three DLE EOT answers (n = 1, 2, 4).
+    /// Every status byte has the fixed pattern 0xx1xx10b.
+    fn from_responses(responses: &[u8]) -> Result<Self, String> {
+        if responses.len() < 3 || responses[..3].iter().any(|b| b & 0x93 != 0x12) {
+            return Err("Respuesta de estado inválida de la impresora".to_string());
+        }
+        let (printer, offline, paper) = (responses[0], responses[1], responses[2]);
+
+        Ok(Self {
+            online: printer & 0x08 == 0,
+            cover_open: offline & 0x04 != 0,
+            paper_near_end: paper & 0x0C != 0,
+            paper_out: paper & 0x60 != 0 || offline & 0x20 != 0,
+            error: offline & 0x40 != 0,
+        })
+    }
+}
+
+/// Writes the status queries and reads the answers. The stream must have a
+/// read timeout; an expired one means the printer did not answer.
+fn exchange_status<S: Read + Write>(mut stream: S) -> Result<PrinterStatus, String> {
+    stream
+        .write_all(&STATUS_QUERIES)
+        .and_then(|_| stream.flush())
+        .map_err(|e| format!("Error consultando estado: {}", e))?;
+
+    let mut responses = [0u8; 3];
+    match stream.read_exact(&mut responses) {
+        Ok(()) => PrinterStatus::from_responses(&responses),
+        Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+            Err("La impresora no respondió a la consulta de estado".to_string())
+        }
+        Err(e) => Err(format!("Error leyendo estado de la impresora: {}", e)),
+    }
+}
+
+pub struct SpoolerTransport {
+    pub printer_name: String,
+}
+
+impl PrinterTransport for SpoolerTransport {
+    fn send(&self, data: &[u8]) -> Result<(), String> {
+        let printers_list = printers::get_printers();
+        let printer = printers_list
+            .iter()
+            .find(|p| p.name == self.printer_name)
+            .ok_or_else(|| format!("Impresora '{}' no encontrada", self.printer_name))?;
+
+        printer
+            .print(data, PrinterJobOptions::none())
+            .map(|_| ())
+            .map_err(|e| format!("{:?}", e))
+    }
+
+    fn describe(&self) -> String {
+        self.printer_name.clone()
+    }
+}
+
+pub struct TcpTransport {
+    pub address: String,
+}
+
+impl TcpTransport {
+    /// Accepts "host" or "host:port"; the port defaults to 9100.
+    fn socket_addr(&self) -> Result<SocketAddr, String> {
+        let address = self.address.trim();
+        let with_port = if address.rsplit_once(':').map(|(_, p)| p.parse::<u16>().is_ok()) == Some(true) {
+            address.to_string()
+        } else {
+            format!("{}:{}", address, DEFAULT_RAW_PORT)
+        };
+
+        with_port
+            .to_socket_addrs()
+            .map_err(|e| format!("Dirección de impresora inválida '{}': {}", self.address, e))?
+            .next()
+            .ok_or(format!("Dirección de impresora inválida '{}'", self.address))
+    }
+
+    /// Opens a connection for request/response exchanges (status queries).
+    pub fn connect(&self) -> Result<TcpStream, String> {
+        self.connect_with_timeout(CONNECT_TIMEOUT)
+    }
+
+    fn connect_with_timeout(&self, timeout: Duration) -> Result<TcpStream, String> {
+        let addr = self.socket_addr()?;
+        let stream = TcpStream::connect_timeout(&addr, timeout)
+            .map_err(|e| format!("No se pudo conectar con la impresora ({}): {}", self.address, e))?;
+        stream
+            .set_write_timeout(Some(WRITE_TIMEOUT))
+            .map_err(|e| e.to_string())?;
+        Ok(stream)
+    }
+}
+
+impl PrinterTransport for TcpTransport {
+    fn send(&self, data: &[u8]) -> Result<(), String> {
+        let mut stream = self.connect()?;
+        stream
+            .write_all(data)
+            .and_then(|_| stream.flush())
+            .map_err(|e| format!("Error enviando datos a {}: {}", self.address, e))?;
+        // Half-close so the printer sees the end of the job
+        let _ = stream.shutdown(Shutdown::Write);
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        self.address.clone()
+    }
+
+    fn query_status(&self) -> Result<Option<PrinterStatus>, String> {
+        // Nothing listening: report offline instead of failing
+        let stream = match self.connect_with_timeout(STATUS_CONNECT_TIMEOUT) {
+            Ok(stream) => stream,
+            Err(_) => return Ok(Some(PrinterStatus::unreachable())),
+        };
+        stream
+            .set_read_timeout(Some(STATUS_READ_TIMEOUT))
+            .map_err(|e| e.to_string())?;
+        exchange_status(stream).map(Some)
+    }
+}
+
+pub struct SerialTransport {
+    pub port: String,
+    pub baud_rate: u32,
+}
+
+impl SerialTransport {
+    /// Opens the port with explicit line settings; `timeout` bounds every
+    /// read and write.
+    pub fn open(&self, timeout: Duration) -> Result<Box<dyn serialport::SerialPort>, String> {
+        serialport::new(&self.port, self.baud_rate)
+            .data_bits(serialport::DataBits::Eight)
+            .parity(serialport::Parity::None)
+            .stop_bits(serialport::StopBits::One)
+            .flow_control(serialport::FlowControl::None)
+            .timeout(timeout)
+            .open()
+            .map_err(|e| format!("No se pudo abrir el puerto {}: {}", self.port, e))
+    }
+}
+
+impl PrinterTransport for SerialTransport {
+    fn send(&self, data: &[u8]) -> Result<(), String> {
+        let mut port = self.open(WRITE_TIMEOUT)?;
+        port.write_all(data)
+            .and_then(|_| port.flush())
+            .map_err(|e| format!("Error enviando datos al puerto {}: {}", self.port, e))
+    }
+
+    fn describe(&self) -> String {
+        self.port.clone()
+    }
+
+    fn query_status(&self) -> Result<Option<PrinterStatus>, String> {
+        // Unplugged adapter: report offline instead of failing
+        let port = match self.open(STATUS_READ_TIMEOUT) {
+            Ok(port) => port,
+            Err(_) => return Ok(Some(PrinterStatus::unreachable())),
+        };
+        // Drop stale bytes so they are not taken for the answers
+        let _ = port.clear(serialport::ClearBuffer::Input);
+        exchange_status(port).map(Some)
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_ref()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty() && v != "none")
+}
+
+/// Transport selected in the hardware config, or `None` when printing is disabled.
+pub fn open_transport(config: &HardwareConfig) -> Result<Option<Box<dyn PrinterTransport>>, String> {
+    match config.printer_transport.as_str() {
+        "tcp" => {
+            let address = non_empty(&config.printer_address)
+                .ok_or("Configure la dirección IP de la impresora.".to_string())?;
+            Ok(Some(Box::new(TcpTransport { address })))
+        }
+        "serial" => {
+            let port = non_empty(&config.printer_serial_port)
+                .ok_or("Configure el puerto serie de la impresora.".to_string())?;
+            Ok(Some(Box::new(SerialTransport {
+                port,
+                baud_rate: config.printer_serial_baud,
+            })))
+        }
+        _ => Ok(non_empty(&config.printer_name)
+            .map(|printer_name| Box::new(SpoolerTransport { printer_name }) as Box<dyn PrinterTransport>)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+
+    /// Local raw-print listener; every connection's bytes are appended to `data`.
+    pub struct CaptureServer {
+        pub address: SocketAddr,
+        pub data: Arc<Mutex<Vec<u8>>>,
+    }
+
+    impl CaptureServer {
+        /// Bytes received so far.
+        pub fn captured(&self) -> Vec<u8> {
+            self.data.lock().map(|d| d.clone()).unwrap_or_default()
+        }
+    }
+
+    /// Starts a listener that behaves like a raw 9100 printer and keeps what it receives.
+    pub fn start_capture_server(bind: &str) -> Result<CaptureServer, String> {
+        let listener =
+            TcpListener::bind(bind).map_err(|e| format!("No se pudo iniciar el receptor: {}", e))?;
+        let address = listener.local_addr().map_err(|e| e.to_string())?;
+        let data = Arc::new(Mutex::new(Vec::new()));
+
+        let sink = data.clone();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut received = Vec::new();
+                if stream.read_to_end(&mut received).is_ok() {
+                    if let Ok(mut buffer) = sink.lock() {
+                        buffer.extend_from_slice(&received);
+                    }
+                }
+            }
+        });
+
+        Ok(CaptureServer { address, data })
+    }
+
+    #[test]
+    fn tcp_transport_delivers_the_job_to_the_capture_server() {
+        let server = start_capture_server("127.0.0.1:0").unwrap();
+        let job = [&[0x1B, 0x40][..], "Ticket de prueba\n".as_bytes(), &[0x1D, 0x56, 0x00]].concat();
+
+        TcpTransport {
+            address: server.address.to_string(),
+        }
+        .send(&job)
+        .unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while server.captured().len() < job.len() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(server.captured(), job);
+    }
+
+    #[test]
+    fn serial_transport_uses_the_printer_port() {
+        let config = HardwareConfig {
+            printer_transport: "serial".to_string(),
+            cash_drawer_port: Some("COM1".to_string()),
+            printer_serial_port: Some("COM7".to_string()),
+            ..Default::default()
+        };
+        let transport = open_transport(&config).unwrap().unwrap();
+        assert_eq!(transport.describe(), "COM7");
+    }
+
+    #[test]
+    fn missing_serial_port_reports_the_printer_offline() {
+        let transport = SerialTransport {
+            port: "/dev/pos-no-such-port".to_string(),
+            baud_rate: 9600,
+        };
+        let status = transport.query_status().unwrap().unwrap();
+        assert_eq!(status, PrinterStatus::unreachable());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::barcode_utils::{self, Symbology};
 use image::imageops::FilterType;
 use image::DynamicImage;
 use std::sync::Mutex;
@@ -16,62 +17,77 @@ const CMD_SIZE_DOUBLE_H: &[u8] = b"\x1D\x21\x01";
 const CMD_SIZE_DOUBLE_HW: &[u8] = b"\x1D\x21\x11";
 const CMD_CUT: &[u8] = b"\n\n\n\x1D\x56\x42\x00";
 
-/// Helper to resolve and process logo commands from path
-/// TODO: CHECK IN A BETTER PRITER WITH LOGOS WITH ALOT OF BLACK IN IT
-pub fn resolve_logo_bytes(
-    app_handle: &tauri::AppHandle,
-    logo_path: &str,
-    max_width: u32,
-) -> Option<Vec<u8>> {
+/// Absolute path of the configured logo, if the file exists.
+pub fn resolve_logo_path(app_handle: &tauri::AppHandle, logo_path: &str) -> Option<std::path::PathBuf> {
     if logo_path.is_empty() {
         return None;
     }
 
-    use std::path::Path;
+    use std::path::PathBuf;
     use tauri::Manager;
 
-    let logo_path_str = if logo_path.contains("images/settings") {
-        if let Ok(app_dir) = app_handle.path().app_data_dir() {
-            app_dir.join(logo_path).to_string_lossy().to_string()
-        } else {
-            logo_path.to_string()
+    let path = if logo_path.contains("images/settings") {
+        match app_handle.path().app_data_dir() {
+            Ok(app_dir) => app_dir.join(logo_path),
+            Err(_) => PathBuf::from(logo_path),
         }
     } else {
-        logo_path.to_string()
+        PathBuf::from(logo_path)
     };
 
-    if Path::new(&logo_path_str).exists() {
-        let path_obj = Path::new(&logo_path_str);
-        if let Some(stem) = path_obj.file_stem() {
-            let parent = path_obj.parent().unwrap_or(Path::new(""));
-            // Dynamic flush: use exact width for binary suffix to support custom sizes
-            let bin_filename = format!("{}_{}.bin", stem.to_string_lossy(), max_width);
-            let bin_path = parent.join(bin_filename);
-
-            // Try to read pre-converted binary
-            if bin_path.exists() {
-                if let Ok(bin_data) = std::fs::read(&bin_path) {
-                    return Some(bin_data);
-                }
+    path.exists().then_some(path)
+}
+
+/// Helper to resolve and process logo commands from path
+/// TODO: CHECK IN A BETTER PRITER WITH LOGOS WITH ALOT OF BLACK IN IT
+pub fn resolve_logo_bytes(
+    app_handle: &tauri::AppHandle,
+    logo_path: &str,
+    max_width: u32,
+) -> Option<Vec<u8>> {
+    use std::path::Path;
+
+    let path_obj = resolve_logo_path(app_handle, logo_path)?;
+    let logo_path_str = path_obj.to_string_lossy().to_string();
+
+    if let Some(stem) = path_obj.file_stem() {
+        let parent = path_obj.parent().unwrap_or(Path::new(""));
+        // Dynamic flush: use exact width for binary suffix to support custom sizes
+        let bin_filename = format!("{}_{}.bin", stem.to_string_lossy(), max_width);
+        let bin_path = parent.join(bin_filename);
+
+        // Try to read pre-converted binary
+        if bin_path.exists() {
+            if let Ok(bin_data) = std::fs::read(&bin_path) {
+                return Some(bin_data);
             }
+        }
 
-            // Fallback to runtime conversion AND caching
-            match image_to_escpos(&logo_path_str, max_width) {
-                Ok(cmds) => {
-                    // Try to save for cache
-                    if let Err(e) = std::fs::write(&bin_path, &cmds) {
-                        println!("Warning: Failed to save cached logo: {}", e);
-                    }
-                    return Some(cmds);
+        // Fallback to runtime conversion AND caching
+        match image_to_escpos(&logo_path_str, max_width) {
+            Ok(cmds) => {
+                // Try to save for cache
+                if let Err(e) = std::fs::write(&bin_path, &cmds) {
+                    println!("Warning: Failed to save cached logo: {}", e);
                 }
-                Err(e) => println!("Warning: Failed to process logo: {}", e),
+                return Some(cmds);
             }
+            Err(e) => println!("Warning: Failed to process logo: {}", e),
         }
     }
     None
 }
 
 pub fn convert_image_to_escpos(img: DynamicImage, max_width: u32) -> Result<Vec<u8>, String> {
+    convert_image_to_escpos_with(img, max_width, true)
+}
+
+/// `save_energy` prints every other dark pixel; codes meant to be scanned need it off.
+pub fn convert_image_to_escpos_with(
+    img: DynamicImage,
+    max_width: u32,
+    save_energy: bool,
+) -> Result<Vec<u8>, String> {
     // Force target width to be multiple of 8
     let mut target_width = max_width;
     if target_width % 8 != 0 {
@@ -124,7 +140,7 @@ pub fn convert_image_to_escpos(img: DynamicImage, max_width: u32) -> Result<Vec<
                 } else {
                     pixel_val < 128
                 };
-                let save_energy_mask = (x + y) % 2 == 0;
+                let save_energy_mask = !save_energy || (x + y) % 2 == 0;
                 if is_dark_pixel && save_energy_mask {
                     let bit_index = 7 - (x % 8);
                     current_byte |= 1 << bit_index;
@@ -155,9 +171,18 @@ pub fn image_bytes_to_escpos(bytes: &[u8], max_width: u32) -> Result<Vec<u8>, St
 /// Generates a CODE128 barcode as a raster image for ESC/POS printers.
 /// Pure Rust implementation — no external barcode crate needed.
 pub fn generate_barcode_escpos(data: &str, max_width: u32) -> Result<Vec<u8>, String> {
+    generate_barcode_escpos_as(data, Symbology::Code128B, max_width)
+}
+
+/// Raster barcode in the given symbology.
+pub fn generate_barcode_escpos_as(
+    data: &str,
+    symbology: Symbology,
+    max_width: u32,
+) -> Result<Vec<u8>, String> {
     use image::{GrayImage, Luma};
 
-    let encoded = encode_code128b(data)?;
+    let encoded = barcode_utils::encode(data, symbology)?;
 
     // Render barcode as image
     let bar_width: u32 = 3;
@@ -183,188 +208,153 @@ pub fn generate_barcode_escpos(data: &str, max_width: u32) -> Result<Vec<u8>, St
     convert_image_to_escpos(dynamic_img, max_width)
 }
 
-/// CODE128 Code Set B encoder — supports ASCII 32-127 (letters, digits, symbols)
-fn encode_code128b(data: &str) -> Result<Vec<u8>, String> {
-    // CODE128 bar patterns: each symbol = 6 bars (3 black + 3 white) = 11 modules
-    const PATTERNS: &[[u8; 6]; 107] = &[
-        [2, 1, 2, 2, 2, 2],
-        [2, 2, 2, 1, 2, 2],
-        [2, 2, 2, 2, 2, 1],
-        [1, 2, 1, 2, 2, 3],
-        [1, 2, 1, 3, 2, 2], // 0-4
-        [1, 3, 1, 2, 2, 2],
-        [1, 2, 2, 2, 1, 3],
-        [1, 2, 2, 3, 1, 2],
-        [1, 3, 2, 2, 1, 2],
-        [2, 2, 1, 2, 1, 3], // 5-9
-        [2, 2, 1, 3, 1, 2],
-        [2, 3, 1, 2, 1, 2],
-        [1, 1, 2, 2, 3, 2],
-        [1, 2, 2, 1, 3, 2],
-        [1, 2, 2, 2, 3, 1], // 10-14
-        [1, 1, 3, 2, 2, 2],
-        [1, 2, 3, 1, 2, 2],
-        [1, 2, 3, 2, 2, 1],
-        [2, 2, 3, 2, 1, 1],
-        [2, 2, 1, 1, 3, 2], // 15-19
-        [2, 2, 1, 2, 3, 1],
-        [2, 1, 3, 2, 1, 2],
-        [2, 2, 3, 1, 1, 2],
-        [3, 1, 2, 1, 3, 1],
-        [3, 1, 1, 2, 2, 2], // 20-24
-        [3, 2, 1, 1, 2, 2],
-        [3, 2, 1, 2, 2, 1],
-        [3, 1, 2, 2, 1, 2],
-        [3, 2, 2, 1, 1, 2],
-        [3, 2, 2, 2, 1, 1], // 25-29
-        [2, 1, 2, 1, 2, 3],
-        [2, 1, 2, 3, 2, 1],
-        [2, 3, 2, 1, 2, 1],
-        [1, 1, 1, 3, 2, 3],
-        [1, 3, 1, 1, 2, 3], // 30-34
-        [1, 3, 1, 3, 2, 1],
-        [1, 1, 2, 3, 1, 3],
-        [1, 3, 2, 1, 1, 3],
-        [1, 3, 2, 3, 1, 1],
-        [2, 1, 1, 3, 1, 3], // 35-39
-        [2, 3, 1, 1, 1, 3],
-        [2, 3, 1, 3, 1, 1],
-        [1, 1, 2, 1, 3, 3],
-        [1, 1, 2, 3, 3, 1],
-        [1, 3, 2, 1, 3, 1], // 40-44
-        [1, 1, 3, 1, 2, 3],
-        [1, 1, 3, 3, 2, 1],
-        [1, 3, 3, 1, 2, 1],
-        [3, 1, 3, 1, 2, 1],
-        [2, 1, 1, 3, 3, 1], // 45-49
-        [2, 3, 1, 1, 3, 1],
-        [2, 1, 3, 1, 1, 3],
-        [2, 1, 3, 3, 1, 1],
-        [2, 1, 3, 1, 3, 1],
-        [3, 1, 1, 1, 2, 3], // 50-54
-        [3, 1, 1, 3, 2, 1],
-        [3, 3, 1, 1, 2, 1],
-        [3, 1, 2, 1, 1, 3],
-        [3, 1, 2, 3, 1, 1],
-        [3, 3, 2, 1, 1, 1], // 55-59
-        [3, 1, 4, 1, 1, 1],
-        [2, 2, 1, 4, 1, 1],
-        [4, 3, 1, 1, 1, 1],
-        [1, 1, 1, 2, 2, 4],
-        [1, 1, 1, 4, 2, 2], // 60-64
-        [1, 2, 1, 1, 2, 4],
-        [1, 2, 1, 4, 2, 1],
-        [1, 4, 1, 1, 2, 2],
-        [1, 4, 1, 2, 2, 1],
-        [1, 1, 2, 2, 1, 4], // 65-69
-        [1, 1, 2, 4, 1, 2],
-        [1, 2, 2, 1, 1, 4],
-        [1, 2, 2, 4, 1, 1],
-        [1, 4, 2, 1, 1, 2],
-        [1, 4, 2, 2, 1, 1], // 70-74
-        [2, 4, 1, 2, 1, 1],
-        [2, 2, 1, 1, 1, 4],
-        [4, 1, 3, 1, 1, 1],
-        [2, 4, 1, 1, 1, 2],
-        [1, 3, 4, 1, 1, 1], // 75-79
-        [1, 1, 1, 2, 4, 2],
-        [1, 2, 1, 1, 4, 2],
-        [1, 2, 1, 2, 4, 1],
-        [1, 1, 4, 2, 1, 2],
-        [1, 2, 4, 1, 1, 2], // 80-84
-        [1, 2, 4, 2, 1, 1],
-        [4, 1, 1, 2, 1, 2],
-        [4, 2, 1, 1, 1, 2],
-        [4, 2, 1, 2, 1, 1],
-        [2, 1, 2, 1, 4, 1], // 85-89
-        [2, 1, 4, 1, 2, 1],
-        [4, 1, 2, 1, 2, 1],
-        [1, 1, 1, 1, 4, 3],
-        [1, 1, 1, 3, 4, 1],
-        [1, 3, 1, 1, 4, 1], // 90-94
-        [1, 1, 4, 1, 1, 3],
-        [1, 1, 4, 3, 1, 1],
-        [4, 1, 1, 1, 1, 3],
-        [4, 1, 1, 3, 1, 1],
-        [1, 1, 3, 1, 4, 1], // 95-99
-        [1, 1, 4, 1, 3, 1],
-        [3, 1, 1, 1, 4, 1],
-        [4, 1, 1, 1, 3, 1],
-        [2, 1, 1, 4, 1, 2],
-        [2, 1, 1, 2, 1, 4], // 100-104
-        [2, 1, 1, 2, 3, 2],
-        [2, 3, 3, 1, 1, 1], // 105-106
-    ];
-    const STOP: [u8; 7] = [2, 3, 3, 1, 1, 1, 2]; // Stop pattern (13 modules)
-
-    let start_code = 104u32; // Start Code B
-    let mut values: Vec<u32> = Vec::new();
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QrErrorCorrection {
+    L,
+    M,
+    Q,
+    H,
+}
 
-    for ch in data.chars() {
-        let ascii = ch as u32;
-        if ascii < 32 || ascii > 127 {
-            return Err(format!("Carácter no soportado en CODE128B: '{}'", ch));
+impl QrErrorCorrection {
+    pub fn parse(level: &str) -> Self {
+        match level.trim().to_uppercase().as_str() {
+            "L" => Self::L,
+            "Q" => Self::Q,
+            "H" => Self::H,
+            _ => Self::M,
         }
-        values.push(ascii - 32);
     }
 
-    // Calculate checksum
-    let mut checksum = start_code;
-    for (i, &val) in values.iter().enumerate() {
-        checksum += val * (i as u32 + 1);
+    /// Largest byte-mode payload of a version 40 symbol at this level.
+    fn max_bytes(self) -> usize {
+        match self {
+            Self::L => 2953,
+            Self::M => 2331,
+            Self::Q => 1663,
+            Self::H => 1273,
+        }
     }
-    checksum %= 103;
 
-    // Build bar pattern
-    let mut bars: Vec<u8> = Vec::new();
-
-    // Quiet zone
-    for _ in 0..10 {
-        bars.push(0);
+    /// `n` for GS ( k <Function 169>
+    fn escpos_code(self) -> u8 {
+        match self {
+            Self::L => 0x30,
+            Self::M => 0x31,
+            Self::Q => 0x32,
+            Self::H => 0x33,
+        }
     }
 
-    // Start pattern
-    let pattern_to_bars = |pattern: &[u8], bars: &mut Vec<u8>| {
-        let mut is_bar = true; // starts with a bar (black)
-        for &width in pattern {
-            for _ in 0..width {
-                bars.push(if is_bar { 1 } else { 0 });
-            }
-            is_bar = !is_bar;
+    fn ec_level(self) -> qrcode::EcLevel {
+        match self {
+            Self::L => qrcode::EcLevel::L,
+            Self::M => qrcode::EcLevel::M,
+            Self::Q => qrcode::EcLevel::Q,
+            Self::H => qrcode::EcLevel::H,
         }
-    };
+    }
+}
 
-    pattern_to_bars(&PATTERNS[start_code as usize], &mut bars);
+#[derive(Debug, Clone, Copy)]
+pub struct QrOptions {
+    /// Printer-side QR (GS ( k); otherwise a raster image is sent
+    pub native: bool,
+    /// Dots per module, 1-16
+    pub module_size: u8,
+    pub error_correction: QrErrorCorrection,
+}
 
-    // Data patterns
-    for &val in &values {
-        pattern_to_bars(&PATTERNS[val as usize], &mut bars);
+impl Default for QrOptions {
+    fn default() -> Self {
+        Self {
+            native: true,
+            module_size: 6,
+            error_correction: QrErrorCorrection::M,
+        }
     }
+}
 
-    // Checksum pattern
-    pattern_to_bars(&PATTERNS[checksum as usize], &mut bars);
-
-    // Stop pattern
-    let mut is_bar = true;
-    for &width in STOP.iter() {
-        for _ in 0..width {
-            bars.push(if is_bar { 1 } else { 0 });
+impl QrOptions {
+    pub fn from_config(config: &HardwareConfig) -> Self {
+        Self {
+            native: config.qr_mode != "raster",
+            module_size: config.qr_module_size.clamp(1, 16),
+            error_correction: QrErrorCorrection::parse(&config.qr_error_correction),
         }
-        is_bar = !is_bar;
     }
+}
+
+/// Native QR commands: model 2, module size, error correction, store data, print.
+pub fn generate_qr_native(data: &str, options: &QrOptions) -> Result<Vec<u8>, String> {
+    let bytes = data.as_bytes();
+    let max_bytes = options.error_correction.max_bytes();
+    if bytes.is_empty() || bytes.len() > max_bytes {
+        return Err(format!(
+            "El contenido del QR debe tener entre 1 y {} bytes",
+            max_bytes
+        ));
+    }
+
+    let len = bytes.len() + 3;
+    let (p_l, p_h) = ((len % 256) as u8, (len / 256) as u8);
+
+    let mut cmds = Vec::with_capacity(bytes.len() + 40);
+    cmds.extend_from_slice(&[0x1D, 0x28, 0x6B, 0x04, 0x00, 0x31, 0x41, 0x32, 0x00]);
+    cmds.extend_from_slice(&[0x1D, 0x28, 0x6B, 0x03, 0x00, 0x31, 0x43, options.module_size.clamp(1, 16)]);
+    cmds.extend_from_slice(&[0x1D, 0x28, 0x6B, 0x03, 0x00, 0x31, 0x45, options.error_correction.escpos_code()]);
+    cmds.extend_from_slice(&[0x1D, 0x28, 0x6B, p_l, p_h, 0x31, 0x50, 0x30]);
+    cmds.extend_from_slice(bytes);
+    cmds.extend_from_slice(&[0x1D, 0x28, 0x6B, 0x03, 0x00, 0x31, 0x51, 0x30]);
+    Ok(cmds)
+}
+
+/// QR code as a raster image, for printers without native QR support.
+/// The module size shrinks if the code would not fit in `max_width` dots.
+pub fn generate_qr_escpos(data: &str, options: &QrOptions, max_width: u32) -> Result<Vec<u8>, String> {
+    use image::{GrayImage, Luma};
+
+    let code = qrcode::QrCode::with_error_correction_level(data.as_bytes(), options.error_correction.ec_level())
+        .map_err(|e| format!("No se pudo generar el QR: {}", e))?;
 
-    // Quiet zone
-    for _ in 0..10 {
-        bars.push(0);
+    let quiet_zone: u32 = 4;
+    let modules = code.width() as u32 + quiet_zone * 2;
+    let module_px = (options.module_size.clamp(1, 16) as u32).min(max_width / modules).max(1);
+
+    // Width in whole bytes so the image is not rescaled
+    let size = modules * module_px;
+    let img_width = size.div_ceil(8) * 8;
+    let offset = (img_width - size) / 2;
+
+    let mut img = GrayImage::from_pixel(img_width, size, Luma([255u8]));
+    let colors = code.to_colors();
+    let width = code.width();
+    for (i, color) in colors.iter().enumerate() {
+        if *color != qrcode::Color::Dark {
+            continue;
+        }
+        let x0 = offset + ((i % width) as u32 + quiet_zone) * module_px;
+        let y0 = ((i / width) as u32 + quiet_zone) * module_px;
+        for dy in 0..module_px {
+            for dx in 0..module_px {
+                img.put_pixel(x0 + dx, y0 + dy, Luma([0u8]));
+            }
+        }
     }
 
-    Ok(bars)
+    convert_image_to_escpos_with(DynamicImage::ImageLuma8(img), img_width, false)
 }
 
+use crate::commands::cash_register::daily_report::DailyReport;
 use crate::commands::cash_register::details::ShiftDetailsDto;
+use crate::commands::cash_register::payments::{
+    fetch_sale_payments, payment_method_label, SalePaymentView,
+};
 use crate::commands::settings::business::BusinessSettings;
 use crate::commands::settings::hardware::HardwareConfig;
-use printers::common::base::job::PrinterJobOptions;
+use crate::commands::print_queue::submit_job;
+use crate::printer_transport::open_transport;
+use crate::receipt_template::{EscPosSink, ReceiptTemplate, TemplateContext};
 
 use tauri::Manager;
 
@@ -377,9 +367,7 @@ pub struct TicketData {
     pub subtotal: f64,
     pub discount: f64,
     pub total: f64,
-    pub cash_amount: f64,
-    pub card_amount: f64,
-    pub voucher_amount: f64,
+    pub payments: Vec<SalePaymentView>,
     pub change: f64,
     pub customer_name: Option<String>,
     pub customer_code: Option<String>,
@@ -401,6 +389,7 @@ pub struct ReceiptBuilder {
     pub content: Vec<u8>,
     pub max_width: u32,
     pub max_chars: usize,
+    pub qr_options: QrOptions,
 }
 
 impl ReceiptBuilder {
@@ -414,6 +403,7 @@ impl ReceiptBuilder {
             content: Vec::new(),
             max_width,
             max_chars,
+            qr_options: QrOptions::default(),
         }
     }
 
@@ -483,6 +473,22 @@ impl ReceiptBuilder {
         }
     }
 
+    /// QR code using `qr_options`; native codes that the printer cannot
+    /// take (too long) are rasterized instead.
+    pub fn add_qr(&mut self, data: &str) {
+        let cmds = if self.qr_options.native {
+            generate_qr_native(data, &self.qr_options)
+                .or_else(|_| generate_qr_escpos(data, &self.qr_options, self.max_width))
+        } else {
+            generate_qr_escpos(data, &self.qr_options, self.max_width)
+        };
+
+        match cmds {
+            Ok(cmds) => self.add_image(&cmds),
+            Err(e) => println!("Warning: Failed to print QR: {}", e),
+        }
+    }
+
     pub fn kick_drawer(&mut self, hex_cmd: &str) {
         if let Ok(bytes) = hex_to_bytes(hex_cmd) {
             self.content.extend_from_slice(&bytes);
@@ -498,6 +504,366 @@ impl ReceiptBuilder {
     }
 }
 
+pub struct VoucherReceiptData {
+    pub code: String,
+    pub balance: f64,
+    pub created_at: String,
+    pub sale_folio: Option<String>,
+    pub return_folio: Option<i64>,
+}
+
+pub struct PaymentReceiptData {
+    pub folio: String,
+    pub amount: f64,
+    pub cash_amount: f64,
+    pub card_amount: f64,
+    pub payment_method: String,
+    pub payment_date: String,
+    pub customer_name: String,
+    pub customer_code: String,
+}
+
+// --- Templates ---
+
+/// Active custom template for a document type, if one was saved.
+fn custom_template(app_handle: &tauri::AppHandle, document_type: &str) -> Option<ReceiptTemplate> {
+    use crate::commands::settings::receipt_templates::fetch_active_template;
+    use rusqlite::Connection;
+
+    let db_state: State<Mutex<Connection>> = app_handle.state();
+    let content = {
+        let conn = db_state.lock().ok()?;
+        fetch_active_template(&conn, document_type).ok()??
+    };
+
+    match ReceiptTemplate::parse(&content) {
+        Ok(template) => Some(template),
+        Err(e) => {
+            println!("Warning: Invalid '{}' receipt template: {}", document_type, e);
+            None
+        }
+    }
+}
+
+/// Template printed for a document type: the active custom one, or the
+/// starter layout when none was saved or the saved one no longer parses.
+pub(crate) fn receipt_template(
+    app_handle: &tauri::AppHandle,
+    document_type: &str,
+) -> Result<ReceiptTemplate, String> {
+    use crate::commands::settings::receipt_templates::default_template;
+
+    match custom_template(app_handle, document_type) {
+        Some(template) => Ok(template),
+        None => ReceiptTemplate::parse(&default_template(document_type)),
+    }
+}
+
+/// Renders a receipt template to ESC/POS bytes.
+fn render_template_job(
+    app_handle: &tauri::AppHandle,
+    template: &ReceiptTemplate,
+    context: &TemplateContext,
+    settings: &BusinessSettings,
+    hardware_config: &HardwareConfig,
+    kick_drawer: bool,
+) -> Vec<u8> {
+    let width_val = hardware_config.printer_width.parse::<u32>().unwrap_or(80);
+    let mut builder = ReceiptBuilder::new(width_val);
+    builder.qr_options = QrOptions::from_config(hardware_config);
+    builder.init();
+    if kick_drawer && hardware_config.auto_open_cash_drawer {
+        builder.kick_drawer(&hardware_config.cash_drawer_command);
+    }
+
+    let logo_width = (builder.max_width as f64 * 0.5) as u32;
+    let logo = resolve_logo_bytes(app_handle, &settings.logo_path, logo_width);
+    let mut sink = EscPosSink { builder, logo };
+    template.render(context, &mut sink);
+    sink.builder.build()
+}
+
+fn store_template_context(settings: &BusinessSettings) -> TemplateContext {
+    let mut context = TemplateContext::new();
+    context
+        .text("store_name", settings.store_name.as_str())
+        .text("store_address", settings.store_address.as_str())
+        .text("ticket_header", settings.ticket_header.as_str())
+        .text("ticket_footer", settings.ticket_footer.as_str())
+        .text("currency_symbol", settings.currency_symbol.as_str())
+        .text(
+            "printed_at",
+            chrono::Local::now().format("%d/%m/%Y %H:%M").to_string(),
+        );
+    context
+}
+
+fn payments_template_list(payments: &[SalePaymentView]) -> Vec<TemplateContext> {
+    payments
+        .iter()
+        .map(|payment| {
+            let mut item = TemplateContext::new();
+            item.text("method", payment_method_label(&payment.method))
+                .number("amount", payment.amount)
+                .number("tendered", payment.tendered)
+                .opt_text("currency", payment.currency_code.as_deref())
+                .number("foreign_amount", payment.foreign_amount.unwrap_or(0.0))
+                .number("exchange_rate", payment.exchange_rate.unwrap_or(0.0))
+                .opt_text("card_brand", payment.card_brand.as_deref())
+                .opt_text("card_last4", payment.card_last4.as_deref())
+                .opt_text("auth_code", payment.auth_code.as_deref())
+                .opt_text("reference", payment.reference.as_deref());
+            item
+        })
+        .collect()
+}
+
+pub(crate) fn sale_template_context(data: &TicketData) -> TemplateContext {
+    let items = data
+        .items
+        .iter()
+        .map(|ticket_item| {
+            let mut item = TemplateContext::new();
+            item.text("description", ticket_item.description.as_str())
+                .number("quantity", ticket_item.quantity)
+                .number("unit_price", ticket_item.unit_price)
+                .number("total", ticket_item.total)
+                .opt_text("promotion", ticket_item.promotion_name.as_deref());
+            item
+        })
+        .collect();
+
+    // Items outside promotions merged by description and price
+    let mut lines: Vec<(&str, f64, f64, f64)> = Vec::new();
+    // Promotion groups in order of appearance
+    let mut combos: Vec<(&str, &str, Vec<&TicketItem>)> = Vec::new();
+    for item in &data.items {
+        if let Some(promotion_id) = item.promotion_id.as_deref() {
+            match combos.iter_mut().find(|(id, _, _)| *id == promotion_id) {
+                Some((_, _, group)) => group.push(item),
+                None => {
+                    let name = item.promotion_name.as_deref().unwrap_or("Promocion");
+                    combos.push((promotion_id, name, vec![item]));
+                }
+            }
+            continue;
+        }
+
+        let same_line = |line: &&mut (&str, f64, f64, f64)| {
+            line.0 == item.description && (line.1 - item.unit_price).abs() < 0.005
+        };
+        match lines.iter_mut().find(same_line) {
+            Some(line) => {
+                line.2 += item.quantity;
+                line.3 += item.total;
+            }
+            None => lines.push((item.description.as_str(), item.unit_price, item.quantity, item.total)),
+        }
+    }
+    let lines = lines
+        .into_iter()
+        .map(|(description, unit_price, quantity, total)| {
+            let mut line = TemplateContext::new();
+            line.text("description", description)
+                .number("quantity", quantity)
+                .number("unit_price", unit_price)
+                .number("total", total);
+            line
+        })
+        .collect();
+    let combos = combos
+        .into_iter()
+        .map(|(_, name, group)| {
+            let items = group
+                .iter()
+                .map(|ticket_item| {
+                    let mut item = TemplateContext::new();
+                    item.text("description", ticket_item.description.as_str())
+                        .number("quantity", ticket_item.quantity)
+                        .number("total", ticket_item.total);
+                    item
+                })
+                .collect();
+            let mut combo = TemplateContext::new();
+            combo
+                .text("name", name)
+                .list("items", items)
+                .number("total", group.iter().map(|i| i.total).sum());
+            combo
+        })
+        .collect();
+
+    let mut context = store_template_context(&data.business_settings);
+    context
+        .text("folio", data.folio.as_str())
+        .text("date", data.date.as_str())
+        .number("item_count", data.items.iter().map(|i| i.quantity).sum())
+        .list("items", items)
+        .list("lines", lines)
+        .list("combos", combos)
+        .number("subtotal", data.subtotal)
+        .number("discount", data.discount)
+        .number("total", data.total)
+        .list("payments", payments_template_list(&data.payments))
+        .number("change", data.change)
+        .opt_text("customer_name", data.customer_name.as_deref())
+        .opt_text("customer_code", data.customer_code.as_deref());
+    context
+}
+
+pub(crate) fn voucher_template_context(
+    settings: &BusinessSettings,
+    data: &VoucherReceiptData,
+) -> TemplateContext {
+    let mut context = store_template_context(settings);
+    context
+        .text("code", data.code.as_str())
+        .number("balance", data.balance)
+        .text("created_at", data.created_at.as_str())
+        .opt_text("sale_folio", data.sale_folio.as_deref())
+        .text(
+            "return_folio",
+            data.return_folio.map(|f| f.to_string()).unwrap_or_default(),
+        );
+    context
+}
+
+pub(crate) fn payment_template_context(
+    settings: &BusinessSettings,
+    data: &PaymentReceiptData,
+) -> TemplateContext {
+    let method = match data.payment_method.as_str() {
+        "cash" => "Efectivo",
+        "card" => "Tarjeta / Transferencia",
+        "mixed" => "Mixto (Efectivo + Tarjeta)",
+        "transfer" => "Transferencia",
+        other => other,
+    };
+
+    let mut context = store_template_context(settings);
+    context
+        .text("folio", data.folio.as_str())
+        .number("amount", data.amount)
+        .number("cash_amount", data.cash_amount)
+        .number("card_amount", data.card_amount)
+        .text("method", method)
+        .text("date", data.payment_date.as_str())
+        .text("customer_name", data.customer_name.as_str())
+        .text("customer_code", data.customer_code.as_str());
+    context
+}
+
+pub(crate) fn shift_template_context(
+    settings: &BusinessSettings,
+    details: &ShiftDetailsDto,
+) -> TemplateContext {
+    let shift = &details.shift;
+    let movements = |type_: &str| {
+        details
+            .movements
+            .iter()
+            .filter(|m| m.type_ == type_)
+            .map(|m| {
+                let mut item = TemplateContext::new();
+                item.text("concept", m.concept.as_str())
+                    .number("amount", m.amount)
+                    .opt_text("note", m.description.as_deref().map(str::trim));
+                item
+            })
+            .collect::<Vec<_>>()
+    };
+    let foreign_cash = details
+        .foreign_cash
+        .iter()
+        .map(|foreign| {
+            let mut item = TemplateContext::new();
+            item.text("currency", foreign.currency_code.as_str())
+                .number("amount", foreign.amount)
+                .number("base_amount", foreign.base_amount);
+            item
+        })
+        .collect();
+    let currency_counts: Vec<TemplateContext> = details
+        .currency_counts
+        .iter()
+        .filter_map(|count| {
+            let (counted, difference) = (count.counted_amount?, count.difference?);
+            let mut item = TemplateContext::new();
+            item.text("currency", count.currency_code.as_str())
+                .number("counted", counted)
+                .number("difference", difference);
+            Some(item)
+        })
+        .collect();
+    let has_foreign_cash = !details.foreign_cash.is_empty() || !currency_counts.is_empty();
+
+    let mut context = store_template_context(settings);
+    context
+        .opt_text("code", shift.code.as_deref())
+        .text("opening_date", shift.opening_date.as_str())
+        .opt_text("closing_date", shift.closing_date.as_deref())
+        .opt_text("opening_user", shift.opening_user_name.as_deref())
+        .opt_text("closing_user", shift.closing_user_name.as_deref())
+        .number("initial_cash", shift.initial_cash)
+        .number("cash_withdrawal", shift.cash_withdrawal.unwrap_or(0.0))
+        .opt_text("notes", shift.notes.as_deref().map(str::trim))
+        .number("sales_count", details.sales_count as f64)
+        .number("total_sales", details.total_sales)
+        .number("total_cash_sales", details.total_cash_sales)
+        .number("total_card_sales", details.total_card_sales)
+        .number("total_credit_sales", details.total_credit_sales)
+        .number("total_voucher_sales", details.total_voucher_sales)
+        .number("total_gift_card_sales", details.total_gift_card_sales)
+        .number("total_points_sales", details.total_points_sales)
+        .number("total_exchange_sales", details.total_exchange_sales)
+        .number("total_debt_payments", details.total_debt_payments)
+        .number("debt_payments_cash", details.debt_payments_cash)
+        .number("debt_payments_card", details.debt_payments_card)
+        .list("movements_in", movements("IN"))
+        .list("movements_out", movements("OUT"))
+        .number("total_movements_in", details.total_movements_in)
+        .number("total_movements_out", details.total_movements_out)
+        .number("total_cash_refunds", details.total_cash_refunds)
+        .number("total_card_refunds", details.total_card_refunds)
+        .number("cash_reversals", details.cash_reversals)
+        .number("card_reversals", details.card_reversals)
+        .list("foreign_cash", foreign_cash)
+        .list("currency_counts", currency_counts)
+        .flag("has_foreign_cash", has_foreign_cash)
+        .flag(
+            "has_cash_activity",
+            [
+                details.total_cash_sales,
+                details.debt_payments_cash,
+                details.total_movements_in,
+                details.total_movements_out,
+                details.total_cash_refunds,
+                details.cash_reversals,
+            ]
+            .iter()
+            .any(|amount| *amount > 0.0),
+        )
+        .flag(
+            "has_card_activity",
+            [
+                details.total_card_sales,
+                details.debt_payments_card,
+                details.total_card_refunds,
+                details.card_reversals,
+            ]
+            .iter()
+            .any(|amount| *amount > 0.0),
+        )
+        .number("total_cash", details.total_cash)
+        .number(
+            "total_card",
+            details.total_card_sales + details.debt_payments_card
+                - details.total_card_refunds
+                - details.card_reversals,
+        );
+    context
+}
+
 fn print_store_header(
     builder: &mut ReceiptBuilder,
     settings: &BusinessSettings,
@@ -529,6 +895,39 @@ fn print_store_header(
     }
 }
 
+/// Prints one line per tender (with its reference, if any) and returns the change given.
+fn print_payment_lines(builder: &mut ReceiptBuilder, payments: &[SalePaymentView]) -> f64 {
+    let mut change = 0.0;
+    for payment in payments {
+        match (&payment.currency_code, payment.foreign_amount, payment.exchange_rate) {
+            (Some(code), Some(foreign_amount), Some(rate)) => {
+                builder.add_text_ln(&format!(
+                    "{} {}: {:>10.2}",
+                    payment_method_label(&payment.method),
+                    code,
+                    foreign_amount
+                ));
+                builder.add_text_ln(&format!("T.C. {:.4} = {:>10.2}", rate, payment.tendered));
+            }
+            _ => builder.add_text_ln(&format!(
+                "{}: {:>10.2}",
+                payment_method_label(&payment.method),
+                payment.tendered
+            )),
+        }
+        if let (Some(brand), Some(last4)) = (&payment.card_brand, &payment.card_last4) {
+            builder.add_text_ln(&format!("{} ****{}", remove_accents(brand), last4));
+        }
+        if let Some(auth_code) = payment.auth_code.as_deref().filter(|a| !a.is_empty()) {
+            builder.add_text_ln(&format!("AUT: {}", auth_code));
+        } else if let Some(reference) = payment.reference.as_deref().filter(|r| !r.is_empty()) {
+            builder.add_text_ln(&format!("REF: {}", remove_accents(reference)));
+        }
+        change += (payment.tendered - payment.amount).max(0.0);
+    }
+    change
+}
+
 fn print_receipt_footer(builder: &mut ReceiptBuilder, settings: &BusinessSettings) {
     builder.align_center();
     if !settings.ticket_footer.is_empty() {
@@ -539,10 +938,18 @@ fn print_receipt_footer(builder: &mut ReceiptBuilder, settings: &BusinessSetting
 }
 
 pub fn print_sale_from_db(app_handle: tauri::AppHandle, sale_id: String) -> Result<(), String> {
+    let ticket_data = load_ticket_data(&app_handle, &sale_id)?;
+    let printer_name = ticket_data.hardware_config.printer_name.clone();
+    print_ticket(&printer_name, ticket_data, app_handle)
+}
+
+/// Ticket contents of a sale, net of partial returns.
+pub fn load_ticket_data(app_handle: &tauri::AppHandle, sale_id: &str) -> Result<TicketData, String> {
     use crate::commands::settings::business::fetch_business_settings;
     use crate::commands::settings::hardware::load_settings;
     use rusqlite::Connection;
 
+    let sale_id = sale_id.to_string();
     let db_state: State<Mutex<Connection>> = app_handle.state();
     let conn = db_state.lock().map_err(|e| e.to_string())?;
 
@@ -571,7 +978,7 @@ pub fn print_sale_from_db(app_handle: tauri::AppHandle, sale_id: String) -> Resu
 
         // Fetch Header
         let sale_row = conn.query_row(
-            "SELECT folio, created_at, subtotal, discount_amount, total, cash_amount, card_transfer_amount, discount_percentage, customer_id, payment_method 
+            "SELECT folio, created_at, subtotal, discount_amount, total, discount_percentage, customer_id, payment_method 
              FROM sales WHERE id = ?1",
             [&sale_id],
             |row| {
@@ -581,11 +988,9 @@ pub fn print_sale_from_db(app_handle: tauri::AppHandle, sale_id: String) -> Resu
                     row.get::<_, f64>(2)?,    // subtotal
                     row.get::<_, f64>(3)?,    // discount_amount
                     row.get::<_, f64>(4)?,    // total
-                    row.get::<_, f64>(5)?,    // cash
-                    row.get::<_, f64>(6)?,    // card
-                    row.get::<_, f64>(7)?,    // discount_percentage
-                    row.get::<_, Option<String>>(8)?, // customer_id
-                    row.get::<_, String>(9)?, // payment_method
+                    row.get::<_, f64>(5)?,    // discount_percentage
+                    row.get::<_, Option<String>>(6)?, // customer_id
+                    row.get::<_, String>(7)?, // payment_method
                 ))
             }
         ).map_err(|e| format!("Venta no encontrada: {}", e))?;
@@ -620,14 +1025,7 @@ pub fn print_sale_from_db(app_handle: tauri::AppHandle, sale_id: String) -> Resu
         (sale_row, items, settings)
     };
 
-    // Calculate Voucher Amount
-    let voucher_amount: f64 = conn
-        .query_row(
-            "SELECT IFNULL(SUM(amount), 0.0) FROM sale_vouchers WHERE sale_id = ?1",
-            [&sale_id],
-            |row| row.get(0),
-        )
-        .unwrap_or(0.0);
+    let payments = fetch_sale_payments(&conn, &sale_id)?;
 
     // Calculate Returns Map
     let mut returns_map: std::collections::HashMap<String, f64> = std::collections::HashMap::new();
@@ -658,8 +1056,6 @@ pub fn print_sale_from_db(app_handle: tauri::AppHandle, sale_id: String) -> Resu
         orig_subtotal,
         orig_discount_amt,
         orig_total,
-        cash,
-        card,
         discount_percentage,
         customer_id,
         sale_payment_method,
@@ -719,34 +1115,48 @@ pub fn print_sale_from_db(app_handle: tauri::AppHandle, sale_id: String) -> Resu
         (filtered_items, new_subtotal, new_discount_amt, new_total)
     };
 
-    let paid_amount = cash + card + voucher_amount;
-    let change = if paid_amount > total {
-        paid_amount - total
-    } else {
-        0.0
-    };
+    let change: f64 = payments
+        .iter()
+        .map(|p| (p.tendered - p.amount).max(0.0))
+        .sum();
 
-    let ticket_data = TicketData {
+    Ok(TicketData {
         business_settings,
-        hardware_config: hardware_config.clone(),
-        folio: folio.clone(),
+        hardware_config,
+        folio,
         date: date_str,
         items: final_items,
         subtotal,
         discount,
         total,
-        cash_amount: cash,
-        card_amount: card,
-        voucher_amount,
+        payments,
         change,
         customer_name: cust_name,
         customer_code: cust_code,
+    })
+}
+
+pub fn print_voucher_from_db(app_handle: tauri::AppHandle, sale_id: String) -> Result<(), String> {
+    use rusqlite::{Connection, OptionalExtension};
+
+    let code: String = {
+        let db_state: State<Mutex<Connection>> = app_handle.state();
+        let conn = db_state.lock().map_err(|e| e.to_string())?;
+
+        conn.query_row(
+            "SELECT code FROM store_vouchers WHERE sale_id = ?1 ORDER BY created_at DESC LIMIT 1",
+            [&sale_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Error consultando vale: {}", e))?
+        .ok_or("No se encontró vale para esta venta".to_string())?
     };
 
-    print_ticket(&hardware_config.printer_name, ticket_data, app_handle)
+    print_voucher_by_code(app_handle, code)
 }
 
-pub fn print_voucher_from_db(app_handle: tauri::AppHandle, sale_id: String) -> Result<(), String> {
+pub fn print_voucher_by_code(app_handle: tauri::AppHandle, code: String) -> Result<(), String> {
     use crate::commands::settings::business::fetch_business_settings;
     use crate::commands::settings::hardware::load_settings;
     use rusqlite::{Connection, OptionalExtension};
@@ -754,14 +1164,15 @@ pub fn print_voucher_from_db(app_handle: tauri::AppHandle, sale_id: String) -> R
     let db_state: State<Mutex<Connection>> = app_handle.state();
     let conn = db_state.lock().map_err(|e| e.to_string())?;
 
-    // Fetch voucher data
-    let voucher_data: Option<(String, f64, f64, bool, String, Option<String>)> = conn.query_row(
-        "SELECT sv.code, sv.initial_balance, sv.current_balance, sv.is_active, sv.created_at, sv.expires_at
+    // Fetch voucher data (origin is either the sale or a return without ticket)
+    let voucher_data: Option<(String, f64, f64, bool, String, Option<String>, Option<String>, Option<i64>)> = conn.query_row(
+        "SELECT sv.code, sv.initial_balance, sv.current_balance, sv.is_active, sv.created_at, sv.expires_at,
+                s.folio, r.folio
          FROM store_vouchers sv
-         WHERE sv.sale_id = ?1
-         ORDER BY sv.created_at DESC
-         LIMIT 1",
-        [&sale_id],
+         LEFT JOIN sales s ON s.id = sv.sale_id
+         LEFT JOIN returns r ON r.id = sv.return_id
+         WHERE sv.code = ?1",
+        [&code],
         |row| Ok((
             row.get::<_, String>(0)?,    // code
             row.get::<_, f64>(1)?,       // initial_balance
@@ -769,23 +1180,18 @@ pub fn print_voucher_from_db(app_handle: tauri::AppHandle, sale_id: String) -> R
             row.get::<_, bool>(3)?,      // is_active
             row.get::<_, String>(4)?,    // created_at
             row.get::<_, Option<String>>(5)?, // expires_at
+            row.get::<_, Option<String>>(6)?, // sale folio
+            row.get::<_, Option<i64>>(7)?,    // return folio
         ))
     ).optional().map_err(|e| format!("Error consultando vale: {}", e))?;
     #[allow(unused_variables)] // only for the moment, we don't use expires_at
-    let (code, _initial_balance, current_balance, is_active, created_at, expires_at) =
-        voucher_data.ok_or("No se encontró vale para esta venta".to_string())?;
+    let (code, _initial_balance, current_balance, is_active, created_at, expires_at, sale_folio, return_folio) =
+        voucher_data.ok_or("Vale no encontrado".to_string())?;
 
     if !is_active {
         return Err("El vale ya no está activo".to_string());
     }
 
-    // Fetch sale folio
-    let sale_folio: String = conn
-        .query_row("SELECT folio FROM sales WHERE id = ?1", [&sale_id], |row| {
-            row.get(0)
-        })
-        .map_err(|e| format!("Venta no encontrada: {}", e))?;
-
     // Fetch business settings
     let settings = fetch_business_settings(&conn).unwrap_or_default();
 
@@ -793,90 +1199,22 @@ pub fn print_voucher_from_db(app_handle: tauri::AppHandle, sale_id: String) -> R
 
     let hardware_config = load_settings(app_handle.clone()).unwrap_or_else(|_| Default::default());
 
-    let printers_list = printers::get_printers();
-    let printer_name = match &hardware_config.printer_name {
-        Some(name) if !name.is_empty() && name != "none" => name,
-        _ => return Ok(()),
+    let Some(printer) = open_transport(&hardware_config)? else {
+        return Ok(());
     };
 
-    let printer = printers_list
-        .iter()
-        .find(|p| p.name == *printer_name)
-        .ok_or_else(|| format!("Impresora '{}' no encontrada", printer_name))?;
-
-    let width_val = hardware_config.printer_width.parse::<u32>().unwrap_or(80);
-    let mut builder = ReceiptBuilder::new(width_val);
-
-    builder.init();
-
-    // HEADER
-    print_store_header(&mut builder, &settings, &app_handle);
-
-    builder.add_separator('=');
-
-    // VOUCHER TITLE
-    builder.align_center();
-    builder.set_bold(true);
-    builder.set_size_double_hw();
-    builder.add_text_ln("VALE DE TIENDA");
-    builder.set_size_normal();
-    builder.set_bold(false);
-    builder.add_text("\n");
-
-    // VOUCHER CODE
-    builder.set_bold(true);
-    builder.add_text_ln(&format!("Codigo: {}", code));
-    builder.set_bold(false);
-
-    // BARCODE
-    builder.align_center();
-    if let Ok(barcode_cmds) = generate_barcode_escpos(&code, builder.max_width) {
-        builder.add_image(&barcode_cmds);
-    }
-
-    builder.add_separator('-');
-
-    // BALANCE
-    builder.align_center();
-    builder.set_bold(true);
-    builder.set_size_double_h();
-    builder.add_text_ln(&format!("SALDO: ${:.2}", current_balance));
-    builder.set_size_normal();
-    builder.set_bold(false);
-    builder.add_text("\n");
-
-    builder.add_separator('-');
-
-    // DETAILS
-    builder.align_left();
-    builder.add_text_ln(&format!("Venta original: {}", remove_accents(&sale_folio)));
-    builder.add_text_ln(&format!("Fecha emision: {}", remove_accents(&created_at)));
-
-    // FOR THE MOMENT, THE VOUCHERS DON'T HAVE AN EXPIRATION DATE
-    // if let Some(ref exp) = expires_at {
-    //     builder.add_text_ln(format!("Vigencia: {}", remove_accents(exp)));
-    // } else {
-    //     builder.add_text_ln("Vigencia: Sin fecha de expiracion");
-    // }
-    // builder.add_text("\n");
-
-    // NOTICE
-    builder.align_center();
-    builder.add_text_ln("Este vale es valido para compras");
-    builder.add_text_ln("en tienda. No es canjeable");
-    builder.add_text_ln("por efectivo.");
-
-    // FOOTER
-    print_receipt_footer(&mut builder, &settings);
-
-    builder.cut();
-
-    // Send
-    printer
-        .print(&builder.build(), PrinterJobOptions::none())
-        .map_err(|e| format!("Error imprimiendo vale: {:?}", e))?;
-
-    Ok(())
+    let template = receipt_template(&app_handle, "voucher")?;
+    let data = VoucherReceiptData {
+        code: code.clone(),
+        balance: current_balance,
+        created_at,
+        sale_folio,
+        return_folio,
+    };
+    let context = voucher_template_context(&settings, &data);
+    let job = render_template_job(&app_handle, &template, &context, &settings, &hardware_config, false);
+    submit_job(&app_handle, printer.as_ref(), "voucher", Some(code.as_str()), job)
+        .map_err(|e| format!("Error imprimiendo vale: {}", e))
 }
 
 pub fn print_ticket(
@@ -884,209 +1222,36 @@ pub fn print_ticket(
     data: TicketData,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
-    // --- Refactored Implementation using ReceiptBuilder ---
-
-    let printer_name = match printer_name_opt {
-        Some(name) if !name.is_empty() && name != "none" => name,
-        _ => return Ok(()),
+    let mut transport_config = data.hardware_config.clone();
+    transport_config.printer_name = printer_name_opt.clone();
+    let Some(printer) = open_transport(&transport_config)? else {
+        return Ok(());
     };
 
-    let printers_list = printers::get_printers();
-    let printer = printers_list
-        .iter()
-        .find(|p| p.name == *printer_name)
-        .ok_or_else(|| format!("Impresora '{}' no encontrada", printer_name))?;
-
-    let width_val = data
-        .hardware_config
-        .printer_width
-        .parse::<u32>()
-        .unwrap_or(80);
-    let mut builder = ReceiptBuilder::new(width_val);
-    let settings = &data.business_settings;
-
-    builder.init();
-
-    if data.hardware_config.auto_open_cash_drawer {
-        builder.kick_drawer(&data.hardware_config.cash_drawer_command);
-    }
-
-    // HEADER & STORE INFO
-    print_store_header(&mut builder, settings, &app_handle);
+    let template = receipt_template(&app_handle, "sale")?;
+    let context = sale_template_context(&data);
+    let job = render_template_job(
+        &app_handle,
+        &template,
+        &context,
+        &data.business_settings,
+        &data.hardware_config,
+        true,
+    );
+    submit_job(&app_handle, printer.as_ref(), "sale", Some(data.folio.as_str()), job)
+        .map_err(|e| format!("Error imprimiendo: {}", e))
+}
 
-    // TICKET INFO
-    builder.align_left();
-    builder.add_text_ln(&format!("Folio: {}", data.folio));
-    builder.add_text_ln(&format!("Fecha: {}", data.date));
-    let total_items_count: f64 = data.items.iter().map(|i| i.quantity).sum();
-    builder.add_text_ln(&format!("TOTAL ARTICULOS: {:>10.2}", total_items_count));
-    if let Some(cust) = &data.customer_name {
-        builder.add_text_ln(&format!("Cliente: {}", cust));
-        if let Some(code) = &data.customer_code {
-            builder.add_text_ln(&format!("No. Cliente: {}", code));
-        }
-    }
+pub fn print_payment_from_db(
+    app_handle: tauri::AppHandle,
+    payment_id: String,
+) -> Result<(), String> {
+    use crate::commands::settings::business::fetch_business_settings;
+    use crate::commands::settings::hardware::load_settings;
+    use rusqlite::Connection;
 
-    // ITEMS HEADER
-    let qty_w = 4;
-    let total_w = 9;
-    let sp = 1;
-    let desc_w = builder.max_chars - qty_w - total_w - (sp * 2);
-
-    builder.add_separator('-');
-
-    let header_qty = "CANT";
-    let header_desc = "DESCRIPCION";
-    let header_imp = "IMPORTE";
-
-    let header_line = format!(
-        "{:<w_qty$} {:<w_desc$} {:>w_tot$}",
-        header_qty,
-        header_desc,
-        header_imp,
-        w_qty = qty_w,
-        w_desc = desc_w,
-        w_tot = total_w
-    );
-    // Truncate header if too long
-    if header_line.len() > builder.max_chars + 1 {
-        builder.add_text_ln("CANT DESCRIPCION IMPORTE");
-    } else {
-        builder.add_text_ln(&header_line);
-    }
-
-    builder.add_separator('-');
-
-    // ITEMS LOOP
-    // Group items by promotion_id
-    use std::collections::HashMap;
-    let mut promo_groups: HashMap<Option<String>, Vec<&TicketItem>> = HashMap::new();
-    for item in &data.items {
-        promo_groups
-            .entry(item.promotion_id.clone())
-            .or_insert_with(Vec::new)
-            .push(item);
-    }
-
-    for (promo_id_opt, items_group) in promo_groups {
-        if let Some(_promo_id) = promo_id_opt {
-            // PROMOTION GROUP
-            let promo_name = items_group[0]
-                .promotion_name
-                .as_ref()
-                .map(|s| s.as_str())
-                .unwrap_or("Promocion");
-
-            // Promo header
-            builder.set_bold(true);
-            builder.add_text_ln(&format!("COMBO: {}", remove_accents(promo_name)));
-            builder.set_bold(false);
-
-            // Items in promo
-            let mut promo_total = 0.0;
-            for item in &items_group {
-                let clean_desc = remove_accents(&item.description);
-                let desc_display = if clean_desc.chars().count() > desc_w {
-                    clean_desc.chars().take(desc_w - 2).collect::<String>()
-                } else {
-                    clean_desc
-                };
-
-                let quantity_str = format!("{:<.2}", item.quantity);
-                builder.add_text_ln(&format!("  {}x {}", quantity_str, desc_display));
-                promo_total += item.total;
-            }
-
-            builder.add_text_ln(&format!("  Precio Promo: {:>10.2}", promo_total));
-            builder.add_text("\n");
-        } else {
-            // NORMAL ITEMS - Consolidate
-            let mut consolidated: HashMap<(String, String), (f64, f64)> = HashMap::new();
-
-            for item in &items_group {
-                let key = (item.description.clone(), format!("{:.2}", item.unit_price));
-                let entry = consolidated.entry(key).or_insert((0.0, 0.0));
-                entry.0 += item.quantity;
-                entry.1 += item.total;
-            }
-
-            for ((description, _unit_price_str), (total_qty, total_amount)) in consolidated {
-                let quantity_str = format!("{:<.2}", total_qty);
-                let quantity_display = if quantity_str.len() > qty_w {
-                    &quantity_str[0..qty_w]
-                } else {
-                    &quantity_str
-                };
-
-                let total_str = format!("{:.2}", total_amount);
-
-                let clean_desc = remove_accents(&description);
-                let desc_display = if clean_desc.chars().count() > desc_w {
-                    clean_desc.chars().take(desc_w).collect::<String>()
-                } else {
-                    clean_desc
-                };
-
-                builder.add_text_ln(&format!(
-                    "{:<w_qty$} {:<w_desc$} {:>w_tot$}",
-                    quantity_display,
-                    desc_display,
-                    total_str,
-                    w_qty = qty_w,
-                    w_desc = desc_w,
-                    w_tot = total_w
-                ));
-            }
-        }
-    }
-
-    builder.add_separator('-');
-
-    // TOTALS
-    builder.align_right();
-    builder.add_text_ln(&format!("SUBTOTAL: {:>10.2}", data.subtotal));
-    if data.discount > 0.0 {
-        builder.add_text_ln(&format!("DESCUENTO: {:>10.2}", data.discount));
-    }
-    builder.set_bold(true);
-    builder.add_text_ln(&format!("TOTAL: {:>10.2}", data.total));
-    builder.set_bold(false);
-
-    // Payment Methods
-    if data.cash_amount > 0.0 {
-        builder.add_text_ln(&format!("EFECTIVO: {:>10.2}", data.cash_amount));
-    }
-    if data.card_amount > 0.0 {
-        builder.add_text_ln(&format!("TARJETA: {:>10.2}", data.card_amount));
-    }
-    if data.voucher_amount > 0.0 {
-        builder.add_text_ln(&format!("VALE: {:>10.2}", data.voucher_amount));
-    }
-    builder.add_text_ln(&format!("CAMBIO: {:>10.2}", data.change));
-
-    // FOOTER
-    print_receipt_footer(&mut builder, settings);
-
-    builder.cut();
-
-    // Send
-    printer
-        .print(&builder.build(), PrinterJobOptions::none())
-        .map_err(|e| format!("Error imprimiendo: {:?}", e))?;
-
-    Ok(())
-}
-
-pub fn print_payment_from_db(
-    app_handle: tauri::AppHandle,
-    payment_id: String,
-) -> Result<(), String> {
-    use crate::commands::settings::business::fetch_business_settings;
-    use crate::commands::settings::hardware::load_settings;
-    use rusqlite::Connection;
-
-    let db_state: State<Mutex<Connection>> = app_handle.state();
-    let conn = db_state.lock().map_err(|e| e.to_string())?;
+    let db_state: State<Mutex<Connection>> = app_handle.state();
+    let conn = db_state.lock().map_err(|e| e.to_string())?;
 
     // Fetch payment data with customer info
     let payment = conn
@@ -1139,17 +1304,106 @@ pub fn print_payment_from_db(
 
     let hardware_config = load_settings(app_handle.clone()).unwrap_or_else(|_| Default::default());
 
-    let printers_list = printers::get_printers();
+    let Some(printer) = open_transport(&hardware_config)? else {
+        return Ok(());
+    };
 
-    let printer_name = match &hardware_config.printer_name {
-        Some(name) if !name.is_empty() && name != "none" => name,
-        _ => return Ok(()),
+    let template = receipt_template(&app_handle, "payment")?;
+    let data = PaymentReceiptData {
+        folio: folio.clone(),
+        amount,
+        cash_amount,
+        card_amount,
+        payment_method,
+        payment_date,
+        customer_name,
+        customer_code,
     };
+    let context = payment_template_context(&settings, &data);
+    let job = render_template_job(&app_handle, &template, &context, &settings, &hardware_config, true);
+    submit_job(&app_handle, printer.as_ref(), "payment", Some(folio.as_str()), job)
+        .map_err(|e| format!("Error imprimiendo comprobante: {}", e))
+}
 
-    let printer = printers_list
-        .iter()
-        .find(|p| p.name == *printer_name)
-        .ok_or_else(|| format!("Impresora '{}' no encontrada", printer_name))?;
+/// Prints a single ticket for an exchange: returned items, replacement items and the difference.
+pub fn print_exchange_from_db(
+    app_handle: tauri::AppHandle,
+    return_id: String,
+) -> Result<(), String> {
+    use crate::commands::settings::business::fetch_business_settings;
+    use crate::commands::settings::hardware::load_settings;
+    use rusqlite::Connection;
+
+    let db_state: State<Mutex<Connection>> = app_handle.state();
+    let conn = db_state.lock().map_err(|e| e.to_string())?;
+
+    let (return_total, refund_method, exchange_amount, original_folio, sale_id): (
+        f64,
+        String,
+        f64,
+        String,
+        String,
+    ) = conn
+        .query_row(
+            "SELECT r.total, r.refund_method, r.exchange_amount, s.folio, r.exchange_sale_id
+             FROM returns r
+             JOIN sales s ON s.id = r.sale_id
+             WHERE r.id = ?1 AND r.exchange_sale_id IS NOT NULL",
+            [&return_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .map_err(|e| format!("Cambio no encontrado: {}", e))?;
+
+    let (folio, date_str, sale_total): (String, String, f64) = conn
+        .query_row(
+            "SELECT folio, created_at, total FROM sales WHERE id = ?1",
+            [&sale_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| format!("Venta no encontrada: {}", e))?;
+
+    let payments = fetch_sale_payments(&conn, &sale_id)?;
+
+    let mut returned_items: Vec<(f64, String, f64)> = Vec::new();
+    {
+        let mut stmt = conn
+            .prepare(
+                "SELECT ri.quantity, si.product_name, ri.subtotal
+                 FROM return_items ri
+                 JOIN sale_items si ON si.id = ri.sale_item_id
+                 WHERE ri.return_id = ?1",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([&return_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| e.to_string())?;
+        for r in rows {
+            returned_items.push(r.map_err(|e| e.to_string())?);
+        }
+    }
+
+    let mut new_items: Vec<(f64, String, f64)> = Vec::new();
+    {
+        let mut stmt = conn
+            .prepare("SELECT quantity, product_name, total FROM sale_items WHERE sale_id = ?1")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([&sale_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| e.to_string())?;
+        for r in rows {
+            new_items.push(r.map_err(|e| e.to_string())?);
+        }
+    }
+
+    let settings = fetch_business_settings(&conn).unwrap_or_default();
+
+    drop(conn); // Unlock DB before printing
+
+    let hardware_config = load_settings(app_handle.clone()).unwrap_or_else(|_| Default::default());
+
+    let Some(printer) = open_transport(&hardware_config)? else {
+        return Ok(());
+    };
 
     let width_val = hardware_config.printer_width.parse::<u32>().unwrap_or(80);
     let mut builder = ReceiptBuilder::new(width_val);
@@ -1163,59 +1417,77 @@ pub fn print_payment_from_db(
     // HEADER
     print_store_header(&mut builder, &settings, &app_handle);
 
-    builder.add_separator('-');
-
     // TITLE
     builder.align_center();
     builder.set_bold(true);
-    builder.set_size_double_hw();
-    builder.add_text_ln("COMPROBANTE DE ABONO");
+    builder.set_size_double_h();
+    builder.add_text_ln("CAMBIO DE MERCANCIA");
     builder.set_size_normal();
     builder.set_bold(false);
-    builder.add_text("\n");
 
-    // FOLIO
-    builder.align_center();
+    builder.align_left();
+    builder.add_text_ln(&format!("Folio: {}", folio));
+    builder.add_text_ln(&format!("Fecha: {}", date_str));
+    builder.add_text_ln(&format!("Venta original: {}", original_folio));
+
+    let qty_w = 4;
+    let total_w = 9;
+    let desc_w = builder.max_chars - qty_w - total_w - 2;
+    let print_lines = |builder: &mut ReceiptBuilder, items: &[(f64, String, f64)], sign: &str| {
+        for (qty, description, amount) in items {
+            let clean_desc = remove_accents(description);
+            let desc_display: String = clean_desc.chars().take(desc_w).collect();
+            builder.add_text_ln(&format!(
+                "{:<w_qty$} {:<w_desc$} {:>w_tot$}",
+                format!("{:.2}", qty).chars().take(qty_w).collect::<String>(),
+                desc_display,
+                format!("{}{:.2}", sign, amount),
+                w_qty = qty_w,
+                w_desc = desc_w,
+                w_tot = total_w
+            ));
+        }
+    };
+
+    // RETURNED ITEMS
+    builder.add_separator('-');
     builder.set_bold(true);
-    builder.add_text_ln(&format!("Folio: {}", remove_accents(&folio)));
+    builder.add_text_ln("DEVUELVE");
     builder.set_bold(false);
-    builder.add_text("\n");
+    print_lines(&mut builder, &returned_items, "-");
 
+    // NEW ITEMS
     builder.add_separator('-');
-
-    // AMOUNT
-    builder.align_center();
     builder.set_bold(true);
-    builder.set_size_double_hw();
-    builder.add_text_ln(&format!("${:.2}", amount));
-    builder.set_size_normal();
+    builder.add_text_ln("LLEVA");
     builder.set_bold(false);
-    builder.add_text("\n");
+    print_lines(&mut builder, &new_items, "");
 
     builder.add_separator('-');
 
-    // DETAILS
-    builder.align_left();
-    builder.add_text_ln(&format!("Fecha: {}", remove_accents(&payment_date)));
-    builder.add_text_ln(&format!("No. Cliente: {}", remove_accents(&customer_code)));
-    builder.add_text_ln(&format!("Cliente: {}", remove_accents(&customer_name)));
-
-    let method_label = match payment_method.as_str() {
-        "cash" => "Efectivo",
-        "card" => "Tarjeta / Transferencia",
-        "mixed" => "Mixto (Efectivo + Tarjeta)",
-        "transfer" => "Transferencia",
-        _ => &payment_method,
-    };
-    builder.add_text_ln(&format!("Metodo: {}", method_label));
-    builder.add_text("\n");
-
-    // Payment breakdown
-    if cash_amount > 0.0 {
-        builder.add_text_ln(&format!("  Efectivo: ${:.2}", cash_amount));
+    // TOTALS
+    builder.align_right();
+    builder.add_text_ln(&format!("DEVUELTO: {:>10.2}", return_total));
+    builder.add_text_ln(&format!("NUEVA VENTA: {:>10.2}", sale_total));
+    builder.set_bold(true);
+    let amount_due = sale_total - exchange_amount;
+    let refund_total = return_total - exchange_amount;
+    if refund_total > 0.001 {
+        let method_label = match refund_method.as_str() {
+            "cash" => "EFECTIVO",
+            "card" => "TARJETA",
+            "credit" => "CREDITO",
+            _ => "VALE",
+        };
+        builder.add_text_ln(&format!("A FAVOR ({}): {:>10.2}", method_label, refund_total));
+    } else {
+        builder.add_text_ln(&format!("DIFERENCIA: {:>10.2}", amount_due));
     }
-    if card_amount > 0.0 {
-        builder.add_text_ln(&format!("  Tarjeta:  ${:.2}", card_amount));
+    builder.set_bold(false);
+
+    if amount_due > 0.001 {
+        let change = print_payment_lines(&mut builder, &payments);
+        builder.add_text_ln(&format!("CAMBIO: {:>10.2}", change));
     }
 
     // FOOTER
@@ -1224,14 +1496,13 @@ pub fn print_payment_from_db(
     builder.cut();
 
     // Send
-    printer
-        .print(&builder.build(), PrinterJobOptions::none())
-        .map_err(|e| format!("Error imprimiendo comprobante: {:?}", e))?;
+    submit_job(&app_handle, printer.as_ref(), "exchange", Some(folio.as_str()), builder.build())
+        .map_err(|e| format!("Error imprimiendo cambio: {}", e))?;
 
     Ok(())
 }
 
-fn remove_accents(s: &str) -> String {
+pub(crate) fn remove_accents(s: &str) -> String {
     // Remove accents from string for alignment purposes
     s.chars()
         .map(|c| match c {
@@ -1269,291 +1540,176 @@ pub fn print_shift_summary(
         return Err("Solo se pueden imprimir turnos que ya estén cerrados".to_string());
     }
 
-    let mut movements_in = Vec::new();
-    let mut movements_out = Vec::new();
-    for m in &details.movements {
-        if m.type_ == "IN" {
-            movements_in.push((m.amount, m.concept.clone(), m.description.clone()));
-        } else {
-            movements_out.push((m.amount, m.concept.clone(), m.description.clone()));
-        }
-    }
-
     let settings = fetch_business_settings(&conn).unwrap_or_default();
 
     drop(conn);
 
     let hardware_config = load_settings(app_handle.clone()).unwrap_or_else(|_| Default::default());
 
-    let printers_list = printers::get_printers();
-    let printer_name = match &hardware_config.printer_name {
-        Some(name) if !name.is_empty() && name != "none" => name,
-        _ => return Ok(()),
+    let Some(printer) = open_transport(&hardware_config)? else {
+        return Ok(());
     };
 
-    let printer = printers_list
-        .iter()
-        .find(|p| p.name == *printer_name)
-        .ok_or_else(|| format!("Impresora '{}' no encontrada", printer_name))?;
+    let reference = shift.code.clone().unwrap_or_else(|| shift.id.to_string());
+    let template = receipt_template(&app_handle, "shift")?;
+    let context = shift_template_context(&settings, &details);
+    let job = render_template_job(&app_handle, &template, &context, &settings, &hardware_config, true);
+    submit_job(&app_handle, printer.as_ref(), "shift", Some(reference.as_str()), job)
+        .map_err(|e| format!("Error imprimiendo corte de caja: {}", e))
+}
+
+/// Prints an X or Z daily report. `reprint` marks copies of an issued Z report.
+pub fn print_daily_report(
+    app_handle: tauri::AppHandle,
+    report: &DailyReport,
+    reprint: bool,
+) -> Result<(), String> {
+    use crate::commands::settings::business::fetch_business_settings;
+    use crate::commands::settings::hardware::load_settings;
+    use rusqlite::Connection;
+
+    let settings = {
+        let db_state: State<Mutex<Connection>> = app_handle.state();
+        let conn = db_state.lock().map_err(|e| e.to_string())?;
+        fetch_business_settings(&conn).unwrap_or_default()
+    };
+
+    let hardware_config = load_settings(app_handle.clone()).unwrap_or_else(|_| Default::default());
+
+    let printer = open_transport(&hardware_config)?
+        .ok_or("No hay una impresora configurada".to_string())?;
 
     let width_val = hardware_config.printer_width.parse::<u32>().unwrap_or(80);
     let mut builder = ReceiptBuilder::new(width_val);
 
-    // BUILD TICKET
     builder.init();
-
-    if hardware_config.auto_open_cash_drawer {
-        builder.kick_drawer(&hardware_config.cash_drawer_command);
-    }
     print_store_header(&mut builder, &settings, &app_handle);
 
     builder.align_center();
     builder.set_bold(true);
     builder.set_size_double_h();
-    builder.add_text_ln("CORTE DE CAJA");
+    match report.report_number {
+        Some(number) if report.report_type == "Z" => {
+            builder.add_text_ln(&format!("REPORTE Z #{:06}", number))
+        }
+        _ => builder.add_text_ln("REPORTE X"),
+    }
     builder.set_size_normal();
+    if reprint {
+        builder.add_text_ln("*** REIMPRESION ***");
+    } else if report.report_type == "X" {
+        builder.add_text_ln("LECTURA PARCIAL - NO ES CIERRE");
+    }
     builder.set_bold(false);
     let now = chrono::Local::now().format("%d/%m/%Y %H:%M").to_string();
     builder.add_text_ln(&format!("Impreso: {}", now));
     builder.add_text("\n");
 
     builder.align_left();
-    builder.add_text_ln(&format!("Folio: {}", shift.code.as_deref().unwrap_or("-")));
-    builder.add_text_ln(&format!("Apertura: {}", shift.opening_date));
-    if let Some(c_date) = &shift.closing_date {
-        builder.add_text_ln(&format!("Cierre: {}", c_date));
+    builder.add_text_ln(&format!("Fecha del dia: {}", report.business_date));
+    builder.add_text_ln(&format!("Generado: {}", report.generated_at));
+    builder.add_text_ln(&format!("Por: {}", remove_accents(&report.generated_by)));
+    builder.add_text_ln(&format!("Turnos: {}", report.shift_codes.join(", ")));
+    if report.open_shifts > 0 {
+        builder.add_text_ln(&format!("Turnos abiertos: {}", report.open_shifts));
     }
     builder.add_text_ln(&format!(
-        "Abre: {}",
-        shift.opening_user_name.as_deref().unwrap_or("Desc.")
+        "Folio inicial: {}",
+        report.first_folio.as_deref().unwrap_or("-")
     ));
     builder.add_text_ln(&format!(
-        "Cierra: {}",
-        shift.closing_user_name.as_deref().unwrap_or("Desc.")
+        "Folio final: {}",
+        report.last_folio.as_deref().unwrap_or("-")
     ));
+    builder.add_separator('-');
 
-    // SALES SUMMARY
-    let has_sales = details.total_sales > 0.0;
-    if has_sales {
-        builder.align_center();
-        builder.set_bold(true);
-        builder.add_text_ln("TOTAL DE VENTAS");
-        builder.set_bold(false);
-        builder.align_left();
-        builder.add_text_ln(&format!("Cantidad de ventas: {:.0}", details.sales_count));
-        builder.add_separator('-');
-
-        builder.align_center();
-        builder.set_bold(true);
-        builder.add_text_ln("RESUMEN DE VENTAS");
-        builder.set_bold(false);
-        builder.align_left();
-
-        if details.total_cash_sales > 0.0 {
-            builder.add_row_with_dots(
-                "Ventas Efectivo:",
-                &format!("${:.2}", details.total_cash_sales),
-            );
-        }
-        if details.total_card_sales > 0.0 {
-            builder.add_row_with_dots(
-                "Ventas Tarjeta:",
-                &format!("${:.2}", details.total_card_sales),
-            );
-        }
-        if details.total_credit_sales > 0.0 {
-            builder.add_row_with_dots(
-                "Ventas Credito:",
-                &format!("${:.2}", details.total_credit_sales),
-            );
-        }
-        if details.total_voucher_sales > 0.0 {
-            builder.add_row_with_dots(
-                "Ventas Cupones:",
-                &format!("${:.2}", details.total_voucher_sales),
-            );
-        }
-        builder.set_bold(true);
-        builder.add_row_with_dots("Total Ventas:", &format!("${:.2}", details.total_sales));
-        builder.set_bold(false);
-        builder.add_separator('-');
+    // SALES
+    builder.align_center();
+    builder.set_bold(true);
+    builder.add_text_ln("VENTAS");
+    builder.set_bold(false);
+    builder.align_left();
+    builder.add_row_with_dots("Cantidad de ventas:", &report.sales_count.to_string());
+    builder.add_row_with_dots("Venta bruta:", &format!("${:.2}", report.gross_sales));
+    builder.add_row_with_dots("Descuentos:", &format!("-${:.2}", report.total_discounts));
+    builder.set_bold(true);
+    builder.add_row_with_dots("Total Ventas:", &format!("${:.2}", report.total_sales));
+    builder.set_bold(false);
+    if report.tax_rate > 0.0 {
+        builder.add_row_with_dots("Base gravable:", &format!("${:.2}", report.tax_base));
+        builder.add_row_with_dots(
+            &format!("IVA {:.2}%:", report.tax_rate),
+            &format!("${:.2}", report.tax_amount),
+        );
     }
+    builder.add_separator('-');
 
-    // DEBT PAYMENTS
-    let has_debt_payments = details.total_debt_payments > 0.0;
-    if has_debt_payments {
+    // TENDERS
+    if !report.payments.is_empty() {
         builder.align_center();
         builder.set_bold(true);
-        builder.add_text_ln("ABONOS A DEUDAS");
+        builder.add_text_ln("FORMAS DE PAGO");
         builder.set_bold(false);
         builder.align_left();
-
-        if details.debt_payments_cash > 0.0 {
+        for payment in &report.payments {
             builder.add_row_with_dots(
-                "Abonos Efectivo:",
-                &format!("${:.2}", details.debt_payments_cash),
+                &format!("{} ({}):", payment.label, payment.sales_count),
+                &format!("${:.2}", payment.amount),
             );
         }
-        if details.debt_payments_card > 0.0 {
+        for foreign in &report.foreign_cash {
             builder.add_row_with_dots(
-                "Abonos Tarjeta:",
-                &format!("${:.2}", details.debt_payments_card),
+                &format!("Cobrado en {}:", foreign.currency_code),
+                &format!("{:.2}", foreign.amount),
             );
         }
-        builder.set_bold(true);
-        builder.add_row_with_dots(
-            "Total Abonos:",
-            &format!("${:.2}", details.total_debt_payments),
-        );
-        builder.set_bold(false);
-    }
-
-    // CASH MOVEMENTS (DETAILED)
-    if !movements_in.is_empty() {
         builder.add_separator('-');
-        builder.align_center();
-        builder.set_bold(true);
-        builder.add_text_ln("ENTRADAS DE EFECTIVO");
-        builder.set_bold(false);
-        builder.align_left();
-
-        for (amt, concept, desc) in &movements_in {
-            builder.add_row_with_dots(concept, &format!("${:.2}", amt));
-
-            if let Some(d) = desc {
-                if !d.trim().is_empty() {
-                    builder.add_text_ln(&format!("  Nota: {}", remove_accents(d.trim())));
-                }
-            }
-        }
-        builder.set_bold(true);
-        builder.add_row_with_dots(
-            "Total Entradas:",
-            &format!("${:.2}", details.total_movements_in),
-        );
-        builder.set_bold(false);
     }
 
-    if !movements_out.is_empty() {
-        builder.add_separator('-');
-        builder.align_center();
-        builder.set_bold(true);
-        builder.add_text_ln("SALIDAS DE EFECTIVO");
-        builder.set_bold(false);
-        builder.align_left();
-
-        for (amt, concept, desc) in &movements_out {
-            builder.add_row_with_dots(concept, &format!("${:.2}", amt));
-
-            if let Some(d) = desc {
-                if !d.trim().is_empty() {
-                    builder.add_text_ln(&format!("  Nota: {}", remove_accents(d.trim())));
-                }
-            }
-        }
-        builder.set_bold(true);
+    // ADJUSTMENTS
+    builder.align_center();
+    builder.set_bold(true);
+    builder.add_text_ln("CANCELACIONES Y DEVOLUCIONES");
+    builder.set_bold(false);
+    builder.align_left();
+    builder.add_row_with_dots(
+        &format!("Cancelaciones ({}):", report.cancellations_count),
+        &format!("${:.2}", report.cancellations_total),
+    );
+    if report.reversals_count > 0 {
         builder.add_row_with_dots(
-            "Total Salidas:",
-            &format!("${:.2}", details.total_movements_out),
+            &format!("Cancel. Turnos Ant. ({}):", report.reversals_count),
+            &format!("-${:.2}", report.reversals_total),
         );
-        builder.set_bold(false);
-        builder.add_separator('-');
     }
-
-    // CASH RECONCILIATION
-    if details.total_cash_sales > 0.0
-        || details.debt_payments_cash > 0.0
-        || details.total_movements_in > 0.0
-        || details.total_movements_out > 0.0
-    {
-        builder.align_center();
-        builder.set_bold(true);
-        builder.add_text_ln("TOTAL EFECTIVO");
-        builder.set_bold(false);
-        builder.align_left();
-
-        builder.add_row_with_dots("Fondo Inicial:", &format!("+${:.2}", shift.initial_cash));
-        builder.add_row_with_dots(
-            "Ventas Efectivo:",
-            &format!("+${:.2}", details.total_cash_sales),
-        );
-        builder.add_row_with_dots(
-            "Abonos Efectivo:",
-            &format!("+${:.2}", details.debt_payments_cash),
-        );
-        builder.add_row_with_dots(
-            "Entradas Efectivo:",
-            &format!("+${:.2}", details.total_movements_in),
-        );
-        builder.add_row_with_dots(
-            "Salidas Efectivo:",
-            &format!("-${:.2}", details.total_movements_out),
-        );
-        builder.set_bold(true);
-        builder.add_row_with_dots("Total Efectivo:", &format!("${:.2}", details.total_cash));
-        builder.set_bold(false);
-        builder.set_bold(true);
-        builder.add_separator('-');
-        if let Some(cw) = shift.cash_withdrawal {
-            builder.add_row_with_dots("Monto a Retirar:", &format!("${:.2}", cw));
-        }
-        builder.set_bold(false);
+    builder.add_row_with_dots(
+        &format!("Devoluciones ({}):", report.returns_count),
+        &format!("-${:.2}", report.returns_total),
+    );
+    if report.cash_refunds > 0.0 {
+        builder.add_row_with_dots("  Reemb. Efectivo:", &format!("${:.2}", report.cash_refunds));
     }
-    // CARD INFORMATION
-    if details.total_card_sales > 0.0 || details.debt_payments_card > 0.0 {
-        builder.add_separator('-');
-        builder.align_center();
-        builder.set_bold(true);
-        builder.add_text_ln("TOTAL TARJETA");
-        builder.set_bold(false);
-        builder.align_left();
-        builder.add_row_with_dots(
-            "Ventas Tarjeta:",
-            &format!("+${:.2}", details.total_card_sales),
-        );
-        builder.add_row_with_dots(
-            "Abonos Tarjeta:",
-            &format!("+${:.2}", details.debt_payments_card),
-        );
-        builder.set_bold(true);
-        builder.add_row_with_dots(
-            "Total Tarjeta:",
-            &format!(
-                "${:.2}",
-                details.total_card_sales + details.debt_payments_card
-            ),
-        );
-        builder.set_bold(false);
+    if report.card_refunds > 0.0 {
+        builder.add_row_with_dots("  Reemb. Tarjeta:", &format!("${:.2}", report.card_refunds));
     }
+    builder.add_separator('-');
+
+    // CASH
+    builder.add_row_with_dots(
+        "Abonos a deudas:",
+        &format!("${:.2}", report.debt_payments_total),
+    );
+    builder.add_row_with_dots("Entradas de caja:", &format!("${:.2}", report.movements_in));
+    builder.add_row_with_dots("Salidas de caja:", &format!("${:.2}", report.movements_out));
 
-    if let Some(n) = &shift.notes {
-        if !n.trim().is_empty() {
-            builder.add_separator('-');
-            builder.align_left();
-            builder.set_bold(true);
-            builder.add_text_ln("NOTAS DEL CIERRE:");
-            builder.set_bold(false);
-            let mut line = String::new();
-            for word in n.split_whitespace() {
-                if line.len() + word.len() + 1 > builder.max_chars {
-                    builder.add_text_ln(&line);
-                    line.clear();
-                }
-                if !line.is_empty() {
-                    line.push(' ');
-                }
-                line.push_str(word);
-            }
-            if !line.is_empty() {
-                builder.add_text_ln(&line);
-            }
-        }
-    }
     builder.cut();
 
-    printer
-        .print(&builder.build(), PrinterJobOptions::none())
-        .map_err(|e| format!("Error imprimiendo corte de caja: {:?}", e))?;
+    let reference = match report.report_number {
+        Some(number) => format!("Z-{}", number),
+        None => format!("{}-{}", report.report_type, report.business_date),
+    };
+    submit_job(&app_handle, printer.as_ref(), "daily_report", Some(reference.as_str()), builder.build())
+        .map_err(|e| format!("Error imprimiendo reporte: {}", e))?;
 
     Ok(())
 }
@@ -1581,22 +1737,15 @@ pub fn kick_drawer_direct(app_handle: &tauri::AppHandle, ignore_toggle: bool) ->
         return Ok(());
     }
 
-    let printer_name = match &config.printer_name {
-        Some(name) if !name.is_empty() && name != "none" => name,
-        _ => return Ok(()),
+    let Some(printer) = open_transport(&config)? else {
+        return Ok(());
     };
 
-    let printers_list = printers::get_printers();
-    let printer = printers_list
-        .iter()
-        .find(|p| p.name == *printer_name)
-        .ok_or_else(|| format!("Impresora '{}' no encontrada", printer_name))?;
-
     let bytes = hex_to_bytes(&config.cash_drawer_command)?;
 
     printer
-        .print(&bytes, PrinterJobOptions::none())
-        .map_err(|e| format!("Error al abrir cajón: {:?}", e))?;
+        .send(&bytes)
+        .map_err(|e| format!("Error al abrir cajón: {}", e))?;
 
     Ok(())
 }
@@ -0,0 +1,547 @@
+// Digital receipts.
+//
+// A receipt template is rendered into a list of lines and images (the same
+// layout the printer gets) and then written as a PNG or a PDF page with the
+// width of the paper roll. Logos, barcodes and QR codes come from the ESC/POS
+// raster helpers and are decoded back into bitmaps, so the file matches what
+// the printer would print.
+
+use std::io::Write;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use font8x8::{UnicodeFonts, BASIC_FONTS, LATIN_FONTS};
+use image::{imageops, GrayImage, Luma};
+use tauri::Manager;
+
+use crate::printer_utils::{self, QrOptions, ReceiptBuilder};
+use crate::receipt_template::{Align, ReceiptSink, TextSize};
+
+/// Pixels per character cell in PNG output (8x8 glyphs drawn at 2x)
+const PNG_SCALE: u32 = 2;
+const PNG_CELL_W: u32 = 8 * PNG_SCALE;
+const PNG_LINE_H: u32 = 12 * PNG_SCALE;
+const PNG_MARGIN: u32 = 24;
+
+const PT_PER_MM: f64 = 72.0 / 25.4;
+const PDF_MARGIN_MM: f64 = 4.0;
+/// Courier advance width relative to the font size
+const COURIER_ADVANCE: f64 = 0.6;
+
+enum Element {
+    Text {
+        text: String,
+        align: Align,
+        bold: bool,
+        size: TextSize,
+    },
+    Image {
+        image: GrayImage,
+        align: Align,
+    },
+    Space(usize),
+}
+
+/// Collects the rendered receipt for the PNG and PDF writers.
+pub struct DocumentSink {
+    max_chars: usize,
+    /// Printable width in printer dots, used by the raster helpers
+    max_width: u32,
+    align: Align,
+    bold: bool,
+    size: TextSize,
+    logo: Option<Vec<u8>>,
+    qr_options: QrOptions,
+    elements: Vec<Element>,
+}
+
+impl DocumentSink {
+    pub fn new(printer_width_mm: u32, logo: Option<Vec<u8>>, qr_options: QrOptions) -> Self {
+        let builder = ReceiptBuilder::new(printer_width_mm);
+        Self {
+            max_chars: builder.max_chars,
+            max_width: builder.max_width,
+            align: Align::Left,
+            bold: false,
+            size: TextSize::Normal,
+            logo,
+            qr_options,
+            elements: Vec::new(),
+        }
+    }
+
+    fn push_raster(&mut self, raster: Result<Vec<u8>, String>) {
+        if let Some(image) = raster.ok().and_then(|cmds| decode_raster(&cmds)) {
+            self.elements.push(Element::Image {
+                image,
+                align: self.align,
+            });
+        }
+    }
+}
+
+impl ReceiptSink for DocumentSink {
+    fn max_chars(&self) -> usize {
+        self.max_chars
+    }
+
+    fn align(&mut self, align: Align) {
+        self.align = align;
+    }
+
+    fn bold(&mut self, on: bool) {
+        self.bold = on;
+    }
+
+    fn size(&mut self, size: TextSize) {
+        self.size = size;
+    }
+
+    fn line(&mut self, text: &str) {
+        for line in text.split('\n') {
+            self.elements.push(Element::Text {
+                text: line.to_string(),
+                align: self.align,
+                bold: self.bold,
+                size: self.size,
+            });
+        }
+    }
+
+    fn logo(&mut self) {
+        if let Some(logo) = self.logo.clone() {
+            self.push_raster(Ok(logo));
+        }
+    }
+
+    fn barcode(&mut self, data: &str) {
+        let raster = printer_utils::generate_barcode_escpos(data, self.max_width);
+        self.push_raster(raster);
+    }
+
+    fn qr(&mut self, data: &str) {
+        let raster = printer_utils::generate_qr_escpos(data, &self.qr_options, self.max_width);
+        self.push_raster(raster);
+    }
+
+    fn feed(&mut self, lines: usize) {
+        self.elements.push(Element::Space(lines));
+    }
+
+    fn cut(&mut self) {}
+}
+
+/// Decodes `GS v 0` raster blocks (as produced by `convert_image_to_escpos`)
+/// into one bitmap, stacking the blocks vertically.
+fn decode_raster(cmds: &[u8]) -> Option<GrayImage> {
+    let mut rows: Vec<Vec<u8>> = Vec::new();
+    let mut width_bytes = 0usize;
+    let mut i = 0;
+
+    while i + 8 <= cmds.len() {
+        if cmds[i..i + 3] != [0x1D, 0x76, 0x30] {
+            i += 1;
+            continue;
+        }
+        let x = cmds[i + 4] as usize + cmds[i + 5] as usize * 256;
+        let y = cmds[i + 6] as usize + cmds[i + 7] as usize * 256;
+        let start = i + 8;
+        let end = start + x * y;
+        if x == 0 || end > cmds.len() {
+            break;
+        }
+        width_bytes = width_bytes.max(x);
+        rows.extend(cmds[start..end].chunks(x).map(|row| row.to_vec()));
+        i = end;
+    }
+
+    if rows.is_empty() {
+        return None;
+    }
+
+    let mut image = GrayImage::from_pixel(width_bytes as u32 * 8, rows.len() as u32, Luma([255]));
+    for (y, row) in rows.iter().enumerate() {
+        for (byte_index, byte) in row.iter().enumerate() {
+            for bit in 0..8 {
+                if byte & (0x80 >> bit) != 0 {
+                    image.put_pixel((byte_index * 8 + bit) as u32, y as u32, Luma([0]));
+                }
+            }
+        }
+    }
+    Some(image)
+}
+
+fn text_scale(size: TextSize) -> (u32, u32) {
+    match size {
+        TextSize::Normal => (1, 1),
+        TextSize::Tall => (1, 2),
+        TextSize::Double => (2, 2),
+    }
+}
+
+fn glyph(c: char) -> [u8; 8] {
+    BASIC_FONTS
+        .get(c)
+        .or_else(|| LATIN_FONTS.get(c))
+        .or_else(|| BASIC_FONTS.get('?'))
+        .unwrap_or([0; 8])
+}
+
+// --- PNG ---
+
+impl DocumentSink {
+    /// Width in pixels of the printable area in PNG output.
+    fn png_content_width(&self) -> u32 {
+        self.max_chars as u32 * PNG_CELL_W
+    }
+
+    /// Printer-dot images scaled to the PNG text width.
+    fn png_image(&self, image: &GrayImage) -> GrayImage {
+        let ratio = self.png_content_width() as f64 / self.max_width as f64;
+        let width = ((image.width() as f64 * ratio) as u32).max(1);
+        let height = ((image.height() as f64 * ratio) as u32).max(1);
+        imageops::resize(image, width, height, imageops::FilterType::Nearest)
+    }
+
+    pub fn to_png(&self) -> GrayImage {
+        let content_width = self.png_content_width();
+        let scaled: Vec<Option<GrayImage>> = self
+            .elements
+            .iter()
+            .map(|element| match element {
+                Element::Image { image, .. } => Some(self.png_image(image)),
+                _ => None,
+            })
+            .collect();
+
+        let height: u32 = self
+            .elements
+            .iter()
+            .zip(&scaled)
+            .map(|(element, image)| match element {
+                Element::Text { size, .. } => PNG_LINE_H * text_scale(*size).1,
+                Element::Image { .. } => image.as_ref().map(|i| i.height()).unwrap_or(0) + PNG_LINE_H / 2,
+                Element::Space(lines) => PNG_LINE_H * *lines as u32,
+            })
+            .sum();
+
+        let mut canvas = GrayImage::from_pixel(
+            content_width + PNG_MARGIN * 2,
+            height + PNG_MARGIN * 2,
+            Luma([255]),
+        );
+        let mut y = PNG_MARGIN;
+
+        for (element, image) in self.elements.iter().zip(&scaled) {
+            match element {
+                Element::Text {
+                    text,
+                    align,
+                    bold,
+                    size,
+                } => {
+                    let (sx, sy) = text_scale(*size);
+                    let chars: Vec<char> = text.chars().collect();
+                    let text_width = chars.len() as u32 * PNG_CELL_W * sx;
+                    let x0 = PNG_MARGIN + offset(*align, content_width, text_width);
+
+                    for (index, c) in chars.iter().enumerate() {
+                        let cell_x = x0 + index as u32 * PNG_CELL_W * sx;
+                        draw_glyph(&mut canvas, glyph(*c), cell_x, y + 2 * PNG_SCALE * sy, sx, sy, *bold);
+                    }
+                    y += PNG_LINE_H * sy;
+                }
+                Element::Image { align, .. } => {
+                    if let Some(image) = image {
+                        let x = PNG_MARGIN + offset(*align, content_width, image.width());
+                        imageops::overlay(&mut canvas, image, x as i64, y as i64);
+                        y += image.height();
+                    }
+                    y += PNG_LINE_H / 2;
+                }
+                Element::Space(lines) => y += PNG_LINE_H * *lines as u32,
+            }
+        }
+        canvas
+    }
+}
+
+fn offset(align: Align, available: u32, used: u32) -> u32 {
+    match align {
+        Align::Left => 0,
+        Align::Center => available.saturating_sub(used) / 2,
+        Align::Right => available.saturating_sub(used),
+    }
+}
+
+fn draw_glyph(canvas: &mut GrayImage, rows: [u8; 8], x0: u32, y0: u32, sx: u32, sy: u32, bold: bool) {
+    let px_w = PNG_SCALE * sx;
+    let px_h = PNG_SCALE * sy;
+    for (row_index, row) in rows.iter().enumerate() {
+        for col in 0..8u32 {
+            if row & (1 << col) == 0 {
+                continue;
+            }
+            // Bold: smear one pixel to the right
+            let extra = if bold { 1 } else { 0 };
+            for dy in 0..px_h {
+                for dx in 0..px_w + extra {
+                    let x = x0 + col * px_w + dx;
+                    let y = y0 + row_index as u32 * px_h + dy;
+                    if x < canvas.width() && y < canvas.height() {
+                        canvas.put_pixel(x, y, Luma([0]));
+                    }
+                }
+            }
+        }
+    }
+}
+
+// --- PDF ---
+
+/// Characters Courier can show with WinAnsiEncoding; anything else becomes '?'.
+fn pdf_string(text: &str) -> Vec<u8> {
+    let mut out = vec![b'('];
+    for c in text.chars() {
+        let code = c as u32;
+        let byte = if (0x20..0x7F).contains(&code) || (0xA0..=0xFF).contains(&code) {
+            code as u8
+        } else {
+            b'?'
+        };
+        if matches!(byte, b'(' | b')' | b'\\') {
+            out.push(b'\\');
+        }
+        out.push(byte);
+    }
+    out.push(b')');
+    out
+}
+
+impl DocumentSink {
+    pub fn to_pdf(&self, printer_width_mm: u32) -> Vec<u8> {
+        let page_width = printer_width_mm as f64 * PT_PER_MM;
+        let margin = PDF_MARGIN_MM * PT_PER_MM;
+        let content_width = page_width - margin * 2.0;
+        let font_size = content_width / (self.max_chars as f64 * COURIER_ADVANCE);
+        let line_height = font_size * 1.25;
+        let dot = content_width / self.max_width as f64;
+
+        let height: f64 = self
+            .elements
+            .iter()
+            .map(|element| match element {
+                Element::Text { size, .. } => line_height * text_scale(*size).1 as f64,
+                Element::Image { image, .. } => image.height() as f64 * dot + line_height / 2.0,
+                Element::Space(lines) => line_height * *lines as f64,
+            })
+            .sum();
+        let page_height = height + margin * 2.0;
+
+        let mut content: Vec<u8> = Vec::new();
+        let mut images: Vec<&GrayImage> = Vec::new();
+        let mut y = page_height - margin;
+
+        for element in &self.elements {
+            match element {
+                Element::Text {
+                    text,
+                    align,
+                    bold,
+                    size,
+                } => {
+                    let (sx, sy) = text_scale(*size);
+                    let size_pt = font_size * sy as f64;
+                    // Tall text keeps the normal width
+                    let h_scale = 100.0 * sx as f64 / sy as f64;
+                    y -= line_height * sy as f64;
+
+                    let text_width = text.chars().count() as f64 * font_size * COURIER_ADVANCE * sx as f64;
+                    let x = margin
+                        + match align {
+                            Align::Left => 0.0,
+                            Align::Center => ((content_width - text_width) / 2.0).max(0.0),
+                            Align::Right => (content_width - text_width).max(0.0),
+                        };
+
+                    if !text.trim().is_empty() {
+                        let font = if *bold { "F2" } else { "F1" };
+                        let _ = write!(
+                            content,
+                            "BT /{} {:.2} Tf {:.1} Tz {:.2} {:.2} Td ",
+                            font,
+                            size_pt,
+                            h_scale,
+                            x,
+                            y + line_height * sy as f64 * 0.2
+                        );
+                        content.extend_from_slice(&pdf_string(text));
+                        content.extend_from_slice(b" Tj ET\n");
+                    }
+                }
+                Element::Image { image, align } => {
+                    let width = image.width() as f64 * dot;
+                    let height = image.height() as f64 * dot;
+                    y -= height;
+                    let x = margin
+                        + match align {
+                            Align::Left => 0.0,
+                            Align::Center => ((content_width - width) / 2.0).max(0.0),
+                            Align::Right => (content_width - width).max(0.0),
+                        };
+                    let _ = writeln!(
+                        content,
+                        "q {:.2} 0 0 {:.2} {:.2} {:.2} cm /Im{} Do Q",
+                        width,
+                        height,
+                        x,
+                        y,
+                        images.len()
+                    );
+                    images.push(image);
+                    y -= line_height / 2.0;
+                }
+                Element::Space(lines) => y -= line_height * *lines as f64,
+            }
+        }
+
+        write_pdf(page_width, page_height, &content, &images)
+    }
+}
+
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    let _ = encoder.write_all(data);
+    encoder.finish().unwrap_or_default()
+}
+
+/// Single-page PDF with Courier text and grayscale images.
+fn write_pdf(width: f64, height: f64, content: &[u8], images: &[&GrayImage]) -> Vec<u8> {
+    let mut objects: Vec<Vec<u8>> = Vec::new();
+
+    let image_refs: String = (0..images.len())
+        .map(|i| format!("/Im{} {} 0 R ", i, 7 + i))
+        .collect();
+
+    objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+    objects.push(b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec());
+    objects.push(
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] \
+             /Resources << /Font << /F1 4 0 R /F2 5 0 R >> /XObject << {}>> >> /Contents 6 0 R >>",
+            width, height, image_refs
+        )
+        .into_bytes(),
+    );
+    objects.push(
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_vec(),
+    );
+    objects.push(
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier-Bold /Encoding /WinAnsiEncoding >>"
+            .to_vec(),
+    );
+
+    let stream = |dict: String, data: Vec<u8>| {
+        let mut object = format!("{} /Length {} >>\nstream\n", dict, data.len()).into_bytes();
+        object.extend_from_slice(&data);
+        object.extend_from_slice(b"\nendstream");
+        object
+    };
+
+    objects.push(stream("<< /Filter /FlateDecode".to_string(), deflate(content)));
+    for image in images {
+        objects.push(stream(
+            format!(
+                "<< /Type /XObject /Subtype /Image /Width {} /Height {} \
+                 /ColorSpace /DeviceGray /BitsPerComponent 8 /Filter /FlateDecode",
+                image.width(),
+                image.height()
+            ),
+            deflate(image.as_raw()),
+        ));
+    }
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+
+    let xref = pdf.len();
+    pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        )
+        .as_bytes(),
+    );
+    pdf
+}
+
+// --- Files ---
+
+/// Renders a sale receipt ('pdf' or 'png') into the app data `receipts`
+/// folder and returns the file path. Uses the sale template (custom or
+/// starter); no printer is involved.
+pub fn render_sale_receipt_file(
+    app_handle: &tauri::AppHandle,
+    sale_id: &str,
+    format: &str,
+    printer_width_mm: Option<u32>,
+) -> Result<String, String> {
+    if format != "pdf" && format != "png" {
+        return Err(format!("Formato no soportado: {}", format));
+    }
+
+    let data = printer_utils::load_ticket_data(app_handle, sale_id)?;
+    let width_mm = printer_width_mm
+        .or_else(|| data.hardware_config.printer_width.parse::<u32>().ok())
+        .map(|w| if w == 58 { 58 } else { 80 })
+        .unwrap_or(80);
+
+    let template = printer_utils::receipt_template(app_handle, "sale")?;
+
+    let max_width = ReceiptBuilder::new(width_mm).max_width;
+    let logo_width = (max_width as f64 * 0.5) as u32;
+    // Full raster, not the save-energy checkerboard used for thermal paper
+    let logo = printer_utils::resolve_logo_path(app_handle, &data.business_settings.logo_path)
+        .and_then(|path| image::open(path).ok())
+        .and_then(|img| printer_utils::convert_image_to_escpos_with(img, logo_width, false).ok());
+
+    let mut sink = DocumentSink::new(width_mm, logo, QrOptions::from_config(&data.hardware_config));
+    template.render(&printer_utils::sale_template_context(&data), &mut sink);
+
+    let dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("receipts");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Error creando carpeta de tickets: {}", e))?;
+
+    let safe_folio: String = data
+        .folio
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    let path = dir.join(format!("ticket-{}-{}mm.{}", safe_folio, width_mm, format));
+
+    if format == "png" {
+        sink.to_png()
+            .save_with_format(&path, image::ImageFormat::Png)
+            .map_err(|e| format!("Error guardando imagen: {}", e))?;
+    } else {
+        std::fs::write(&path, sink.to_pdf(width_mm))
+            .map_err(|e| format!("Error guardando PDF: {}", e))?;
+    }
+
+    Ok(path.to_string_lossy().to_string())
+}
@@ -0,0 +1,883 @@
+// Receipt template language.
+//
+// A template is plain text processed line by line. Lines starting with '@'
+// are directives, lines starting with '#' are comments and every other line
+// is printed as-is after replacing its placeholders.
+//
+//   {{name}} {{name|filter}}     placeholder; filters: money, qty, int, upper,
+//                                lower, left:N, right:N (pad/truncate to N chars,
+//                                N up to 256)
+//   @align left|center|right
+//   @bold on|off
+//   @size normal|tall|double
+//   @separator [char]            full-width line (default '-')
+//   @row Label | value           label, dot leader and right-aligned value
+//   @cols 4 * 9> | a | b | c     columns: fixed width, '*' fills the rest,
+//                                '>' aligns right
+//   @logo                        store logo from the business settings
+//   @barcode value / @qr value
+//   @feed [n] / @cut             n up to 20 blank lines
+//   @if name / @if !name / @else / @endif
+//   @each list ... @endeach      loop; item fields and {{index}} in scope
+//   @section name ... @endsection
+//                                named block, printed in place and reusable
+//   @include name                prints a section again
+//
+// Rendering goes through a `ReceiptSink`: ESC/POS for the printer or plain
+// text for previews.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::printer_utils::{self, ReceiptBuilder};
+
+const MAX_INCLUDE_DEPTH: usize = 8;
+/// Widest `left:N`/`right:N` field or `@cols` column; wider than any roll.
+const MAX_FIELD_WIDTH: usize = 256;
+/// Most blank lines a single `@feed` may print.
+const MAX_FEED_LINES: usize = 20;
+
+// --- Values ---
+
+#[derive(Debug, Clone)]
+pub enum TemplateValue {
+    Text(String),
+    Number(f64),
+    Bool(bool),
+    List(Vec<TemplateContext>),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    values: BTreeMap<String, TemplateValue>,
+}
+
+impl TemplateContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text(&mut self, key: &str, value: impl Into<String>) -> &mut Self {
+        self.values
+            .insert(key.to_string(), TemplateValue::Text(value.into()));
+        self
+    }
+
+    /// Stores `None` as an empty text so `@if` treats it as false.
+    pub fn opt_text(&mut self, key: &str, value: Option<&str>) -> &mut Self {
+        self.text(key, value.unwrap_or_default())
+    }
+
+    pub fn number(&mut self, key: &str, value: f64) -> &mut Self {
+        self.values
+            .insert(key.to_string(), TemplateValue::Number(value));
+        self
+    }
+
+    pub fn flag(&mut self, key: &str, value: bool) -> &mut Self {
+        self.values.insert(key.to_string(), TemplateValue::Bool(value));
+        self
+    }
+
+    pub fn list(&mut self, key: &str, items: Vec<TemplateContext>) -> &mut Self {
+        self.values.insert(key.to_string(), TemplateValue::List(items));
+        self
+    }
+
+    fn get(&self, key: &str) -> Option<&TemplateValue> {
+        self.values.get(key)
+    }
+}
+
+fn is_truthy(value: Option<&TemplateValue>) -> bool {
+    match value {
+        Some(TemplateValue::Text(s)) => !s.trim().is_empty(),
+        Some(TemplateValue::Number(n)) => n.abs() > 0.0001,
+        Some(TemplateValue::Bool(b)) => *b,
+        Some(TemplateValue::List(items)) => !items.is_empty(),
+        None => false,
+    }
+}
+
+// --- Syntax tree ---
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextSize {
+    Normal,
+    Tall,
+    Double,
+}
+
+#[derive(Debug, Clone)]
+enum Filter {
+    Money,
+    Qty,
+    Int,
+    Upper,
+    Lower,
+    Left(usize),
+    Right(usize),
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Literal(String),
+    Value { name: String, filters: Vec<Filter> },
+}
+
+/// A line of text with placeholders.
+#[derive(Debug, Clone)]
+struct Text(Vec<Segment>);
+
+#[derive(Debug, Clone, Copy)]
+struct Column {
+    /// `None` fills the remaining width
+    width: Option<usize>,
+    align_right: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Line(Text),
+    Align(Align),
+    Bold(bool),
+    Size(TextSize),
+    Separator(char),
+    Row(Text, Text),
+    Columns(Vec<Column>, Vec<Text>),
+    Logo,
+    Barcode(Text),
+    Qr(Text),
+    Feed(usize),
+    Cut,
+    If {
+        name: String,
+        negate: bool,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    Each {
+        name: String,
+        body: Vec<Node>,
+    },
+    Section(String),
+    Include(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ReceiptTemplate {
+    nodes: Vec<Node>,
+    sections: HashMap<String, Vec<Node>>,
+}
+
+// --- Parser ---
+
+enum Block {
+    If {
+        name: String,
+        negate: bool,
+        then: Vec<Node>,
+        in_else: bool,
+        line: usize,
+    },
+    Each {
+        name: String,
+        line: usize,
+    },
+    Section {
+        name: String,
+        line: usize,
+    },
+}
+
+fn parse_filter(spec: &str) -> Result<Filter, String> {
+    let (name, arg) = match spec.split_once(':') {
+        Some((name, arg)) => (name.trim(), Some(arg.trim())),
+        None => (spec.trim(), None),
+    };
+    let width = || {
+        let width = arg
+            .and_then(|a| a.parse::<usize>().ok())
+            .ok_or(format!("El filtro '{}' requiere un ancho", name))?;
+        if width > MAX_FIELD_WIDTH {
+            return Err(format!(
+                "El ancho del filtro '{}' no puede pasar de {}",
+                name, MAX_FIELD_WIDTH
+            ));
+        }
+        Ok(width)
+    };
+
+    match name {
+        "money" => Ok(Filter::Money),
+        "qty" => Ok(Filter::Qty),
+        "int" => Ok(Filter::Int),
+        "upper" => Ok(Filter::Upper),
+        "lower" => Ok(Filter::Lower),
+        "left" => Ok(Filter::Left(width()?)),
+        "right" => Ok(Filter::Right(width()?)),
+        _ => Err(format!("Filtro desconocido '{}'", name)),
+    }
+}
+
+fn parse_text(source: &str) -> Result<Text, String> {
+    let mut segments = Vec::new();
+    let mut rest = source;
+
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            segments.push(Segment::Literal(rest[..start].to_string()));
+        }
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or("Falta cerrar '}}' en un campo".to_string())?;
+
+        let mut parts = after[..end].split('|');
+        let name = parts.next().unwrap_or_default().trim().to_string();
+        if name.is_empty() {
+            return Err("Campo vacío '{{}}'".to_string());
+        }
+        let filters = parts.map(parse_filter).collect::<Result<Vec<_>, _>>()?;
+        segments.push(Segment::Value { name, filters });
+
+        rest = &after[end + 2..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest.to_string()));
+    }
+    Ok(Text(segments))
+}
+
+/// Splits directive arguments on '|' outside placeholders (filters use '|' too).
+fn split_cells(args: &str) -> Vec<&str> {
+    let mut cells = Vec::new();
+    let mut start = 0;
+    let mut inside = false;
+    let bytes = args.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i..].starts_with(b"{{") {
+            inside = true;
+            i += 2;
+        } else if bytes[i..].starts_with(b"}}") {
+            inside = false;
+            i += 2;
+        } else {
+            if bytes[i] == b'|' && !inside {
+                cells.push(&args[start..i]);
+                start = i + 1;
+            }
+            i += 1;
+        }
+    }
+    cells.push(&args[start..]);
+    cells
+}
+
+fn parse_columns(args: &str) -> Result<Node, String> {
+    let mut parts = split_cells(args).into_iter();
+    let spec = parts.next().unwrap_or_default();
+
+    let columns = spec
+        .split_whitespace()
+        .map(|width| {
+            let align_right = width.ends_with('>');
+            let width = width.trim_end_matches('>');
+            let width = if width == "*" {
+                None
+            } else {
+                let parsed = width
+                    .parse::<usize>()
+                    .map_err(|_| format!("Ancho de columna inválido '{}'", width))?;
+                if parsed > MAX_FIELD_WIDTH {
+                    return Err(format!(
+                        "El ancho de columna no puede pasar de {}",
+                        MAX_FIELD_WIDTH
+                    ));
+                }
+                Some(parsed)
+            };
+            Ok(Column { width, align_right })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let cells = parts
+        .map(|cell| parse_text(cell.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if columns.is_empty() || columns.len() != cells.len() {
+        return Err("@cols necesita un ancho por cada columna".to_string());
+    }
+    Ok(Node::Columns(columns, cells))
+}
+
+fn parse_directive(keyword: &str, args: &str) -> Result<Node, String> {
+    match keyword {
+        "align" => match args {
+            "left" => Ok(Node::Align(Align::Left)),
+            "center" => Ok(Node::Align(Align::Center)),
+            "right" => Ok(Node::Align(Align::Right)),
+            _ => Err(format!("Alineación inválida '{}'", args)),
+        },
+        "bold" => match args {
+            "on" => Ok(Node::Bold(true)),
+            "off" => Ok(Node::Bold(false)),
+            _ => Err(format!("Valor inválido para @bold '{}'", args)),
+        },
+        "size" => match args {
+            "normal" => Ok(Node::Size(TextSize::Normal)),
+            "tall" => Ok(Node::Size(TextSize::Tall)),
+            "double" => Ok(Node::Size(TextSize::Double)),
+            _ => Err(format!("Tamaño inválido '{}'", args)),
+        },
+        "separator" => Ok(Node::Separator(args.chars().next().unwrap_or('-'))),
+        "row" => {
+            let cells = split_cells(args);
+            let [label, value] = cells.as_slice() else {
+                return Err("@row necesita 'etiqueta | valor'".to_string());
+            };
+            Ok(Node::Row(parse_text(label.trim())?, parse_text(value.trim())?))
+        }
+        "cols" => parse_columns(args),
+        "logo" => Ok(Node::Logo),
+        "barcode" => Ok(Node::Barcode(parse_text(args)?)),
+        "qr" => Ok(Node::Qr(parse_text(args)?)),
+        "feed" => {
+            let lines = if args.is_empty() {
+                1
+            } else {
+                args.parse::<usize>()
+                    .map_err(|_| format!("Número de líneas inválido '{}'", args))?
+            };
+            if lines > MAX_FEED_LINES {
+                return Err(format!("@feed no puede pasar de {} líneas", MAX_FEED_LINES));
+            }
+            Ok(Node::Feed(lines))
+        }
+        "cut" => Ok(Node::Cut),
+        "include" if !args.is_empty() => Ok(Node::Include(args.to_string())),
+        _ => Err(format!("Instrucción desconocida '@{}'", keyword)),
+    }
+}
+
+impl ReceiptTemplate {
+    /// Parses a template; errors mention the offending line.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut stack: Vec<(Block, Vec<Node>)> = Vec::new();
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut sections: HashMap<String, Vec<Node>> = HashMap::new();
+
+        for (index, raw) in source.lines().enumerate() {
+            let line_no = index + 1;
+            let at_line = |e: String| format!("Línea {}: {}", line_no, e);
+            let line = raw.trim_end();
+
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            let Some(directive) = line.trim_start().strip_prefix('@') else {
+                let node = Node::Line(parse_text(line).map_err(at_line)?);
+                current(&mut stack, &mut nodes).push(node);
+                continue;
+            };
+
+            let (keyword, args) = match directive.split_once(char::is_whitespace) {
+                Some((keyword, args)) => (keyword, args.trim()),
+                None => (directive, ""),
+            };
+
+            match keyword {
+                "if" => {
+                    let (negate, name) = match args.strip_prefix('!') {
+                        Some(name) => (true, name.trim()),
+                        None => (false, args),
+                    };
+                    if name.is_empty() {
+                        return Err(at_line("@if necesita un campo".to_string()));
+                    }
+                    stack.push((
+                        Block::If {
+                            name: name.to_string(),
+                            negate,
+                            then: Vec::new(),
+                            in_else: false,
+                            line: line_no,
+                        },
+                        Vec::new(),
+                    ));
+                }
+                "else" => match stack.last_mut() {
+                    Some((Block::If { then, in_else, .. }, body)) if !*in_else => {
+                        *then = std::mem::take(body);
+                        *in_else = true;
+                    }
+                    _ => return Err(at_line("@else sin @if".to_string())),
+                },
+                "endif" => match stack.pop() {
+                    Some((
+                        Block::If {
+                            name,
+                            negate,
+                            then,
+                            in_else,
+                            ..
+                        },
+                        body,
+                    )) => {
+                        let (then, otherwise) = if in_else { (then, body) } else { (body, Vec::new()) };
+                        current(&mut stack, &mut nodes).push(Node::If {
+                            name,
+                            negate,
+                            then,
+                            otherwise,
+                        });
+                    }
+                    _ => return Err(at_line("@endif sin @if".to_string())),
+                },
+                "each" => {
+                    if args.is_empty() {
+                        return Err(at_line("@each necesita una lista".to_string()));
+                    }
+                    stack.push((
+                        Block::Each {
+                            name: args.to_string(),
+                            line: line_no,
+                        },
+                        Vec::new(),
+                    ));
+                }
+                "endeach" => match stack.pop() {
+                    Some((Block::Each { name, .. }, body)) => {
+                        current(&mut stack, &mut nodes).push(Node::Each { name, body });
+                    }
+                    _ => return Err(at_line("@endeach sin @each".to_string())),
+                },
+                "section" => {
+                    if args.is_empty() {
+                        return Err(at_line("@section necesita un nombre".to_string()));
+                    }
+                    if sections.contains_key(args) {
+                        return Err(at_line(format!("La sección '{}' ya existe", args)));
+                    }
+                    stack.push((
+                        Block::Section {
+                            name: args.to_string(),
+                            line: line_no,
+                        },
+                        Vec::new(),
+                    ));
+                }
+                "endsection" => match stack.pop() {
+                    Some((Block::Section { name, .. }, body)) => {
+                        sections.insert(name.clone(), body);
+                        current(&mut stack, &mut nodes).push(Node::Section(name));
+                    }
+                    _ => return Err(at_line("@endsection sin @section".to_string())),
+                },
+                _ => {
+                    let node = parse_directive(keyword, args).map_err(at_line)?;
+                    current(&mut stack, &mut nodes).push(node);
+                }
+            }
+        }
+
+        if let Some((block, _)) = stack.last() {
+            let (directive, line) = match block {
+                Block::If { line, .. } => ("@if", line),
+                Block::Each { line, .. } => ("@each", line),
+                Block::Section { line, .. } => ("@section", line),
+            };
+            return Err(format!("Línea {}: {} sin cerrar", line, directive));
+        }
+
+        let template = Self { nodes, sections };
+        template.check_includes(&template.nodes)?;
+        Ok(template)
+    }
+
+    fn check_includes(&self, nodes: &[Node]) -> Result<(), String> {
+        for node in nodes {
+            match node {
+                Node::Include(name) if !self.sections.contains_key(name) => {
+                    return Err(format!("La sección '{}' no existe", name));
+                }
+                Node::If { then, otherwise, .. } => {
+                    self.check_includes(then)?;
+                    self.check_includes(otherwise)?;
+                }
+                Node::Each { body, .. } => self.check_includes(body)?,
+                Node::Section(name) => self.check_includes(&self.sections[name])?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders the template into `sink` with the given data.
+    pub fn render(&self, context: &TemplateContext, sink: &mut dyn ReceiptSink) {
+        let scope = Scope {
+            context,
+            parent: None,
+        };
+        self.render_nodes(&self.nodes, &scope, sink, 0);
+    }
+
+    fn render_nodes(&self, nodes: &[Node], scopes: &Scope, sink: &mut dyn ReceiptSink, depth: usize) {
+        for node in nodes {
+            match node {
+                Node::Line(text) => sink.line(&render_text(text, scopes)),
+                Node::Align(align) => sink.align(*align),
+                Node::Bold(on) => sink.bold(*on),
+                Node::Size(size) => sink.size(*size),
+                Node::Separator(c) => {
+                    let line = c.to_string().repeat(sink.max_chars());
+                    sink.line(&line);
+                }
+                Node::Row(label, value) => {
+                    let line = dotted_row(
+                        &render_text(label, scopes),
+                        &render_text(value, scopes),
+                        sink.max_chars(),
+                    );
+                    sink.line(&line);
+                }
+                Node::Columns(columns, cells) => {
+                    let values: Vec<String> =
+                        cells.iter().map(|cell| render_text(cell, scopes)).collect();
+                    sink.line(&format_columns(columns, &values, sink.max_chars()));
+                }
+                Node::Logo => sink.logo(),
+                Node::Barcode(text) => {
+                    let value = render_text(text, scopes);
+                    if !value.trim().is_empty() {
+                        sink.barcode(value.trim());
+                    }
+                }
+                Node::Qr(text) => {
+                    let value = render_text(text, scopes);
+                    if !value.trim().is_empty() {
+                        sink.qr(value.trim());
+                    }
+                }
+                Node::Feed(lines) => sink.feed(*lines),
+                Node::Cut => sink.cut(),
+                Node::If {
+                    name,
+                    negate,
+                    then,
+                    otherwise,
+                } => {
+                    let branch = if is_truthy(scopes.lookup(name)) != *negate {
+                        then
+                    } else {
+                        otherwise
+                    };
+                    self.render_nodes(branch, scopes, sink, depth);
+                }
+                Node::Each { name, body } => {
+                    let Some(TemplateValue::List(items)) = scopes.lookup(name) else {
+                        continue;
+                    };
+                    for (index, item) in items.iter().enumerate() {
+                        let mut context = item.clone();
+                        context
+                            .number("index", (index + 1) as f64)
+                            .flag("first", index == 0)
+                            .flag("last", index + 1 == items.len());
+                        let scope = Scope {
+                            context: &context,
+                            parent: Some(scopes),
+                        };
+                        self.render_nodes(body, &scope, sink, depth);
+                    }
+                }
+                Node::Section(name) | Node::Include(name) => {
+                    if depth < MAX_INCLUDE_DEPTH {
+                        if let Some(section) = self.sections.get(name) {
+                            self.render_nodes(section, scopes, sink, depth + 1);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn current<'a>(stack: &'a mut [(Block, Vec<Node>)], nodes: &'a mut Vec<Node>) -> &'a mut Vec<Node> {
+    match stack.last_mut() {
+        Some((_, body)) => body,
+        None => nodes,
+    }
+}
+
+// --- Rendering helpers ---
+
+/// Data visible while rendering: a loop item first, then its enclosing scopes.
+struct Scope<'a> {
+    context: &'a TemplateContext,
+    parent: Option<&'a Scope<'a>>,
+}
+
+impl Scope<'_> {
+    fn lookup(&self, name: &str) -> Option<&TemplateValue> {
+        self.context
+            .get(name)
+            .or_else(|| self.parent.and_then(|parent| parent.lookup(name)))
+    }
+}
+
+fn format_number(value: f64) -> String {
+    if value.fract().abs() < 0.0001 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+fn apply_filter(value: &str, number: Option<f64>, filter: &Filter) -> String {
+    let parsed = || number.or_else(|| value.trim().parse::<f64>().ok());
+    match filter {
+        Filter::Money => parsed().map(|n| format!("{:.2}", n)).unwrap_or_else(|| value.to_string()),
+        Filter::Qty => parsed()
+            .map(|n| {
+                let formatted = format!("{:.3}", n);
+                formatted
+                    .trim_end_matches('0')
+                    .trim_end_matches('.')
+                    .to_string()
+            })
+            .unwrap_or_else(|| value.to_string()),
+        Filter::Int => parsed().map(|n| format!("{:.0}", n)).unwrap_or_else(|| value.to_string()),
+        Filter::Upper => value.to_uppercase(),
+        Filter::Lower => value.to_lowercase(),
+        Filter::Left(width) => fit(value, *width, false),
+        Filter::Right(width) => fit(value, *width, true),
+    }
+}
+
+fn render_text(text: &Text, scopes: &Scope) -> String {
+    let mut out = String::new();
+    for segment in &text.0 {
+        match segment {
+            Segment::Literal(literal) => out.push_str(literal),
+            Segment::Value { name, filters } => {
+                let (mut value, number) = match scopes.lookup(name) {
+                    Some(TemplateValue::Text(s)) => (s.clone(), None),
+                    Some(TemplateValue::Number(n)) => (format_number(*n), Some(*n)),
+                    Some(TemplateValue::Bool(b)) => ((if *b { "Si" } else { "No" }).to_string(), None),
+                    Some(TemplateValue::List(items)) => (items.len().to_string(), Some(items.len() as f64)),
+                    None => (String::new(), None),
+                };
+                for filter in filters {
+                    let numeric = matches!(filter, Filter::Money | Filter::Qty | Filter::Int);
+                    value = apply_filter(&value, if numeric { number } else { None }, filter);
+                }
+                out.push_str(&value);
+            }
+        }
+    }
+    out
+}
+
+/// Pads or truncates `value` to exactly `width` characters.
+fn fit(value: &str, width: usize, align_right: bool) -> String {
+    let truncated: String = value.chars().take(width).collect();
+    if align_right {
+        format!("{:>width$}", truncated, width = width)
+    } else {
+        format!("{:<width$}", truncated, width = width)
+    }
+}
+
+fn dotted_row(label: &str, value: &str, max_chars: usize) -> String {
+    let label = label.trim();
+    let value = value.trim();
+    let used = label.chars().count() + value.chars().count() + 2;
+
+    if used + 2 <= max_chars {
+        format!("{} {} {}", label, ".".repeat(max_chars - used), value)
+    } else {
+        format!("{} {}", label, value)
+    }
+}
+
+fn format_columns(columns: &[Column], values: &[String], max_chars: usize) -> String {
+    let fixed: usize = columns.iter().filter_map(|c| c.width).sum();
+    let fill_count = columns.iter().filter(|c| c.width.is_none()).count();
+    let gaps = columns.len().saturating_sub(1);
+    let fill_width = if fill_count > 0 {
+        max_chars.saturating_sub(fixed + gaps) / fill_count
+    } else {
+        0
+    };
+
+    columns
+        .iter()
+        .zip(values)
+        .map(|(column, value)| fit(value, column.width.unwrap_or(fill_width), column.align_right))
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end()
+        .to_string()
+}
+
+// --- Sinks ---
+
+pub trait ReceiptSink {
+    fn max_chars(&self) -> usize;
+    fn align(&mut self, align: Align);
+    fn bold(&mut self, on: bool);
+    fn size(&mut self, size: TextSize);
+    fn line(&mut self, text: &str);
+    fn logo(&mut self);
+    fn barcode(&mut self, data: &str);
+    /// Sinks without QR output print the content as text.
+    fn qr(&mut self, data: &str) {
+        self.line(data);
+    }
+    fn feed(&mut self, lines: usize);
+    fn cut(&mut self);
+}
+
+/// ESC/POS output through `ReceiptBuilder`.
+pub struct EscPosSink {
+    pub builder: ReceiptBuilder,
+    /// Pre-rendered logo raster, printed by `@logo`
+    pub logo: Option<Vec<u8>>,
+}
+
+impl ReceiptSink for EscPosSink {
+    fn max_chars(&self) -> usize {
+        self.builder.max_chars
+    }
+
+    fn align(&mut self, align: Align) {
+        match align {
+            Align::Left => self.builder.align_left(),
+            Align::Center => self.builder.align_center(),
+            Align::Right => self.builder.align_right(),
+        }
+    }
+
+    fn bold(&mut self, on: bool) {
+        self.builder.set_bold(on);
+    }
+
+    fn size(&mut self, size: TextSize) {
+        match size {
+            TextSize::Normal => self.builder.set_size_normal(),
+            TextSize::Tall => self.builder.set_size_double_h(),
+            TextSize::Double => self.builder.set_size_double_hw(),
+        }
+    }
+
+    fn line(&mut self, text: &str) {
+        self.builder.add_text_ln(&printer_utils::remove_accents(text));
+    }
+
+    fn logo(&mut self) {
+        if let Some(logo) = &self.logo {
+            self.builder.add_image(logo);
+        }
+    }
+
+    fn barcode(&mut self, data: &str) {
+        if let Ok(cmds) = printer_utils::generate_barcode_escpos(data, self.builder.max_width) {
+            self.builder.add_image(&cmds);
+        }
+    }
+
+    fn qr(&mut self, data: &str) {
+        self.builder.add_qr(data);
+    }
+
+    fn feed(&mut self, lines: usize) {
+        self.builder.add_text(&"\n".repeat(lines));
+    }
+
+    fn cut(&mut self) {
+        self.builder.cut();
+    }
+}
+
+/// Plain-text rendering used by previews; alignment is applied with spaces.
+pub struct PlainTextSink {
+    max_chars: usize,
+    align: Align,
+    lines: Vec<String>,
+}
+
+impl PlainTextSink {
+    pub fn new(printer_width_mm: u32) -> Self {
+        Self {
+            max_chars: ReceiptBuilder::new(printer_width_mm).max_chars,
+            align: Align::Left,
+            lines: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, text: &str) {
+        let width = text.chars().count();
+        let padding = match self.align {
+            Align::Left => 0,
+            Align::Center => self.max_chars.saturating_sub(width) / 2,
+            Align::Right => self.max_chars.saturating_sub(width),
+        };
+        self.lines.push(format!("{}{}", " ".repeat(padding), text));
+    }
+
+    pub fn finish(self) -> String {
+        self.lines.join("\n")
+    }
+}
+
+impl ReceiptSink for PlainTextSink {
+    fn max_chars(&self) -> usize {
+        self.max_chars
+    }
+
+    fn align(&mut self, align: Align) {
+        self.align = align;
+    }
+
+    fn bold(&mut self, _on: bool) {}
+
+    fn size(&mut self, _size: TextSize) {}
+
+    fn line(&mut self, text: &str) {
+        for line in text.split('\n') {
+            self.push(line);
+        }
+    }
+
+    fn logo(&mut self) {
+        self.push("[LOGO]");
+    }
+
+    fn barcode(&mut self, data: &str) {
+        self.push(&format!("[CODIGO DE BARRAS: {}]", data));
+    }
+
+    fn qr(&mut self, data: &str) {
+        self.push(&format!("[QR: {}]", data));
+    }
+
+    fn feed(&mut self, lines: usize) {
+        for _ in 0..lines {
+            self.lines.push(String::new());
+        }
+    }
+
+    fn cut(&mut self) {
+        self.lines.push("- - - - - - - - corte - - - - - - - -".to_string());
+    }
+}
@@ -34,6 +34,8 @@ import { toast } from "sonner";
 import { ShiftSummary } from "@/features/cash-register/components/ShiftSummary";
 import { ScrollArea } from "@/components/ui/scroll-area";
 import { backupDatabase } from "@/lib/api/backup";
+import { MoneyInput } from "@/components/ui/money-input";
+import { formatCurrency } from "@/lib/utils";
 
 interface CloseShiftModalProps {
   shiftId: number;
@@ -71,29 +73,41 @@ export function CloseShiftModal({
     resolver: zodResolver(closeShiftSchema) as any,
     defaultValues: {
       terminal_cut_confirmed: undefined as any,
+      counted_cash: "" as any,
       notes: "",
     },
   });
 
   useEffect(() => {
     if (isOpen) {
-      reset({ terminal_cut_confirmed: undefined, notes: "" });
+      reset({ terminal_cut_confirmed: undefined, counted_cash: "" as any, notes: "" });
       setSessionKey((prev) => prev + 1);
       setShowConfirm(false);
       setShowNotes(false);
     }
   }, [isOpen, reset]);
 
+  const countedInput = watch("counted_cash") as unknown as string | number;
+  const countedCash =
+    countedInput === "" || countedInput === undefined ? null : Number(countedInput);
+  const cashDifference =
+    details && countedCash !== null && !Number.isNaN(countedCash)
+      ? countedCash - details.total_cash
+      : null;
+
   const onSubmit = (_values: CloseShiftFormValues) => {
     setShowConfirm(true);
   };
 
   const handleConfirm = async () => {
-    if (!user?.id) return;
+    if (!user?.id || countedCash === null) return;
     const notes = watch("notes");
     setIsClosing(true);
     try {
-      const closed = await closeShift({ notes: notes || undefined }, user.id);
+      const closed = await closeShift(
+        { notes: notes || undefined, counted_cash: countedCash },
+        user.id
+      );
       toast.success("Turno cerrado correctamente", {
         description: `Folio: ${closed.code ?? "—"}`,
       });
@@ -128,7 +142,11 @@ export function CloseShiftModal({
   };
 
   const onInvalid = (errors: FieldErrors<CloseShiftFormValues>) => {
-    if (errors.terminal_cut_confirmed) {
+    if (errors.counted_cash) {
+      toast.error("Acción requerida", {
+        description: errors.counted_cash.message,
+      });
+    } else if (errors.terminal_cut_confirmed) {
       toast.error("Acción requerida", {
         description: errors.terminal_cut_confirmed.message,
       });
@@ -210,6 +228,48 @@ export function CloseShiftModal({
 
             {/* ── Footer form ── */}
             <div className="border-t p-4 bg-background shrink-0 space-y-4">
+              {/* Counted cash */}
+              <div className="flex flex-col sm:flex-row sm:items-end gap-3">
+                <div className="flex flex-col gap-1.5 sm:w-64">
+                  <Label htmlFor="counted_cash" className="text-sm font-medium">
+                    Efectivo contado en caja
+                  </Label>
+                  <Controller
+                    name="counted_cash"
+                    control={control}
+                    render={({ field }) => (
+                      <MoneyInput
+                        id="counted_cash"
+                        {...field}
+                        value={field.value as unknown as string}
+                      />
+                    )}
+                  />
+                  {errors.counted_cash && (
+                    <p className="text-xs text-destructive">
+                      {errors.counted_cash.message}
+                    </p>
+                  )}
+                </div>
+                {details && cashDifference !== null && (
+                  <p className="text-sm text-muted-foreground pb-2">
+                    Esperado: {formatCurrency(details.total_cash)} · Diferencia:{" "}
+                    <span
+                      className={
+                        Math.abs(cashDifference) < 0.005
+                          ? "text-foreground font-medium"
+                          : cashDifference > 0
+                            ? "text-green-700 font-medium"
+                            : "text-destructive font-medium"
+                      }
+                    >
+                      {cashDifference > 0 ? "+" : ""}
+                      {formatCurrency(cashDifference)}
+                    </span>
+                  </p>
+                )}
+              </div>
+
               {/* Notes — collapsible */}
               <div>
                 <button
@@ -3,6 +3,12 @@ import { z } from 'zod';
 export const closeShiftSchema = z.object({
   terminal_cut_confirmed: z.literal(true, 'Debe confirmar el corte de la terminal bancaria'),
 
+  counted_cash: z
+    .union([z.string(), z.number()])
+    .refine((val) => val !== '', 'Capture el efectivo contado')
+    .pipe(z.coerce.number())
+    .pipe(z.number().min(0, 'El efectivo contado no puede ser negativo')),
+
   notes: z
     .string()
     .trim()
@@ -36,6 +36,7 @@ import { ScannerInput } from "@/features/sales/components/ScannerInput";
 import { CashMovementModal } from "@/features/cash-register/components/CashMovementModal";
 import { DiscountModal } from "@/features/sales/components/DiscountModal";
 import { OutOfStockWarningModal } from "@/features/sales/components/OutOfStockWarningModal";
+import { updateCustomerDisplay } from "@/lib/api/customer-display";
 
 import {
   AlertDialog,
@@ -120,6 +121,7 @@ export default function SalesPage() {
       if (isBlocked) return;
 
       setIsCheckoutOpen(true);
+      updateCustomerDisplay({ kind: "total", total: ticketTotal });
     }
   };
 
@@ -549,6 +551,7 @@ export default function SalesPage() {
           onClearTicket={() => {
             clearTicket();
             setSelectedItemUuid(null);
+            updateCustomerDisplay({ kind: "idle" });
           }}
           onCheckout={handleCheckoutRequest}
           canCreateSales={can("sales:create")}
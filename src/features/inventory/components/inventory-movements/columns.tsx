@@ -71,6 +71,7 @@ export const getColumns = (): ColumnDef<InventoryMovement>[] => [
         PURCHASE: "Compra",
         ADJUSTMENT: "Ajuste",
         RETURN: "Devolución",
+        RETURN_NO_RECEIPT: "Devolución sin Ticket",
         DAMAGED: "Merma / Daño",
         INITIAL_STOCK: "Inventario Inicial",
         CANCELLED_SALE: "Venta Cancelada",
@@ -268,6 +268,8 @@ export function ProductDialog({
         form.setError("barcode", {
           message: "Este código de barras ya está en uso",
         });
+      } else if (errCode === "INVALID_BARCODE") {
+        form.setError("barcode", { message: errMsg });
       } else {
         toast.error(errMsg);
       }
@@ -0,0 +1,62 @@
+import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { getCustomerDisplayState } from "@/lib/api/customer-display";
+import { CustomerDisplayUpdate } from "@/types/customer-display";
+
+// Contenido de la segunda ventana (pantalla orientada al cliente)
+export function CustomerDisplayScreen() {
+  const [update, setUpdate] = useState<CustomerDisplayUpdate | null>(null);
+
+  useEffect(() => {
+    getCustomerDisplayState().then(setUpdate).catch(() => {});
+
+    const unlisten = listen<CustomerDisplayUpdate>("customer-display", (event) => {
+      setUpdate(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const money = (amount: number) => `${update?.currencySymbol ?? "$"}${amount.toFixed(2)}`;
+  const event = update?.event;
+
+  return (
+    <div className="flex h-screen w-screen flex-col items-center justify-center gap-6 bg-black p-8 text-white">
+      {(!event || event.kind === "idle") && (
+        <>
+          <p className="text-5xl font-bold text-center">{update?.rows[0].trim()}</p>
+          <p className="text-3xl text-center text-white/70">{update?.rows[1].trim()}</p>
+        </>
+      )}
+
+      {event?.kind === "line-added" && (
+        <>
+          <p className="text-3xl text-center">
+            {event.quantity !== 1 && `${event.quantity} x `}
+            {event.description}
+          </p>
+          <p className="text-4xl">{money(event.quantity * event.unitPrice)}</p>
+          <p className="text-2xl text-white/70">Total</p>
+          <p className="text-7xl font-bold">{money(event.subtotal)}</p>
+        </>
+      )}
+
+      {event?.kind === "total" && (
+        <>
+          <p className="text-3xl text-white/70">Total a pagar</p>
+          <p className="text-8xl font-bold">{money(event.total)}</p>
+        </>
+      )}
+
+      {event?.kind === "change" && (
+        <>
+          <p className="text-3xl text-white/70">Total {money(event.total)} · Pago {money(event.paid)}</p>
+          <p className="text-3xl">Su cambio</p>
+          <p className="text-8xl font-bold">{money(event.change)}</p>
+          <p className="text-2xl text-white/70">¡Gracias por su compra!</p>
+        </>
+      )}
+    </div>
+  );
+}
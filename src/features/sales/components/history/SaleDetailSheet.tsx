@@ -59,9 +59,11 @@ export function SaleDetailPanel({ saleId, onClose, hideCustomerInfo }: SaleDetai
   const canReturn = daysSinceSale <= 30;
   const { shift: activeShift } = useCashRegisterStore();
   
+  const isSameShift = !!activeShift &&
+    sale?.cash_register_shift_id === String(activeShift.id);
   const canCancel = sale?.status === "completed" && 
     !!activeShift && 
-    sale?.cash_register_shift_id === String(activeShift.id);
+    (isSameShift || can("history:cancel_closed_shift"));
   
   const showActionButtons = sale?.status !== "cancelled" && sale?.status !== "fully_returned";
   
@@ -240,7 +242,9 @@ export function SaleDetailPanel({ saleId, onClose, hideCustomerInfo }: SaleDetai
                           <p>
                             {sale.status === "partial_return"
                               ? "No se puede cancelar una venta con devoluciones"
-                              : "Solo se puede cancelar una venta dentro del mismo turno de caja"
+                              : !activeShift
+                                ? "Se requiere un turno abierto para cancelar ventas"
+                                : "Solo se puede cancelar una venta dentro del mismo turno de caja"
                             }
                           </p>
                         </TooltipContent>
@@ -10,6 +10,7 @@ import * as CartProcessor from '@/features/sales/services/cartProcessor';
 import { Ticket } from '@/types/sales';
 import { useOutOfStockWarningStore } from './outOfStockWarningStore';
 import { useBusinessStore } from '@/stores/businessStore';
+import { updateCustomerDisplay } from '@/lib/api/customer-display';
 
 
 
@@ -163,6 +164,19 @@ export const useCartStore = create<CartState>()(
 
           return { tickets: newTickets };
         });
+
+        if (addedUuid) {
+          const item = get().getActiveTicket()?.items.find(i => i.uuid === addedUuid);
+          if (item) {
+            updateCustomerDisplay({
+              kind: 'line-added',
+              description: item.name,
+              quantity: item.quantity,
+              unitPrice: item.finalPrice,
+              subtotal: get().getTicketTotal(),
+            });
+          }
+        }
         return addedUuid;
       },
 
@@ -217,6 +231,12 @@ export const useCartStore = create<CartState>()(
 
           return { tickets: newTickets };
         });
+
+        if (get().getActiveTicket()?.items.length) {
+          updateCustomerDisplay({ kind: 'total', total: get().getTicketTotal() });
+        } else {
+          updateCustomerDisplay({ kind: 'idle' });
+        }
       },
 
       updateQuantity: (uuid: string, quantity) => {
@@ -350,6 +370,19 @@ export const useCartStore = create<CartState>()(
 
           return { tickets: newTickets };
         });
+
+        const item = get().getActiveTicket()?.items.find(i => i.uuid === uuid);
+        if (item) {
+          updateCustomerDisplay({
+            kind: 'line-added',
+            description: item.name,
+            quantity: item.quantity,
+            unitPrice: item.finalPrice,
+            subtotal: get().getTicketTotal(),
+          });
+        } else {
+          updateCustomerDisplay({ kind: 'total', total: get().getTicketTotal() });
+        }
       },
 
 
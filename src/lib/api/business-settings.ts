@@ -19,6 +19,10 @@ export interface BusinessSettings {
   discountPresetOptions: string;
   maxDiscountPercentage: number;
   maxOpenTickets: number;
+  internalBarcodePrefix: string;
+  autoInternalBarcode: boolean;
+  customerDisplayTopLine: string; // vacío = nombre de la tienda
+  customerDisplayBottomLine: string;
 }
 
 export const getBusinessSettings = async (): Promise<BusinessSettings> => {
@@ -0,0 +1,18 @@
+import { invoke } from '@tauri-apps/api/core';
+import { DailyReport, ZReportSummary } from '@/types/cash-register';
+
+export const getXReport = async (userId: string, date?: string): Promise<DailyReport> => {
+  return await invoke('get_x_report', { date, userId });
+};
+
+export const createZReport = async (userId: string, date?: string): Promise<DailyReport> => {
+  return await invoke('create_z_report', { date, userId });
+};
+
+export const getZReports = async (userId: string): Promise<ZReportSummary[]> => {
+  return await invoke('get_z_reports', { userId });
+};
+
+export const getZReport = async (zReportId: string, userId: string): Promise<DailyReport> => {
+  return await invoke('get_z_report', { zReportId, userId });
+};
@@ -1,5 +1,11 @@
 import { invoke } from '@tauri-apps/api/core';
-import { SaleRequest, SaleResponse, VoucherValidationResponse } from '@/types/sale';
+import {
+  SalePaymentMethod,
+  SaleRequest,
+  SaleResponse,
+  TerminalChargeResponse,
+  VoucherValidationResponse,
+} from '@/types/sale';
 
 export const processSale = async (payload: SaleRequest): Promise<SaleResponse> => {
   return await invoke('process_sale', { payload });
@@ -8,3 +14,15 @@ export const processSale = async (payload: SaleRequest): Promise<SaleResponse> =
 export const validateVoucher = async (code: string): Promise<VoucherValidationResponse> => {
   return await invoke('validate_voucher', { code });
 };
+
+export const chargeCardTerminal = async (
+  amount: number,
+  method: SalePaymentMethod,
+  userId: string
+): Promise<TerminalChargeResponse> => {
+  return await invoke('charge_card_terminal', { amount, method, userId });
+};
+
+export const voidTerminalCharge = async (transactionId: string): Promise<void> => {
+  return await invoke('void_terminal_charge', { transactionId });
+};
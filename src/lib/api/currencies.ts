@@ -0,0 +1,18 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { Currency, CurrencyInput } from '@/types/currency';
+
+export async function getCurrencies(): Promise<Currency[]> {
+  return await invoke<Currency[]>('get_currencies');
+}
+
+export async function saveCurrency(currency: CurrencyInput, userId: string): Promise<Currency> {
+  return await invoke<Currency>('save_currency', { currency, userId });
+}
+
+export async function setExchangeRate(
+  currencyCode: string,
+  rate: number,
+  userId: string,
+): Promise<Currency> {
+  return await invoke<Currency>('set_exchange_rate', { currencyCode, rate, userId });
+}
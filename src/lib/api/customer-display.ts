@@ -0,0 +1,23 @@
+import { invoke } from "@tauri-apps/api/core";
+import {
+  CustomerDisplayConfig,
+  CustomerDisplayEvent,
+  CustomerDisplayUpdate,
+} from "@/types/customer-display";
+
+// Fire-and-forget: a display problem must never interrupt the sale
+export const updateCustomerDisplay = (event: CustomerDisplayEvent): void => {
+  invoke('update_customer_display', { event }).catch(() => {});
+};
+
+export const openCustomerDisplay = async (): Promise<void> => {
+  return await invoke('open_customer_display');
+};
+
+export const getCustomerDisplayState = async (): Promise<CustomerDisplayUpdate | null> => {
+  return await invoke<CustomerDisplayUpdate | null>('get_customer_display_state');
+};
+
+export const testCustomerDisplay = async (config: CustomerDisplayConfig): Promise<void> => {
+  return await invoke('test_customer_display', { config });
+};
@@ -0,0 +1,115 @@
+import { invoke } from "@tauri-apps/api/core";
+import { formatDate } from "@/lib/api/reports";
+import { ExportOptions, ExportResult } from "@/types/exports";
+import { SalesHistoryFilter } from "@/types/sales-history";
+import { MovementsFilter } from "@/types/inventory-movements";
+import { ReorderParams } from "@/types/reports";
+
+export const exportSalesReport = async (
+  userId: string,
+  options: ExportOptions,
+  from: Date,
+  to: Date,
+): Promise<ExportResult> => {
+  return await invoke<ExportResult>("export_sales_report", {
+    userId,
+    options,
+    fromDate: formatDate(from),
+    toDate: formatDate(to),
+  });
+};
+
+export const exportTopSellingProducts = async (
+  userId: string,
+  options: ExportOptions,
+  from: Date,
+  to: Date,
+  categoryIds?: string[],
+  sortBy?: string,
+  sortOrder?: string,
+): Promise<ExportResult> => {
+  return await invoke<ExportResult>("export_top_selling_products", {
+    userId,
+    options,
+    fromDate: formatDate(from),
+    toDate: formatDate(to),
+    categoryIds: categoryIds?.length ? categoryIds : null,
+    sortBy: sortBy || null,
+    sortOrder: sortOrder || null,
+  });
+};
+
+export const exportDeadStockReport = async (
+  userId: string,
+  options: ExportOptions,
+  from: Date,
+  to: Date,
+  categoryIds?: string[],
+  sortBy?: string,
+  sortOrder?: string,
+): Promise<ExportResult> => {
+  return await invoke<ExportResult>("export_dead_stock_report", {
+    userId,
+    options,
+    fromDate: formatDate(from),
+    toDate: formatDate(to),
+    categoryIds: categoryIds?.length ? categoryIds : null,
+    sortBy: sortBy || null,
+    sortOrder: sortOrder || null,
+  });
+};
+
+export const exportInventoryValuation = async (
+  userId: string,
+  options: ExportOptions,
+): Promise<ExportResult> => {
+  return await invoke<ExportResult>("export_inventory_valuation", { userId, options });
+};
+
+export const exportLowStockProducts = async (
+  userId: string,
+  options: ExportOptions,
+  categoryIds?: string[],
+  sortBy?: string,
+  sortOrder?: string,
+): Promise<ExportResult> => {
+  return await invoke<ExportResult>("export_low_stock_products", {
+    userId,
+    options,
+    categoryIds: categoryIds?.length ? categoryIds : null,
+    sortBy: sortBy || null,
+    sortOrder: sortOrder || null,
+  });
+};
+
+export const exportReorderList = async (
+  userId: string,
+  options: ExportOptions,
+  params?: ReorderParams,
+): Promise<ExportResult> => {
+  return await invoke<ExportResult>("export_reorder_list", {
+    userId,
+    options,
+    params: params ?? null,
+  });
+};
+
+export const exportSalesHistory = async (
+  userId: string,
+  options: ExportOptions,
+  filter: SalesHistoryFilter,
+): Promise<ExportResult> => {
+  return await invoke<ExportResult>("export_sales_history", { userId, options, filter });
+};
+
+export const exportKardex = async (
+  userId: string,
+  options: ExportOptions,
+  filters?: MovementsFilter,
+): Promise<ExportResult> => {
+  return await invoke<ExportResult>("export_kardex", {
+    userId,
+    options,
+    filters: filters ?? null,
+  });
+};
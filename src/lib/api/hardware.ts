@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import { CustomerDisplayConfig } from "@/types/customer-display";
 
 export interface HardwareConfig {
   terminalId: string;
@@ -8,6 +9,26 @@ export interface HardwareConfig {
   cashDrawerPort?: string;
   autoOpenCashDrawer: boolean;
   paddingLines?: number;
+  paymentTerminal?: PaymentTerminalConfig;
+  printerTransport?: PrinterTransport;
+  printerAddress?: string | null; // host[:puerto] para 'tcp' (9100 por defecto)
+  printerSerialPort?: string | null; // COM3, /dev/ttyUSB0... para 'serial'
+  printerSerialBaud?: number; // 9600 por defecto, 8N1
+  blockSaleOnPaperOut?: boolean; // rechazar ventas con ticket si no hay papel
+  qrMode?: 'native' | 'raster'; // 'raster' para impresoras sin QR nativo
+  qrModuleSize?: number; // puntos por módulo, 1-16
+  qrErrorCorrection?: 'L' | 'M' | 'Q' | 'H';
+  customerDisplay?: CustomerDisplayConfig;
+}
+
+export type PrinterTransport = 'spooler' | 'tcp' | 'serial';
+
+export interface PaymentTerminalConfig {
+  driver: 'none' | 'tcp' | 'serial' | 'simulator'; // 'simulator' solo en compilaciones de desarrollo
+  address?: string | null;
+  serialPort?: string | null;
+  baudRate?: number; // 9600 por defecto
+  timeoutSecs: number;
 }
 
 export const getSystemPrinters = async (): Promise<string[]> => {
@@ -22,13 +43,42 @@ export const loadSettings = async (): Promise<HardwareConfig> => {
   return await invoke("load_settings");
 };
 
-export const testPrinterConnection = async (printerName: string): Promise<string> => {
-  return await invoke("test_printer_connection", { printerName });
+export const testPrinterConnection = async (
+  printerName: string,
+  config?: HardwareConfig,
+): Promise<string> => {
+  return await invoke("test_printer_connection", { printerName, config: config ?? null });
 };
 
-export const testCashDrawer = async (printerName: string, commandHex: string): Promise<string> => {
-  return await invoke("test_cash_drawer", { printerName, commandHex });
+export const testCashDrawer = async (
+  printerName: string,
+  commandHex: string,
+  config?: HardwareConfig,
+): Promise<string> => {
+  return await invoke("test_cash_drawer", { printerName, commandHex, config: config ?? null });
 };
+export interface PrinterStatus {
+  online: boolean;
+  cover_open: boolean;
+  paper_near_end: boolean;
+  paper_out: boolean;
+  error: boolean;
+}
+
+// También se emite como evento 'printer-status' cuando cambia
+export interface PrinterStatusReport {
+  transport: PrinterTransport;
+  destination: string | null;
+  supported: boolean; // false con el spooler del sistema
+  status: PrinterStatus | null;
+  error: string | null;
+  checked_at: string;
+}
+
+export const getPrinterStatus = async (): Promise<PrinterStatusReport> => {
+  return await invoke("get_printer_status");
+};
+
 export const openCashDrawer = async (): Promise<void> => {
   return await invoke("open_cash_drawer");
 };
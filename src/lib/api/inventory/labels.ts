@@ -0,0 +1,38 @@
+import { invoke } from "@tauri-apps/api/core";
+import { ProductFilters } from "@/types/inventory";
+import { LabelConfig, LabelPrintResult, PriceChangedProduct } from "@/types/labels";
+
+export const getLabelConfig = async (): Promise<LabelConfig> => {
+  return await invoke<LabelConfig>('get_label_config');
+};
+
+export const saveLabelConfig = async (config: LabelConfig): Promise<void> => {
+  return await invoke('save_label_config', { config });
+};
+
+export const printProductLabels = async (options: {
+  search?: string;
+  filters?: ProductFilters;
+  productIds?: string[];
+  copies?: number;
+  userId?: string;
+}): Promise<LabelPrintResult> => {
+  return await invoke<LabelPrintResult>('print_product_labels', {
+    search: options.search || null,
+    filters: options.filters || null,
+    productIds: options.productIds?.length ? options.productIds : null,
+    copies: options.copies ?? null,
+    userId: options.userId ?? null,
+  });
+};
+
+export const getPriceChangedProducts = async (): Promise<PriceChangedProduct[]> => {
+  return await invoke<PriceChangedProduct[]>('get_price_changed_products');
+};
+
+export const printPriceChangeLabels = async (copies?: number, userId?: string): Promise<LabelPrintResult> => {
+  return await invoke<LabelPrintResult>('print_price_change_labels', {
+    copies: copies ?? null,
+    userId: userId ?? null,
+  });
+};
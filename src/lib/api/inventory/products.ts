@@ -7,7 +7,8 @@ import {
   ProductDetail,
   UpdateProductPayload,
   ProductFilters,
-  ProductDependencies
+  ProductDependencies,
+  BarcodeBackfillResult
 } from "@/types/inventory";
 import { BulkUpdateProductsPayload } from "@/types/inventory";
 
@@ -105,4 +106,11 @@ export const bulkUpdateProducts = async (payload: BulkUpdateProductsPayload): Pr
   } catch (error) {
     throw new Error(String(error));
   }
-};
\ No newline at end of file
+};
+export const generateInternalBarcode = async (): Promise<string> => {
+  return await invoke<string>("generate_internal_barcode");
+};
+
+export const backfillInternalBarcodes = async (userId: string): Promise<BarcodeBackfillResult> => {
+  return await invoke<BarcodeBackfillResult>("backfill_internal_barcodes", { userId });
+};
@@ -0,0 +1,18 @@
+import { invoke } from "@tauri-apps/api/core";
+import { PrintJob, PrintJobStatus } from "@/types/print-queue";
+
+export const getPrintJobs = async (status?: PrintJobStatus[]): Promise<PrintJob[]> => {
+  return await invoke<PrintJob[]>('get_print_jobs', { status: status?.length ? status : null });
+};
+
+export const retryPrintJob = async (jobId: number): Promise<void> => {
+  return await invoke('retry_print_job', { jobId });
+};
+
+export const retryFailedPrintJobs = async (): Promise<number> => {
+  return await invoke<number>('retry_failed_print_jobs');
+};
+
+export const discardPrintJob = async (jobId: number): Promise<void> => {
+  return await invoke('discard_print_job', { jobId });
+};
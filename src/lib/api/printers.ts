@@ -13,6 +13,15 @@ export const printSaleTicket = async (saleId: string): Promise<string> => {
   return await invoke('print_sale_ticket', { saleId });
 };
 
+/** Renders the sale receipt to a PDF or PNG file and returns its path (no printer needed). */
+export const renderSaleReceipt = async (
+  saleId: string,
+  format: 'pdf' | 'png',
+  printerWidth?: 58 | 80,
+): Promise<string> => {
+  return await invoke('render_sale_receipt', { saleId, format, printerWidth: printerWidth ?? null });
+};
+
 export const printReturnVoucher = async (saleId: string): Promise<string> => {
   return await invoke('print_return_voucher', { saleId });
 };
@@ -25,3 +34,11 @@ export const printShiftTicket = async (shiftId: number | string): Promise<string
 export const printPaymentReceipt = async (paymentId: string | number): Promise<string> => {
   return await invoke('print_payment_receipt', { paymentId: String(paymentId) });
 };
+
+export const printXReport = async (userId: string, date?: string): Promise<string> => {
+  return await invoke('print_x_report', { date, userId });
+};
+
+export const printZReport = async (zReportId: string, userId: string): Promise<string> => {
+  return await invoke('print_z_report', { zReportId, userId });
+};
@@ -0,0 +1,35 @@
+import { invoke } from "@tauri-apps/api/core";
+import { ReceiptDocumentType, ReceiptTemplate } from "@/types/receipt-templates";
+
+export const getReceiptTemplates = async (): Promise<ReceiptTemplate[]> => {
+  return await invoke<ReceiptTemplate[]>('get_receipt_templates');
+};
+
+export const saveReceiptTemplate = async (
+  userId: string,
+  documentType: ReceiptDocumentType,
+  content: string,
+  isActive: boolean,
+): Promise<void> => {
+  return await invoke('save_receipt_template', { userId, documentType, content, isActive });
+};
+
+export const resetReceiptTemplate = async (
+  userId: string,
+  documentType: ReceiptDocumentType,
+): Promise<void> => {
+  return await invoke('reset_receipt_template', { userId, documentType });
+};
+
+/** Plain-text rendering with sample data; pass `content` to preview unsaved edits. */
+export const previewReceiptTemplate = async (
+  documentType: ReceiptDocumentType,
+  content?: string,
+  printerWidth?: number,
+): Promise<string> => {
+  return await invoke<string>('preview_receipt_template', {
+    documentType,
+    content: content ?? null,
+    printerWidth: printerWidth ?? null,
+  });
+};
@@ -0,0 +1,32 @@
+import { invoke } from "@tauri-apps/api/core";
+import { ReportDelivery, ReportDeliveryConfig } from "@/types/report-delivery";
+
+export async function getReportDeliveryConfig(userId: string): Promise<ReportDeliveryConfig> {
+  return await invoke<ReportDeliveryConfig>("get_report_delivery_config", { userId });
+}
+
+export async function saveReportDeliveryConfig(
+  config: ReportDeliveryConfig,
+  userId: string
+): Promise<void> {
+  return await invoke("save_report_delivery_config", { config, userId });
+}
+
+export async function testReportDelivery(
+  config: ReportDeliveryConfig,
+  userId: string
+): Promise<string> {
+  return await invoke<string>("test_report_delivery", { config, userId });
+}
+
+export async function sendDailyReportNow(userId: string, date?: string): Promise<ReportDelivery> {
+  return await invoke<ReportDelivery>("send_daily_report_now", { date, userId });
+}
+
+export async function getReportDeliveries(userId: string, limit?: number): Promise<ReportDelivery[]> {
+  return await invoke<ReportDelivery[]>("get_report_deliveries", { limit, userId });
+}
+
+export async function retryReportDelivery(deliveryId: number, userId: string): Promise<ReportDelivery> {
+  return await invoke<ReportDelivery>("retry_report_delivery", { deliveryId, userId });
+}
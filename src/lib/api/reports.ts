@@ -1,16 +1,24 @@
 import { invoke } from "@tauri-apps/api/core";
 import { PaginatedResponse } from "@/types/pagination";
-import { SalesReport, TopSellingProduct, DeadStockProduct, InventoryValuation, LowStockProduct } from "@/types/reports";
+import { SalesReport, TopSellingProduct, DeadStockProduct, InventoryValuation, LowStockProduct, HeatmapCell, CashierPerformance, DiscountAnalysis, DiscountPeriod, CompareMode, DateRange, ReorderParams, ReorderSuggestion } from "@/types/reports";
 
 export const formatDate = (date: Date): string => {
   return date.toISOString().split('T')[0];
 };
 
-export const getSalesReport = async (from: Date, to: Date): Promise<SalesReport> => {
+export const getSalesReport = async (
+  from: Date,
+  to: Date,
+  compareMode?: CompareMode,
+  compareRange?: DateRange,
+): Promise<SalesReport> => {
   try {
     return await invoke<SalesReport>("get_sales_report", {
       fromDate: formatDate(from),
       toDate: formatDate(to),
+      compareMode: compareMode || null,
+      compareFrom: compareRange ? formatDate(compareRange.from) : null,
+      compareTo: compareRange ? formatDate(compareRange.to) : null,
     });
   } catch (error) {
     throw new Error(`Error fetching sales report: ${error}`);
@@ -91,4 +99,69 @@ export const getLowStockProducts = async (
   } catch (error) {
     throw new Error(`Error fetching low stock products: ${error}`);
   }
-};
\ No newline at end of file
+};
+export const getSalesHeatmap = async (
+  from: Date,
+  to: Date,
+  categoryIds?: string[],
+  cashierId?: string,
+): Promise<HeatmapCell[]> => {
+  try {
+    return await invoke<HeatmapCell[]>("get_sales_heatmap", {
+      fromDate: formatDate(from),
+      toDate: formatDate(to),
+      categoryIds: categoryIds?.length ? categoryIds : null,
+      cashierId: cashierId || null,
+    });
+  } catch (error) {
+    throw new Error(`Error fetching sales heatmap: ${error}`);
+  }
+};
+
+export const getCashierPerformance = async (
+  userId: string,
+  from: Date,
+  to: Date,
+  cashierId?: string,
+): Promise<CashierPerformance[]> => {
+  try {
+    return await invoke<CashierPerformance[]>("get_cashier_performance", {
+      userId,
+      fromDate: formatDate(from),
+      toDate: formatDate(to),
+      cashierId: cashierId || null,
+    });
+  } catch (error) {
+    throw new Error(`Error fetching cashier performance: ${error}`);
+  }
+};
+
+export const getDiscountAnalysis = async (
+  userId: string,
+  from: Date,
+  to: Date,
+  period: DiscountPeriod = 'day',
+  cashierId?: string,
+): Promise<DiscountAnalysis> => {
+  try {
+    return await invoke<DiscountAnalysis>("get_discount_analysis", {
+      userId,
+      fromDate: formatDate(from),
+      toDate: formatDate(to),
+      period,
+      cashierId: cashierId || null,
+    });
+  } catch (error) {
+    throw new Error(`Error fetching discount analysis: ${error}`);
+  }
+};
+
+export const getReorderSuggestions = async (params?: ReorderParams): Promise<ReorderSuggestion[]> => {
+  try {
+    return await invoke<ReorderSuggestion[]>("get_reorder_suggestions", {
+      params: params ?? null,
+    });
+  } catch (error) {
+    throw new Error(`Error fetching reorder suggestions: ${error}`);
+  }
+};
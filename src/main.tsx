@@ -3,13 +3,22 @@ import { QueryClient, QueryClientProvider } from "@tanstack/react-query";
 import App from "./App";
 import "./App.css";
 import { ZoomProvider } from "@/providers/ZoomProvider";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
+import { CustomerDisplayScreen } from "@/features/sales/components/CustomerDisplayScreen";
 
 const queryClient = new QueryClient();
 
+// La ventana del cliente solo muestra la venta en curso: sin login ni rutas
+const isCustomerDisplay = getCurrentWebviewWindow().label === "customer-display";
+
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
-  <QueryClientProvider client={queryClient}>
-    <ZoomProvider>
-      <App />
-    </ZoomProvider>
-  </QueryClientProvider>,
+  isCustomerDisplay ? (
+    <CustomerDisplayScreen />
+  ) : (
+    <QueryClientProvider client={queryClient}>
+      <ZoomProvider>
+        <App />
+      </ZoomProvider>
+    </QueryClientProvider>
+  ),
 );
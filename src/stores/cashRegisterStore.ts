@@ -53,6 +53,8 @@ export const useCashRegisterStore = create<CashRegisterState>()(
             shiftId: shift.id,
             notes: request.notes || null,
             userId,
+            currencyCounts: request.currency_counts ?? null,
+            countedCash: request.counted_cash ?? null,
           });
           set({ shift: updatedShift, isLoading: false });
           return updatedShift;
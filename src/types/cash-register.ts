@@ -39,12 +39,40 @@ export interface ShiftDetailsDto {
   total_card_sales: number;
   total_credit_sales: number;
   total_voucher_sales: number;
+  total_gift_card_sales: number;
+  total_points_sales: number;
+  total_exchange_sales: number;
   total_debt_payments: number;
   debt_payments_cash: number;
   debt_payments_card: number;
+  total_cash_refunds: number;
+  total_card_refunds: number;
+  total_reversals: number;
+  cash_reversals: number;
+  card_reversals: number;
+  foreign_cash: ForeignCashTotal[];
+  currency_counts: ShiftCurrencyCount[];
   total_cash: number;
 }
 
+export interface ForeignCashTotal {
+  currency_code: string;
+  amount: number;
+  base_amount: number;
+}
+
+export interface ShiftCurrencyCount {
+  currency_code: string;
+  expected_amount: number;
+  counted_amount: number | null;
+  difference: number | null;
+}
+
+export interface CurrencyCountInput {
+  currency_code: string;
+  counted_amount: number;
+}
+
 export interface CreateCashMovementRequest {
   shift_id: number;
   type_: 'IN' | 'OUT';
@@ -55,6 +83,8 @@ export interface CreateCashMovementRequest {
 
 export interface CloseShiftRequest {
   notes?: string;
+  currency_counts?: CurrencyCountInput[];
+  counted_cash?: number; // efectivo contado en moneda base
 }
 
 export interface ShiftHistoryFilters {
@@ -70,4 +100,55 @@ export interface PaginatedShifts {
   page: number;
   page_size: number;
   total_pages: number;
-}
\ No newline at end of file
+}
+export interface DailyPaymentTotal {
+  method: string;
+  label: string;
+  sales_count: number;
+  amount: number;
+}
+
+export interface DailyReport {
+  report_type: 'X' | 'Z';
+  report_number: number | null;
+  z_report_id: string | null;
+  business_date: string;
+  generated_at: string;
+  generated_by: string;
+  shift_codes: string[];
+  open_shifts: number;
+  sales_count: number;
+  gross_sales: number;
+  total_discounts: number;
+  total_sales: number;
+  tax_rate: number;
+  tax_base: number;
+  tax_amount: number;
+  payments: DailyPaymentTotal[];
+  foreign_cash: ForeignCashTotal[];
+  cancellations_count: number;
+  cancellations_total: number;
+  reversals_count: number;
+  reversals_total: number;
+  returns_count: number;
+  returns_total: number;
+  cash_refunds: number;
+  card_refunds: number;
+  debt_payments_total: number;
+  movements_in: number;
+  movements_out: number;
+  first_folio: string | null;
+  last_folio: string | null;
+}
+
+export interface ZReportSummary {
+  id: string;
+  report_number: number;
+  business_date: string;
+  first_folio: string | null;
+  last_folio: string | null;
+  total_sales: number;
+  print_count: number;
+  user_name: string | null;
+  created_at: string;
+}
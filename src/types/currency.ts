@@ -0,0 +1,15 @@
+export interface Currency {
+  code: string;
+  name: string;
+  symbol: string;
+  is_active: boolean;
+  rate: number | null;
+  rate_date: string | null;
+}
+
+export interface CurrencyInput {
+  code: string;
+  name: string;
+  symbol: string;
+  is_active: boolean;
+}
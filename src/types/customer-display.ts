@@ -0,0 +1,21 @@
+export type CustomerDisplayEvent =
+  | { kind: 'idle' }
+  | { kind: 'line-added'; description: string; quantity: number; unitPrice: number; subtotal: number }
+  | { kind: 'total'; total: number }
+  | { kind: 'change'; total: number; paid: number; change: number };
+
+export interface CustomerDisplayConfig {
+  driver: 'none' | 'serial' | 'window' | 'both';
+  serialPort?: string | null;
+  baudRate: number;
+  commandSet: 'epson' | 'cd5220' | 'aedex';
+  columns: number;
+  windowMonitor?: number | null; // índice del monitor; null abre una ventana normal
+}
+
+// Payload del evento 'customer-display' en la ventana del cliente
+export interface CustomerDisplayUpdate {
+  event: CustomerDisplayEvent;
+  rows: [string, string];
+  currencySymbol: string;
+}
@@ -0,0 +1,14 @@
+export type ExportFormat = 'csv' | 'xlsx';
+
+export type ExportLocale = 'es-MX' | 'en-US' | 'es-ES';
+
+export interface ExportOptions {
+  path: string;
+  format: ExportFormat;
+  locale?: ExportLocale;
+}
+
+export interface ExportResult {
+  path: string;
+  rows: number;
+}
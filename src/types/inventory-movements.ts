@@ -15,6 +15,7 @@ export interface InventoryMovement {
 
 export interface MovementsFilter {
   search?: string;
+  product_id?: string; // kardex de un solo producto
   movement_type?: 'IN' | 'OUT' | null;
   start_date?: string; // YYYY-MM-DD
   end_date?: string;   // YYYY-MM-DD
@@ -33,6 +33,13 @@ export interface CreateProductPayload {
   user_id?: string;
   is_active: boolean;
   tags: string[];
+  generate_barcode?: boolean; // defaults to the business setting
+}
+
+export interface BarcodeBackfillResult {
+  assigned: number;
+  firstCode: string | null;
+  lastCode: string | null;
 }
 
 // TODO: Hacer genérica esta interfaz para paginación, algo como QueryResult<T>
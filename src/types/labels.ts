@@ -0,0 +1,36 @@
+export type LabelLanguage = 'zpl' | 'epl' | 'escpos';
+export type LabelBarcodeType = 'code128' | 'ean13';
+
+export interface LabelConfig {
+  language: LabelLanguage;
+  widthMm: number;
+  heightMm: number;
+  dpi: number; // 203 or 300
+  showName: boolean;
+  showPrice: boolean;
+  showWholesalePrice: boolean;
+  showBarcode: boolean;
+  barcodeType: LabelBarcodeType;
+  copies: number;
+  // Label printer; null uses the receipt printer
+  printerTransport: 'spooler' | 'tcp' | 'serial' | null;
+  printerName: string | null;
+  printerAddress: string | null;
+}
+
+export interface LabelPrintResult {
+  products: number;
+  labels: number;
+  printer: string;
+}
+
+export interface PriceChangedProduct {
+  id: string;
+  code: string;
+  name: string;
+  retailPrice: number;
+  wholesalePrice: number;
+  printedRetailPrice: number;
+  printedWholesalePrice: number;
+  printedAt: string;
+}
@@ -0,0 +1,32 @@
+export type PrintJobStatus = 'pending' | 'printing' | 'printed' | 'failed' | 'discarded';
+
+export type PrintDocumentType =
+  | 'sale'
+  | 'voucher'
+  | 'exchange'
+  | 'payment'
+  | 'shift'
+  | 'daily_report';
+
+export interface PrintJob {
+  id: number;
+  document_type: PrintDocumentType;
+  reference: string | null;
+  status: PrintJobStatus;
+  attempts: number;
+  last_error: string | null;
+  next_attempt_at: string | null;
+  created_at: string;
+  printed_at: string | null;
+  size_bytes: number;
+}
+
+/** Payload of the `print-job-printed` and `print-job-failed` events. */
+export interface PrintJobEvent {
+  id: number;
+  document_type: PrintDocumentType;
+  reference: string | null;
+  attempts: number;
+  error: string | null;
+  will_retry: boolean;
+}
@@ -0,0 +1,9 @@
+export type ReceiptDocumentType = 'sale' | 'voucher' | 'payment' | 'shift';
+
+export interface ReceiptTemplate {
+  document_type: ReceiptDocumentType;
+  content: string;
+  is_active: boolean;
+  is_custom: boolean; // false: plantilla inicial, aún no guardada
+  updated_at: string | null;
+}
@@ -0,0 +1,31 @@
+export interface ReportDeliveryConfig {
+  enabled: boolean;
+  sendTime: string;
+  channel: 'smtp' | 'webhook';
+  recipients: string[];
+  smtpHost: string;
+  smtpPort: number;
+  smtpSecurity: 'none' | 'starttls' | 'tls';
+  smtpUsername?: string | null;
+  smtpPassword?: string | null; // nunca llega del backend; null conserva la guardada, '' la borra
+  smtpFrom: string;
+  webhookUrl: string;
+  webhookToken?: string | null; // igual que smtpPassword
+  hasSmtpPassword?: boolean;
+  hasWebhookToken?: boolean;
+}
+
+export interface ReportDelivery {
+  id: number;
+  report_date: string;
+  source: 'scheduled' | 'manual';
+  channel: 'smtp' | 'webhook';
+  destination: string;
+  subject: string;
+  status: 'pending' | 'sent' | 'failed';
+  attempts: number;
+  last_error: string | null;
+  next_attempt_at: string | null;
+  sent_at: string | null;
+  created_at: string;
+}
@@ -1,3 +1,5 @@
+import type { SalePaymentMethod } from './sale';
+
 export interface ReportKpis {
   gross_sales: number;
   net_profit: number;
@@ -17,10 +19,55 @@ export interface CategoryDataPoint {
   color?: string;
 }
 
+export interface PaymentMethodDataPoint {
+  method: SalePaymentMethod;
+  total_amount: number;
+  sales_count: number;
+  percentage: number;
+}
+
 export interface SalesReport {
   kpis: ReportKpis;
   sales_chart: ChartDataPoint[];
   category_chart: CategoryDataPoint[];
+  payment_methods: PaymentMethodDataPoint[];
+  comparison: SalesComparison | null;
+}
+
+export type CompareMode = 'previous_period' | 'last_year' | 'custom';
+
+export interface KpiDelta {
+  current: number;
+  previous: number;
+  absolute: number;
+  percent: number | null; // null cuando el periodo anterior es 0
+}
+
+export interface KpiDeltas {
+  gross_sales: KpiDelta;
+  net_profit: KpiDelta;
+  transaction_count: KpiDelta;
+  average_ticket: KpiDelta;
+}
+
+export interface ProductMover {
+  product_id: string;
+  product_name: string;
+  product_code: string;
+  current_quantity: number;
+  previous_quantity: number;
+  quantity_delta: number;
+}
+
+export interface SalesComparison {
+  from_date: string;
+  to_date: string;
+  kpis: ReportKpis;
+  sales_chart: ChartDataPoint[];
+  category_chart: CategoryDataPoint[];
+  deltas: KpiDeltas;
+  top_gainers: ProductMover[];
+  top_losers: ProductMover[];
 }
 
 export interface DateRange {
@@ -70,3 +117,78 @@ export interface LowStockProduct {
   purchase_price: number;
   retail_price: number;
 }
+
+export interface HeatmapCell {
+  weekday: number; // 0 = domingo ... 6 = sábado
+  hour: number;
+  sales_count: number;
+  revenue: number;
+  average_ticket: number;
+}
+
+export interface CashierPerformance {
+  user_id: string;
+  user_name: string;
+  user_avatar: string | null;
+  sales_count: number;
+  revenue: number;
+  average_ticket: number;
+  items_per_ticket: number;
+  discount_count: number;
+  discount_total: number;
+  cancellations_count: number;
+  cancellations_total: number;
+  returns_count: number;
+  returns_total: number;
+  shifts_closed: number;
+  discrepancy_count: number;
+  discrepancy_total: number; // sobrante (+) / faltante (-)
+  discrepancy_absolute: number;
+  voids_count: number;
+  voids_total: number;
+}
+
+export interface DiscountBreakdownRow {
+  key: string;
+  label: string;
+  manual: number;
+  promotion: number;
+  kit: number;
+  wholesale: number;
+  total_discount: number;
+  net_revenue: number;
+  discount_rate: number; // % sobre el valor a precio de lista
+  sales_count: number;
+}
+
+export interface DiscountAnalysis {
+  totals: DiscountBreakdownRow;
+  by_user: DiscountBreakdownRow[];
+  by_product: DiscountBreakdownRow[];
+  by_period: DiscountBreakdownRow[];
+}
+
+export type DiscountPeriod = 'day' | 'week' | 'month';
+
+export interface ReorderParams {
+  window_days?: number;       // ventana de ventas (default 30)
+  target_cover_days?: number; // cobertura deseada (default 14)
+  lead_time_days?: number;    // tiempo de entrega del proveedor (default 3)
+  category_ids?: string[];
+  include_all?: boolean;
+}
+
+export interface ReorderSuggestion {
+  product_id: string;
+  product_name: string;
+  product_code: string;
+  category_name: string;
+  current_stock: number;
+  minimum_stock: number;
+  units_sold: number;
+  daily_average: number;
+  days_of_cover: number | null;
+  suggested_order: number;
+  purchase_price: number;
+  estimated_cost: number;
+}
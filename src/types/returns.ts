@@ -1,3 +1,5 @@
+import type { SaleRequest } from './sale';
+
 export interface ReturnItem {
   saleItemId: string;
   productId: string;
@@ -30,10 +32,69 @@ export interface ProcessReturnRequest {
   notes: string;
   user_id: string;
   items: ReturnItemRequest[];
+  refund_method?: RefundMethod;
 }
 
+export type RefundMethod = 'store_voucher' | 'cash' | 'card' | 'credit';
+
 export interface ReturnResponse {
   return_id: string;
   voucher_code: string;
   total: number;
+  refund_method: RefundMethod;
+}
+
+export interface ProcessExchangeRequest {
+  return_request: ProcessReturnRequest;
+  sale_request: SaleRequest;
+}
+
+export interface ExchangeResponse {
+  return_id: string;
+  sale_id: string;
+  folio: string;
+  return_total: number;
+  sale_total: number;
+  amount_due: number;
+  refund_total: number;
+  refund_method: RefundMethod;
+  voucher_code: string;
+  change: number;
+}
+
+export interface ReturnCandidateFilters {
+  product_id?: string;
+  customer_id?: string;
+  date_from?: string;
+  date_to?: string;
+}
+
+export interface ReturnCandidate {
+  sale_id: string;
+  folio: string;
+  sale_date: string;
+  customer_name: string | null;
+  sale_item_id: string;
+  product_id: string;
+  product_name: string;
+  quantity: number;
+  returned_quantity: number;
+  available_quantity: number;
+  unit_price: number;
+}
+
+export interface NoReceiptReturnRequest {
+  customer_id: string;
+  reason: string;
+  notes: string;
+  user_id: string;
+  refund_method: Extract<RefundMethod, 'store_voucher' | 'cash'>;
+  items: { product_id: string; quantity: number }[];
+}
+
+export interface NoReceiptLimits {
+  max_returns_per_day: number;
+  max_amount_per_day: number;
+  returns_today: number;
+  amount_today: number;
 }
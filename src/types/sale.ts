@@ -6,6 +6,49 @@ export interface SaleItemRequest {
   kit_option_id?: string;
 }
 
+export type SalePaymentMethod =
+  | 'cash'
+  | 'debit_card'
+  | 'credit_card'
+  | 'transfer'
+  | 'voucher'
+  | 'gift_card'
+  | 'credit'
+  | 'points';
+
+export interface SalePaymentRequest {
+  method: SalePaymentMethod;
+  amount: number;
+  reference?: string;
+  voucher_code?: string;
+  currency_code?: string;
+  terminal_transaction_id?: string;
+}
+
+export interface SalePayment {
+  id: string;
+  method: SalePaymentMethod;
+  amount: number;
+  tendered: number;
+  reference: string | null;
+  currency_code: string | null;
+  foreign_amount: number | null;
+  exchange_rate: number | null;
+  auth_code: string | null;
+  card_last4: string | null;
+  card_brand: string | null;
+}
+
+export interface TerminalChargeResponse {
+  transaction_id: string;
+  status: 'approved' | 'declined' | 'timeout';
+  approved: boolean;
+  auth_code: string | null;
+  card_last4: string | null;
+  card_brand: string | null;
+  message: string | null;
+}
+
 export interface SaleRequest {
   discount_percentage: number;
   customer_id: string | null;
@@ -18,6 +61,7 @@ export interface SaleRequest {
   items: SaleItemRequest[];
   should_print: boolean;
   voucher_code?: string;
+  payments?: SalePaymentRequest[];
 }
 
 export interface SaleResponse {
@@ -26,6 +70,7 @@ export interface SaleResponse {
   total: number;
   change: number;
   voucher_used: number;
+  printer_warning: string | null;
 }
 
 export interface VoucherValidationResponse {
@@ -1,3 +1,5 @@
+import type { SalePayment } from './sale';
+
 export interface SalesHistoryFilter {
   page: number;
   page_size: number;
@@ -49,6 +51,7 @@ export interface SaleDetail extends SaleMaster {
   returns: ReturnSummary[];
   voucher?: VoucherInfo;
   customer_name?: string;
+  payments: SalePayment[];
 }
 
 export interface ReturnSummary {